@@ -0,0 +1,15 @@
+//! Run with `cargo +nightly fuzz run decode_tick` from the repository root.
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+
+// The decoder is self contained, so it's compiled straight from the app's source
+#[path = "../../src/smartstream/tick.rs"]
+#[allow(dead_code)]
+mod tick;
+
+fuzz_target!(|data: &[u8]| {
+    if let Ok(tick) = tick::Tick::decode(data) {
+        assert!(tick.token.len() <= 25);
+    }
+});
@@ -0,0 +1,158 @@
+//! Deterministic synthetic candles for `getCandleData`.
+//!
+//! Prices are a pure function of the token and time, so overlapping or
+//! split requests always agree with each other.
+
+use crate::clock::{weekday, IstDateTime, IST_OFFSET_SECS, SECS_PER_DAY};
+use crate::scenario::MockInstrument;
+use serde_json::{json, Value};
+
+// Regular NSE session, minutes after midnight IST
+const SESSION_OPEN_MINUTE: i64 = 9 * 60 + 15;
+const SESSION_CLOSE_MINUTE: i64 = 15 * 60 + 30;
+
+/// Bar length in minutes and the longest range Angel serves per request, in days.
+pub fn interval(name: &str) -> Option<(i64, i64)> {
+    match name {
+        "ONE_MINUTE" => Some((1, 30)),
+        "THREE_MINUTE" => Some((3, 60)),
+        "FIVE_MINUTE" => Some((5, 100)),
+        "TEN_MINUTE" => Some((10, 100)),
+        "FIFTEEN_MINUTE" => Some((15, 200)),
+        "THIRTY_MINUTE" => Some((30, 200)),
+        "ONE_HOUR" => Some((60, 400)),
+        "ONE_DAY" => Some((24 * 60, 2000)),
+        _ => None,
+    }
+}
+
+/// Candles between two unix times, as `[timestamp, open, high, low, close, volume]` rows.
+pub fn generate(
+    instrument: Option<&MockInstrument>,
+    token: &str,
+    interval_name: &str,
+    from: i64,
+    to: i64,
+) -> Result<Vec<Value>, String> {
+    let (minutes, max_days) =
+        interval(interval_name).ok_or_else(|| format!("Invalid interval {}", interval_name))?;
+    if to < from {
+        return Err("todate is before fromdate".to_string());
+    }
+    if to - from > max_days * SECS_PER_DAY {
+        return Err(format!(
+            "Date range exceeds the {} day limit for {}",
+            max_days, interval_name
+        ));
+    }
+
+    let base = instrument
+        .map(|instrument| instrument.close)
+        .unwrap_or(100.0);
+    let seed = token
+        .bytes()
+        .fold(0u64, |seed, b| seed.wrapping_mul(31).wrapping_add(b as u64));
+    let first_day = (from + IST_OFFSET_SECS).div_euclid(SECS_PER_DAY);
+    let last_day = (to + IST_OFFSET_SECS).div_euclid(SECS_PER_DAY);
+
+    let mut candles = Vec::new();
+    for day in first_day..=last_day {
+        if matches!(weekday(day), 0 | 6) {
+            continue;
+        }
+        let midnight = day * SECS_PER_DAY - IST_OFFSET_SECS;
+        if minutes == 24 * 60 {
+            // Daily bars are stamped at midnight and span the session
+            if midnight + SESSION_OPEN_MINUTE * 60 >= from && midnight <= to {
+                let open = midnight + SESSION_OPEN_MINUTE * 60;
+                candles.push(candle(
+                    seed,
+                    base,
+                    midnight,
+                    open,
+                    midnight + SESSION_CLOSE_MINUTE * 60,
+                ));
+            }
+            continue;
+        }
+        let mut minute = SESSION_OPEN_MINUTE;
+        while minute < SESSION_CLOSE_MINUTE {
+            let start = midnight + minute * 60;
+            if start >= from && start <= to {
+                let end = midnight + (minute + minutes).min(SESSION_CLOSE_MINUTE) * 60;
+                candles.push(candle(seed, base, start, start, end));
+            }
+            minute += minutes;
+        }
+    }
+    Ok(candles)
+}
+
+fn candle(seed: u64, base: f64, stamp: i64, start: i64, end: i64) -> Value {
+    let open = price(seed, base, start);
+    let close = price(seed, base, end);
+    let spread = base * 0.002 * noise(seed ^ 0x5eed, start);
+    let high = tick(open.max(close) + spread);
+    let low = tick(open.min(close) - spread * noise(seed ^ 0x10, start));
+    let volume = ((end - start) / 60) as f64 * (200.0 + 2000.0 * noise(seed ^ 0x7a, start));
+    json!([
+        IstDateTime::from_unix(stamp).rfc3339(),
+        open,
+        high,
+        low,
+        close,
+        volume.round() as i64
+    ])
+}
+
+// Slow and fast waves around the close plus a little noise
+fn price(seed: u64, base: f64, time: i64) -> f64 {
+    let t = time as f64;
+    let phase = (seed % 1000) as f64;
+    let drift =
+        0.04 * ((t / (3.0 * 86_400.0)) + phase).sin() + 0.01 * ((t / 7_200.0) + phase).sin();
+    tick(base * (1.0 + drift + 0.002 * (noise(seed, time / 60) - 0.5)))
+}
+
+// Round to the usual 5 paise tick
+fn tick(price: f64) -> f64 {
+    (price * 20.0).round() / 20.0
+}
+
+// splitmix64, mapped to [0, 1)
+fn noise(seed: u64, value: i64) -> f64 {
+    let mut z = seed.wrapping_add((value as u64).wrapping_mul(0x9e37_79b9_7f4a_7c15));
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    z ^= z >> 31;
+    (z >> 11) as f64 / (1u64 << 53) as f64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::clock::parse_ist;
+
+    #[test]
+    fn splits_agree_with_a_single_request() {
+        let from = parse_ist("2024-10-14 09:15").unwrap();
+        let middle = parse_ist("2024-10-15 12:00").unwrap();
+        let to = parse_ist("2024-10-16 15:30").unwrap();
+        let whole = generate(None, "3045", "FIVE_MINUTE", from, to).unwrap();
+        let mut parts = generate(None, "3045", "FIVE_MINUTE", from, middle).unwrap();
+        parts.extend(generate(None, "3045", "FIVE_MINUTE", middle + 60, to).unwrap());
+        assert_eq!(whole.len(), 3 * 75);
+        assert_eq!(whole, parts);
+        assert_eq!(whole[0][0], "2024-10-14T09:15:00+05:30");
+    }
+
+    #[test]
+    fn enforces_interval_limits() {
+        let from = parse_ist("2024-01-01 00:00").unwrap();
+        let to = parse_ist("2024-03-01 00:00").unwrap();
+        assert!(generate(None, "3045", "ONE_MINUTE", from, to).is_err());
+        let days = generate(None, "3045", "ONE_DAY", from, to).unwrap();
+        // Weekdays in January and February 2024, plus 1 March
+        assert_eq!(days.len(), 23 + 21 + 1);
+    }
+}
@@ -0,0 +1,126 @@
+//! Just enough calendar maths for IST timestamps, without a date crate.
+
+use std::time::{SystemTime, UNIX_EPOCH};
+
+pub const IST_OFFSET_SECS: i64 = 5 * 60 * 60 + 30 * 60;
+pub const SECS_PER_DAY: i64 = 24 * 60 * 60;
+const MONTHS: [&str; 12] = [
+    "Jan", "Feb", "Mar", "Apr", "May", "Jun", "Jul", "Aug", "Sep", "Oct", "Nov", "Dec",
+];
+
+pub fn now_secs() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs() as i64)
+        .unwrap_or_default()
+}
+
+/// Calendar fields of a unix time, in IST.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct IstDateTime {
+    pub year: i64,
+    pub month: u32,
+    pub day: u32,
+    pub hour: u32,
+    pub minute: u32,
+    pub second: u32,
+}
+
+impl IstDateTime {
+    pub fn from_unix(secs: i64) -> Self {
+        let local = secs + IST_OFFSET_SECS;
+        let (year, month, day) = civil_from_days(local.div_euclid(SECS_PER_DAY));
+        let time = local.rem_euclid(SECS_PER_DAY);
+        Self {
+            year,
+            month,
+            day,
+            hour: (time / 3600) as u32,
+            minute: (time / 60 % 60) as u32,
+            second: (time % 60) as u32,
+        }
+    }
+
+    /// Candle timestamps, e.g. `2024-10-16T09:15:00+05:30`.
+    pub fn rfc3339(&self) -> String {
+        format!(
+            "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}+05:30",
+            self.year, self.month, self.day, self.hour, self.minute, self.second
+        )
+    }
+
+    /// Order book timestamps, e.g. `16-Oct-2024 09:15:00`.
+    pub fn angel(&self) -> String {
+        format!(
+            "{:02}-{}-{:04} {:02}:{:02}:{:02}",
+            self.day,
+            MONTHS[self.month as usize - 1],
+            self.year,
+            self.hour,
+            self.minute,
+            self.second
+        )
+    }
+}
+
+/// Unix time of an IST `YYYY-MM-DD HH:MM`, the format `getCandleData` takes.
+pub fn parse_ist(text: &str) -> Option<i64> {
+    let (date, time) = text.trim().split_once(' ')?;
+    let mut date = date.split('-').map(str::parse::<i64>);
+    let (year, month, day) = (date.next()?.ok()?, date.next()?.ok()?, date.next()?.ok()?);
+    let (hour, minute) = time.split_once(':')?;
+    let (hour, minute) = (hour.parse::<i64>().ok()?, minute.parse::<i64>().ok()?);
+    if !(1..=12).contains(&month) || !(1..=31).contains(&day) || hour > 23 || minute > 59 {
+        return None;
+    }
+    let days = days_from_civil(year, month as u32, day as u32);
+    Some(days * SECS_PER_DAY + hour * 3600 + minute * 60 - IST_OFFSET_SECS)
+}
+
+/// Day of the week of an IST day number, 0 is Sunday.
+pub fn weekday(days: i64) -> i64 {
+    // The epoch was a Thursday
+    (days + 4).rem_euclid(7)
+}
+
+// Howard Hinnant's civil calendar algorithms
+pub fn days_from_civil(year: i64, month: u32, day: u32) -> i64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let year_of_era = year - era * 400;
+    let month = month as i64;
+    let day_of_year =
+        (153 * (if month > 2 { month - 3 } else { month + 9 }) + 2) / 5 + day as i64 - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    era * 146_097 + day_of_era - 719_468
+}
+
+pub fn civil_from_days(days: i64) -> (i64, u32, u32) {
+    let days = days + 719_468;
+    let era = days.div_euclid(146_097);
+    let day_of_era = days - era * 146_097;
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let mp = (5 * day_of_year + 2) / 153;
+    let day = (day_of_year - (153 * mp + 2) / 5 + 1) as u32;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 } as u32;
+    let year = year_of_era + era * 400 + if month <= 2 { 1 } else { 0 };
+    (year, month, day)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn converts_between_unix_and_ist() {
+        let secs = parse_ist("2024-10-16 09:15").unwrap();
+        assert_eq!(secs, 1_729_050_300);
+        let time = IstDateTime::from_unix(secs);
+        assert_eq!(time.rfc3339(), "2024-10-16T09:15:00+05:30");
+        assert_eq!(time.angel(), "16-Oct-2024 09:15:00");
+        assert_eq!(weekday((secs + IST_OFFSET_SECS) / SECS_PER_DAY), 3);
+        assert_eq!(parse_ist("2024-13-01 09:15"), None);
+    }
+}
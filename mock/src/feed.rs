@@ -0,0 +1,295 @@
+//! Fake SmartStream 2.0 WebSocket playing back the scenario's prices.
+
+use crate::clock::now_secs;
+use crate::scenario::MockInstrument;
+use crate::state::{day_range, price_at, State};
+use axum::extract::ws::{Message, WebSocket, WebSocketUpgrade};
+use axum::extract::State as Extract;
+use axum::http::{HeaderMap, StatusCode};
+use axum::response::{IntoResponse, Response};
+use serde_json::Value;
+use std::collections::BTreeSet;
+use std::sync::Arc;
+use tokio::sync::broadcast::error::RecvError;
+
+const MODE_LTP: u8 = 1;
+const MODE_QUOTE: u8 = 2;
+const MODE_SNAP_QUOTE: u8 = 3;
+
+pub async fn stream(
+    Extract(state): Extract<Arc<State>>,
+    headers: HeaderMap,
+    upgrade: WebSocketUpgrade,
+) -> Response {
+    let header = |name: &str| {
+        headers
+            .get(name)
+            .and_then(|value| value.to_str().ok())
+            .unwrap_or_default()
+    };
+    let authorized = {
+        let account = state.account();
+        let session = account.session_by_feed_token(header("x-feed-token"));
+        !header("x-api-key").is_empty()
+            && header("x-client-code") == state.scenario.client_code
+            && session.is_some_and(|session| {
+                session.jwt_token == header("Authorization").trim_start_matches("Bearer ")
+            })
+    };
+    if !authorized {
+        return (StatusCode::UNAUTHORIZED, "Invalid feed token").into_response();
+    }
+    upgrade.on_upgrade(move |socket| connection(state, socket))
+}
+
+// Subscribed (mode, exchange type, token) triples
+type Subscriptions = BTreeSet<(u8, u8, String)>;
+
+async fn connection(state: Arc<State>, mut socket: WebSocket) {
+    let mut ticks = state.ticks.subscribe();
+    let mut subscriptions = Subscriptions::new();
+    let mut sequence = 0;
+    let mut sent_ticks = 0;
+
+    loop {
+        tokio::select! {
+            message = socket.recv() => match message {
+                Some(Ok(Message::Text(text))) if text == "ping" => {
+                    if socket.send(Message::Text("pong".to_string())).await.is_err() {
+                        return;
+                    }
+                }
+                Some(Ok(Message::Text(text))) => {
+                    let added = match apply(&mut subscriptions, &text) {
+                        Ok(added) => added,
+                        Err(message) => {
+                            let _ = socket.send(Message::Text(message)).await;
+                            continue;
+                        }
+                    };
+                    // New subscribers get the current price straight away
+                    let step = state.account().step;
+                    for packet in packets(&state, &added, step, &mut sequence) {
+                        if socket.send(Message::Binary(packet)).await.is_err() {
+                            return;
+                        }
+                    }
+                }
+                Some(Ok(Message::Close(_))) | Some(Err(_)) | None => return,
+                Some(Ok(_)) => {}
+            },
+            step = ticks.recv() => {
+                let step = match step {
+                    Ok(step) => step,
+                    Err(RecvError::Lagged(_)) => continue,
+                    Err(RecvError::Closed) => return,
+                };
+                for packet in packets(&state, &subscriptions, step, &mut sequence) {
+                    if socket.send(Message::Binary(packet)).await.is_err() {
+                        return;
+                    }
+                }
+                sent_ticks += 1;
+                if state.scenario.disconnect_after_ticks.is_some_and(|limit| sent_ticks >= limit) {
+                    tracing::info!("Dropping feed connection after {} ticks", sent_ticks);
+                    let _ = socket.send(Message::Close(None)).await;
+                    return;
+                }
+            }
+        }
+    }
+}
+
+/// Apply a subscribe or unsubscribe request, returning what was newly subscribed.
+fn apply(subscriptions: &mut Subscriptions, text: &str) -> Result<Subscriptions, String> {
+    let request: Value =
+        serde_json::from_str(text).map_err(|_| "Invalid Request Payload".to_string())?;
+    let action = request["action"].as_u64();
+    let mode = request["params"]["mode"]
+        .as_u64()
+        .filter(|mode| (1..=4).contains(mode))
+        .ok_or("Invalid Subscription Mode")? as u8;
+
+    let mut added = Subscriptions::new();
+    for list in request["params"]["tokenList"]
+        .as_array()
+        .into_iter()
+        .flatten()
+    {
+        let exchange_type = list["exchangeType"]
+            .as_u64()
+            .ok_or("Invalid Exchange Type")? as u8;
+        for token in list["tokens"]
+            .as_array()
+            .into_iter()
+            .flatten()
+            .filter_map(Value::as_str)
+        {
+            let key = (mode, exchange_type, token.to_string());
+            match action {
+                Some(1) => {
+                    if subscriptions.insert(key.clone()) {
+                        added.insert(key);
+                    }
+                }
+                Some(0) => {
+                    subscriptions.remove(&key);
+                }
+                _ => return Err("Invalid Action".to_string()),
+            }
+        }
+    }
+    Ok(added)
+}
+
+fn packets(
+    state: &State,
+    subscriptions: &Subscriptions,
+    step: u64,
+    sequence: &mut i64,
+) -> Vec<Vec<u8>> {
+    subscriptions
+        .iter()
+        .filter_map(|(mode, exchange_type, token)| {
+            let (_, instrument) = state
+                .scenario
+                .instrument(exchange_name(*exchange_type)?, token)?;
+            *sequence += 1;
+            encode(*mode, *exchange_type, instrument, step, *sequence)
+        })
+        .collect()
+}
+
+fn exchange_name(exchange_type: u8) -> Option<&'static str> {
+    match exchange_type {
+        1 => Some("NSE"),
+        2 => Some("NFO"),
+        3 => Some("BSE"),
+        4 => Some("BFO"),
+        5 => Some("MCX"),
+        7 => Some("NCDEX"),
+        13 => Some("CDS"),
+        _ => None,
+    }
+}
+
+/// Packet in SmartStream's little endian layout, `None` for 20 level depth.
+pub fn encode(
+    mode: u8,
+    exchange_type: u8,
+    instrument: &MockInstrument,
+    step: u64,
+    sequence: i64,
+) -> Option<Vec<u8>> {
+    if !(MODE_LTP..=MODE_SNAP_QUOTE).contains(&mode) {
+        return None;
+    }
+    // Currency prices are in 1e-7 rupees, everything else in paise
+    let scale = if exchange_type == 13 {
+        10_000_000.0
+    } else {
+        100.0
+    };
+    let raw = |price: f64| ((price * scale).round() as i64).to_le_bytes();
+    let ltp = price_at(instrument, step);
+
+    let mut packet = Vec::with_capacity(379);
+    packet.push(mode);
+    packet.push(exchange_type);
+    let mut token = [0u8; 25];
+    let len = instrument.token.len().min(25);
+    token[..len].copy_from_slice(&instrument.token.as_bytes()[..len]);
+    packet.extend_from_slice(&token);
+    packet.extend_from_slice(&sequence.to_le_bytes());
+    packet.extend_from_slice(&(now_secs() * 1000).to_le_bytes());
+    packet.extend_from_slice(&raw(ltp));
+    if mode == MODE_LTP {
+        return Some(packet);
+    }
+
+    let (open, high, low) = day_range(instrument, step);
+    let volume = 50_000 + step as i64 * 1_000;
+    packet.extend_from_slice(&10i64.to_le_bytes());
+    packet.extend_from_slice(&raw((open + high + low + ltp) / 4.0));
+    packet.extend_from_slice(&volume.to_le_bytes());
+    packet.extend_from_slice(&(volume as f64 * 0.6).to_le_bytes());
+    packet.extend_from_slice(&(volume as f64 * 0.4).to_le_bytes());
+    for price in [open, high, low, instrument.close] {
+        packet.extend_from_slice(&raw(price));
+    }
+    if mode == MODE_QUOTE {
+        return Some(packet);
+    }
+
+    let open_interest = if instrument.exchange == "NFO" {
+        1_000_000 + step as i64 * 500
+    } else {
+        0
+    };
+    packet.extend_from_slice(&now_secs().to_le_bytes());
+    packet.extend_from_slice(&open_interest.to_le_bytes());
+    packet.extend_from_slice(&0f64.to_le_bytes());
+    // Five bids then five asks, a tick apart around the last price
+    for (flag, side) in [(1i16, -1.0), (0i16, 1.0)] {
+        for level in 1..=5 {
+            packet.extend_from_slice(&flag.to_le_bytes());
+            packet.extend_from_slice(&(level as i64 * 150).to_le_bytes());
+            packet.extend_from_slice(&raw(ltp + side * 0.05 * level as f64));
+            packet.extend_from_slice(&(level as i16 * 3).to_le_bytes());
+        }
+    }
+    let year_high = instrument
+        .prices
+        .iter()
+        .copied()
+        .fold(instrument.close, f64::max)
+        * 1.2;
+    let year_low = instrument
+        .prices
+        .iter()
+        .copied()
+        .fold(instrument.close, f64::min)
+        * 0.8;
+    for price in [
+        instrument.close * 1.1,
+        instrument.close * 0.9,
+        year_high,
+        year_low,
+    ] {
+        packet.extend_from_slice(&raw(price));
+    }
+    Some(packet)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::scenario::Scenario;
+
+    #[test]
+    fn encodes_packets_at_their_mode_length() {
+        let scenario = Scenario::default();
+        let instrument = &scenario.instruments[0];
+        for (mode, len) in [(MODE_LTP, 51), (MODE_QUOTE, 123), (MODE_SNAP_QUOTE, 379)] {
+            let packet = encode(mode, 1, instrument, 0, 1).unwrap();
+            assert_eq!(packet.len(), len);
+            assert_eq!(
+                i64::from_le_bytes(packet[43..51].try_into().unwrap()),
+                81235
+            );
+        }
+        assert_eq!(encode(4, 1, instrument, 0, 1), None);
+    }
+
+    #[test]
+    fn tracks_subscriptions() {
+        let mut subscriptions = Subscriptions::new();
+        let subscribe = r#"{"action":1,"params":{"mode":1,"tokenList":[{"exchangeType":1,"tokens":["3045","2885"]}]}}"#;
+        assert_eq!(apply(&mut subscriptions, subscribe).unwrap().len(), 2);
+        assert!(apply(&mut subscriptions, subscribe).unwrap().is_empty());
+        let unsubscribe = r#"{"action":0,"params":{"mode":1,"tokenList":[{"exchangeType":1,"tokens":["3045"]}]}}"#;
+        apply(&mut subscriptions, unsubscribe).unwrap();
+        assert_eq!(subscriptions.len(), 1);
+        assert!(apply(&mut subscriptions, r#"{"action":1,"params":{"mode":9}}"#).is_err());
+    }
+}
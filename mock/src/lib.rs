@@ -0,0 +1,158 @@
+//! Local stand-in for Angel One's SmartAPI, for offline development and tests.
+//!
+//! Serves the REST endpoints the app calls and a SmartStream WebSocket, all
+//! backed by an in-memory account playing back a [`Scenario`]. Point the app
+//! at it with `API_BASE_URL` and `STREAM_URL`.
+
+use axum::routing::{get, post};
+use axum::Router;
+use std::io;
+use std::net::SocketAddr;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::net::TcpListener;
+use tokio::task::JoinHandle;
+
+mod candles;
+mod clock;
+mod feed;
+mod rest;
+mod scenario;
+mod state;
+
+pub use scenario::{MockHolding, MockInstrument, Scenario, NAMES as SCENARIOS};
+use state::State;
+
+const AUTH: &str = "/rest/auth/angelbroking";
+const SECURE: &str = "/rest/secure/angelbroking";
+
+/// Routes for every mocked endpoint, sharing one account.
+fn router(state: Arc<State>) -> Router {
+    Router::new()
+        .route(
+            &format!("{}/user/v1/loginByPassword", AUTH),
+            post(rest::login),
+        )
+        .route(
+            &format!("{}/jwt/v1/generateTokens", AUTH),
+            post(rest::generate_tokens),
+        )
+        .route(&format!("{}/user/v1/logout", SECURE), post(rest::logout))
+        .route(
+            &format!("{}/user/v1/getProfile", SECURE),
+            get(rest::profile),
+        )
+        .route(&format!("{}/user/v1/getRMS", SECURE), get(rest::funds))
+        .route(
+            &format!("{}/portfolio/v1/getAllHolding", SECURE),
+            get(rest::holdings),
+        )
+        .route(
+            &format!("{}/order/v1/getPosition", SECURE),
+            get(rest::positions),
+        )
+        .route(
+            &format!("{}/order/v1/getOrderBook", SECURE),
+            get(rest::order_book),
+        )
+        .route(
+            &format!("{}/order/v1/getTradeBook", SECURE),
+            get(rest::trade_book),
+        )
+        .route(
+            &format!("{}/order/v1/details/:unique_order_id", SECURE),
+            get(rest::order_details),
+        )
+        .route(
+            &format!("{}/order/v1/placeOrder", SECURE),
+            post(rest::place_order),
+        )
+        .route(
+            &format!("{}/order/v1/modifyOrder", SECURE),
+            post(rest::modify_order),
+        )
+        .route(
+            &format!("{}/order/v1/cancelOrder", SECURE),
+            post(rest::cancel_order),
+        )
+        .route(&format!("{}/market/v1/quote", SECURE), post(rest::quote))
+        .route(&format!("{}/market/v1/quote/", SECURE), post(rest::quote))
+        .route(&format!("{}/margin/v1/batch", SECURE), post(rest::margin))
+        .route(
+            &format!("{}/historical/v1/getCandleData", SECURE),
+            post(rest::candle_data),
+        )
+        .route("/smart-stream", get(feed::stream))
+        .with_state(state)
+}
+
+/// A running mock server, stopped when dropped.
+pub struct MockServer {
+    address: SocketAddr,
+    tasks: Vec<JoinHandle<()>>,
+}
+
+impl MockServer {
+    /// Start on a free local port, for tests.
+    pub async fn start(scenario: Scenario) -> io::Result<Self> {
+        Self::bind(SocketAddr::from(([127, 0, 0, 1], 0)), scenario).await
+    }
+
+    pub async fn bind(address: SocketAddr, scenario: Scenario) -> io::Result<Self> {
+        let listener = TcpListener::bind(address).await?;
+        let address = listener.local_addr()?;
+        let tick_interval = Duration::from_millis(scenario.tick_interval_ms.max(1));
+        let state = Arc::new(State::new(scenario));
+
+        let ticker = tokio::spawn({
+            let state = state.clone();
+            async move {
+                let mut interval = tokio::time::interval(tick_interval);
+                interval.tick().await;
+                loop {
+                    interval.tick().await;
+                    state.advance();
+                }
+            }
+        });
+        let server = tokio::spawn(async move {
+            if let Err(e) = axum::serve(listener, router(state)).await {
+                tracing::error!("Mock server stopped: {}", e);
+            }
+        });
+
+        Ok(Self {
+            address,
+            tasks: vec![ticker, server],
+        })
+    }
+
+    pub fn address(&self) -> SocketAddr {
+        self.address
+    }
+
+    /// Value for the app's `API_BASE_URL`.
+    pub fn base_url(&self) -> String {
+        format!("http://{}/", self.address)
+    }
+
+    /// Value for the app's `STREAM_URL`.
+    pub fn stream_url(&self) -> String {
+        format!("ws://{}/smart-stream", self.address)
+    }
+
+    /// Run until the process is stopped.
+    pub async fn wait(mut self) {
+        for task in self.tasks.drain(..) {
+            let _ = task.await;
+        }
+    }
+}
+
+impl Drop for MockServer {
+    fn drop(&mut self) {
+        for task in &self.tasks {
+            task.abort();
+        }
+    }
+}
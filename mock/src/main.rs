@@ -0,0 +1,65 @@
+use angel_mock::{MockServer, Scenario, SCENARIOS};
+use std::net::SocketAddr;
+use std::process::ExitCode;
+
+const DEFAULT_PORT: u16 = 8787;
+
+fn usage() -> String {
+    format!(
+        "usage: angel-mock [--port PORT] [--scenario NAME|FILE.json]\n\nBuilt in scenarios: {}",
+        SCENARIOS.join(", ")
+    )
+}
+
+#[tokio::main]
+async fn main() -> ExitCode {
+    tracing_subscriber::fmt::init();
+
+    let mut port = DEFAULT_PORT;
+    let mut scenario = "default".to_string();
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        let parsed = match (arg.as_str(), args.next()) {
+            ("--port", Some(value)) => value.parse().map(|value| port = value).is_ok(),
+            ("--scenario", Some(value)) => {
+                scenario = value;
+                true
+            }
+            _ => false,
+        };
+        if !parsed {
+            eprintln!("{}", usage());
+            return ExitCode::FAILURE;
+        }
+    }
+
+    let scenario = match Scenario::load(&scenario) {
+        Ok(scenario) => scenario,
+        Err(e) => {
+            eprintln!("{}\n\n{}", e, usage());
+            return ExitCode::FAILURE;
+        }
+    };
+    let login = (
+        scenario.client_code.clone(),
+        scenario.password.clone(),
+        scenario.totp.clone(),
+    );
+    let server = match MockServer::bind(SocketAddr::from(([127, 0, 0, 1], port)), scenario).await {
+        Ok(server) => server,
+        Err(e) => {
+            eprintln!("Failed to listen on port {}: {}", port, e);
+            return ExitCode::FAILURE;
+        }
+    };
+
+    println!("Mock SmartAPI listening on {}", server.address());
+    println!("  API_BASE_URL={}", server.base_url());
+    println!("  STREAM_URL={}", server.stream_url());
+    println!(
+        "Log in as {} with password {} and TOTP {}",
+        login.0, login.1, login.2
+    );
+    server.wait().await;
+    ExitCode::SUCCESS
+}
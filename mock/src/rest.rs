@@ -0,0 +1,471 @@
+//! SmartAPI REST endpoints, answering with Angel's response envelope.
+
+use crate::candles;
+use crate::clock::parse_ist;
+use crate::scenario::Scenario;
+use crate::state::{day_range, price_at, OrderInput, OrderRecord, Session, State};
+use axum::body::Bytes;
+use axum::extract::{Path, State as Extract};
+use axum::http::{HeaderMap, StatusCode};
+use axum::response::{IntoResponse, Response};
+use axum::Json;
+use serde::Serialize;
+use serde_json::{json, Value};
+use std::sync::Arc;
+
+type Shared = Extract<Arc<State>>;
+
+fn ok(data: impl Serialize) -> Response {
+    Json(json!({ "status": true, "message": "SUCCESS", "errorcode": "", "data": data }))
+        .into_response()
+}
+
+fn error(code: &str, message: &str) -> Response {
+    Json(json!({ "status": false, "message": message, "errorcode": code, "data": null }))
+        .into_response()
+}
+
+// Angel answers throttled requests with a plain text 403
+fn throttled() -> Response {
+    (
+        StatusCode::FORBIDDEN,
+        "Access denied because of exceeding access rate",
+    )
+        .into_response()
+}
+
+/// A request turned down before reaching the account.
+enum Failure {
+    Api(&'static str, String),
+    Throttled,
+}
+
+impl IntoResponse for Failure {
+    fn into_response(self) -> Response {
+        match self {
+            Self::Api(code, message) => error(code, &message),
+            Self::Throttled => throttled(),
+        }
+    }
+}
+
+fn fail(code: &'static str, message: &str) -> Failure {
+    Failure::Api(code, message.to_string())
+}
+
+fn parse_body(body: &Bytes) -> Result<Value, Failure> {
+    serde_json::from_slice(body).map_err(|_| fail("AB1004", "Invalid request body"))
+}
+
+// Amounts arrive either as numbers or numeric strings
+fn number(body: &Value, key: &str) -> Option<f64> {
+    match &body[key] {
+        Value::Number(number) => number.as_f64(),
+        Value::String(text) if text.trim().is_empty() => Some(0.0),
+        Value::String(text) => text.trim().parse().ok(),
+        _ => None,
+    }
+}
+
+fn text(body: &Value, key: &str) -> Result<String, Failure> {
+    match &body[key] {
+        Value::String(text) if !text.is_empty() => Ok(text.clone()),
+        _ => Err(fail("AB1004", &format!("Invalid {}", key))),
+    }
+}
+
+fn check_api_key(headers: &HeaderMap) -> Result<(), Failure> {
+    match headers
+        .get("X-PrivateKey")
+        .and_then(|value| value.to_str().ok())
+    {
+        Some(key) if !key.trim().is_empty() => Ok(()),
+        _ => Err(fail("AG8004", "Invalid API Key")),
+    }
+}
+
+/// Rate limit, API key and JWT checks shared by every secure endpoint.
+fn authorize(state: &State, headers: &HeaderMap, endpoint: &str) -> Result<Session, Failure> {
+    if !state.allow_request(endpoint) {
+        return Err(Failure::Throttled);
+    }
+    check_api_key(headers)?;
+    let jwt_token = headers
+        .get("Authorization")
+        .and_then(|value| value.to_str().ok())
+        .map(|value| value.trim_start_matches("Bearer ").to_string())
+        .ok_or_else(|| fail("AG8001", "Invalid Token"))?;
+    let account = state.account();
+    let session = account
+        .session(&jwt_token)
+        .ok_or_else(|| fail("AG8001", "Invalid Token"))?;
+    if session.expires_at <= crate::clock::now_secs() {
+        return Err(fail("AG8002", "Token Expired"));
+    }
+    Ok(session.clone())
+}
+
+macro_rules! attempt {
+    ($result:expr) => {
+        match $result {
+            Ok(value) => value,
+            Err(failure) => return failure.into_response(),
+        }
+    };
+}
+
+fn tokens(session: &Session) -> Value {
+    json!({
+        "jwtToken": session.jwt_token,
+        "refreshToken": session.refresh_token,
+        "feedToken": session.feed_token,
+    })
+}
+
+pub async fn login(Extract(state): Shared, headers: HeaderMap, body: Bytes) -> Response {
+    if !state.allow_request("loginByPassword") {
+        return throttled();
+    }
+    attempt!(check_api_key(&headers));
+    let body = attempt!(parse_body(&body));
+    let scenario = &state.scenario;
+    if body["clientcode"] != scenario.client_code.as_str()
+        || body["password"] != scenario.password.as_str()
+    {
+        return error("AB1000", "Invalid clientcode or password");
+    }
+    if body["totp"] != scenario.totp.as_str() {
+        return error("AB1050", "Invalid totp");
+    }
+    let session = state.account().login(scenario);
+    ok(tokens(&session))
+}
+
+pub async fn generate_tokens(Extract(state): Shared, headers: HeaderMap, body: Bytes) -> Response {
+    attempt!(check_api_key(&headers));
+    let body = attempt!(parse_body(&body));
+    let refresh_token = body["refreshToken"].as_str().unwrap_or_default();
+    match state.account().refresh(&state.scenario, refresh_token) {
+        Some(session) => ok(tokens(&session)),
+        None => error("AB8050", "Invalid Refresh Token"),
+    }
+}
+
+pub async fn logout(Extract(state): Shared, headers: HeaderMap) -> Response {
+    let session = attempt!(authorize(&state, &headers, "logout"));
+    state.account().logout(&session.jwt_token);
+    ok(Value::String(String::new()))
+}
+
+pub async fn profile(Extract(state): Shared, headers: HeaderMap) -> Response {
+    attempt!(authorize(&state, &headers, "getProfile"));
+    let scenario = &state.scenario;
+    ok(json!({
+        "clientcode": scenario.client_code,
+        "name": scenario.name,
+        "email": scenario.email,
+        "mobileno": "",
+        "exchanges": ["nse_cm", "bse_cm", "nse_fo", "mcx_fo"],
+        "products": ["MARGIN", "MIS", "NRML", "CNC", "CO", "BO"],
+        "lastlogintime": "",
+        "brokerid": "B2C",
+    }))
+}
+
+pub async fn funds(Extract(state): Shared, headers: HeaderMap) -> Response {
+    attempt!(authorize(&state, &headers, "getRMS"));
+    let positions = state.account().positions(&state.scenario);
+    let total = |key: &str| {
+        positions
+            .iter()
+            .filter_map(|position| number(position, key))
+            .sum::<f64>()
+    };
+    let (realised, unrealised) = (total("realised"), total("unrealised"));
+    let cash = state.scenario.available_cash + realised;
+    let amount = |value: f64| format!("{:.2}", value);
+    ok(json!({
+        "net": amount(cash + unrealised),
+        "availablecash": amount(cash),
+        "availableintradaypayin": "0.00",
+        "availablelimitmargin": "0.00",
+        "collateral": "0.00",
+        "m2munrealized": amount(unrealised),
+        "m2mrealized": amount(realised),
+        "utiliseddebits": "0.00",
+        "utilisedspan": "0.00",
+        "utilisedoptionpremium": "0.00",
+        "utilisedholdingsales": "0.00",
+        "utilisedexposure": "0.00",
+        "utilisedturnover": "0.00",
+        "utilisedpayout": "0.00",
+    }))
+}
+
+pub async fn holdings(Extract(state): Shared, headers: HeaderMap) -> Response {
+    attempt!(authorize(&state, &headers, "getAllHolding"));
+    let scenario = &state.scenario;
+    let step = state.account().step;
+    let (mut value, mut invested) = (0.0, 0.0);
+    let holdings: Vec<Value> = scenario
+        .holdings
+        .iter()
+        .filter_map(|holding| {
+            let (_, instrument) = scenario.instrument(&holding.exchange, &holding.token)?;
+            let ltp = price_at(instrument, step);
+            let quantity = holding.quantity as f64;
+            let pnl = (ltp - holding.average_price) * quantity;
+            value += ltp * quantity;
+            invested += holding.average_price * quantity;
+            Some(json!({
+                "tradingsymbol": instrument.symbol,
+                "exchange": holding.exchange,
+                "isin": "",
+                "t1quantity": 0,
+                "realisedquantity": holding.quantity,
+                "quantity": holding.quantity,
+                "authorisedquantity": 0,
+                "product": "DELIVERY",
+                "collateralquantity": null,
+                "collateraltype": null,
+                "haircut": 0,
+                "averageprice": holding.average_price,
+                "ltp": ltp,
+                "symboltoken": holding.token,
+                "close": instrument.close,
+                "profitandloss": round(pnl),
+                "pnlpercentage": round(pnl / (holding.average_price * quantity) * 100.0),
+            }))
+        })
+        .collect();
+    let pnl = value - invested;
+    ok(json!({
+        "holdings": holdings,
+        "totalholding": {
+            "totalholdingvalue": round(value),
+            "totalinvvalue": round(invested),
+            "totalprofitandloss": round(pnl),
+            "totalpnlpercentage": if invested == 0.0 { 0.0 } else { round(pnl / invested * 100.0) },
+        },
+    }))
+}
+
+fn round(value: f64) -> f64 {
+    (value * 100.0).round() / 100.0
+}
+
+// Empty books and flat positions come back as `data: null`, like Angel's
+fn ok_list<T: Serialize>(items: Vec<T>) -> Response {
+    if items.is_empty() {
+        ok(Value::Null)
+    } else {
+        ok(items)
+    }
+}
+
+pub async fn positions(Extract(state): Shared, headers: HeaderMap) -> Response {
+    attempt!(authorize(&state, &headers, "getPosition"));
+    ok_list(state.account().positions(&state.scenario))
+}
+
+pub async fn order_book(Extract(state): Shared, headers: HeaderMap) -> Response {
+    attempt!(authorize(&state, &headers, "getOrderBook"));
+    ok_list(state.account().orders.clone())
+}
+
+pub async fn trade_book(Extract(state): Shared, headers: HeaderMap) -> Response {
+    attempt!(authorize(&state, &headers, "getTradeBook"));
+    ok_list(state.account().trades.clone())
+}
+
+pub async fn order_details(
+    Extract(state): Shared,
+    headers: HeaderMap,
+    Path(unique_order_id): Path<String>,
+) -> Response {
+    attempt!(authorize(&state, &headers, "details"));
+    let account = state.account();
+    match account
+        .orders
+        .iter()
+        .find(|order| order.uniqueorderid == unique_order_id)
+    {
+        Some(order) => ok(order),
+        None => error("AB1013", "Order not found"),
+    }
+}
+
+fn order_input(scenario: &Scenario, body: &Value) -> Result<OrderInput, Failure> {
+    let exchange = text(body, "exchange")?;
+    let token = text(body, "symboltoken")?;
+    let symbol = match body["tradingsymbol"].as_str() {
+        Some(symbol) if !symbol.is_empty() => symbol.to_string(),
+        _ => scenario
+            .instrument(&exchange, &token)
+            .map(|(_, instrument)| instrument.symbol.clone())
+            .ok_or_else(|| fail("AB1009", "Symbol Not Found"))?,
+    };
+    let quantity = number(body, "quantity").unwrap_or_default() as i64;
+    if quantity <= 0 {
+        return Err(fail("AB1004", "Invalid quantity"));
+    }
+    Ok(OrderInput {
+        variety: text(body, "variety")?,
+        symbol,
+        token,
+        transaction_type: body["transactiontype"]
+            .as_str()
+            .unwrap_or_default()
+            .to_string(),
+        exchange,
+        order_type: text(body, "ordertype")?,
+        product: text(body, "producttype")?,
+        duration: body["duration"].as_str().unwrap_or("DAY").to_string(),
+        price: number(body, "price").unwrap_or_default(),
+        trigger_price: number(body, "triggerprice").unwrap_or_default(),
+        quantity,
+    })
+}
+
+fn order_response(order: &OrderRecord) -> Response {
+    ok(json!({
+        "script": order.tradingsymbol,
+        "orderid": order.orderid,
+        "uniqueorderid": order.uniqueorderid,
+    }))
+}
+
+pub async fn place_order(Extract(state): Shared, headers: HeaderMap, body: Bytes) -> Response {
+    attempt!(authorize(&state, &headers, "placeOrder"));
+    let body = attempt!(parse_body(&body));
+    let input = attempt!(order_input(&state.scenario, &body));
+    if !matches!(input.transaction_type.as_str(), "BUY" | "SELL") {
+        return error("AB1004", "Invalid transactiontype");
+    }
+    let order = state.account().place(&state.scenario, input);
+    order_response(&order)
+}
+
+pub async fn modify_order(Extract(state): Shared, headers: HeaderMap, body: Bytes) -> Response {
+    attempt!(authorize(&state, &headers, "modifyOrder"));
+    let body = attempt!(parse_body(&body));
+    let order_id = attempt!(text(&body, "orderid"));
+    let input = attempt!(order_input(&state.scenario, &body));
+    match state.account().modify(&state.scenario, &order_id, input) {
+        Ok(order) => order_response(&order),
+        Err(message) => error("AB1013", message),
+    }
+}
+
+pub async fn cancel_order(Extract(state): Shared, headers: HeaderMap, body: Bytes) -> Response {
+    attempt!(authorize(&state, &headers, "cancelOrder"));
+    let body = attempt!(parse_body(&body));
+    let order_id = attempt!(text(&body, "orderid"));
+    match state.account().cancel(&order_id) {
+        Ok(order) => order_response(&order),
+        Err(message) => error("AB1013", message),
+    }
+}
+
+pub async fn quote(Extract(state): Shared, headers: HeaderMap, body: Bytes) -> Response {
+    attempt!(authorize(&state, &headers, "quote"));
+    let body = attempt!(parse_body(&body));
+    let scenario = &state.scenario;
+    let step = state.account().step;
+    let (mut fetched, mut unfetched) = (Vec::new(), Vec::new());
+    for (exchange, tokens) in body["exchangeTokens"].as_object().into_iter().flatten() {
+        for token in tokens
+            .as_array()
+            .into_iter()
+            .flatten()
+            .filter_map(Value::as_str)
+        {
+            let Some((_, instrument)) = scenario.instrument(exchange, token) else {
+                unfetched.push(json!({ "exchange": exchange, "symbolToken": token }));
+                continue;
+            };
+            let ltp = price_at(instrument, step);
+            let (open, high, low) = day_range(instrument, step);
+            let change = ltp - instrument.close;
+            fetched.push(json!({
+                "exchange": exchange,
+                "tradingSymbol": instrument.symbol,
+                "symbolToken": token,
+                "ltp": ltp,
+                "open": open,
+                "high": high,
+                "low": low,
+                "close": instrument.close,
+                "netChange": round(change),
+                "percentChange": round(change / instrument.close * 100.0),
+                "tradeVolume": 50_000 + step * 1_000,
+            }));
+        }
+    }
+    ok(json!({ "fetched": fetched, "unfetched": unfetched }))
+}
+
+pub async fn margin(Extract(state): Shared, headers: HeaderMap, body: Bytes) -> Response {
+    attempt!(authorize(&state, &headers, "batch"));
+    let body = attempt!(parse_body(&body));
+    let scenario = &state.scenario;
+    let step = state.account().step;
+    let (mut span, mut delivery, mut total) = (0.0, 0.0, 0.0);
+    for leg in body["positions"].as_array().into_iter().flatten() {
+        let exchange = leg["exchange"].as_str().unwrap_or_default();
+        let token = leg["token"].as_str().unwrap_or_default();
+        let price = match number(leg, "price") {
+            Some(price) if price > 0.0 => price,
+            _ => scenario
+                .instrument(exchange, token)
+                .map(|(_, instrument)| price_at(instrument, step))
+                .unwrap_or_default(),
+        };
+        let value = price * number(leg, "qty").unwrap_or_default();
+        // Rough exchange margins, enough to make the numbers look plausible
+        match leg["productType"].as_str().unwrap_or_default() {
+            "DELIVERY" => {
+                delivery += value;
+                total += value;
+            }
+            "INTRADAY" => total += value * 0.2,
+            _ => {
+                span += value * 0.12;
+                total += value * 0.15;
+            }
+        }
+    }
+    ok(json!({
+        "totalMarginRequired": round(total),
+        "marginComponents": {
+            "netPremium": 0.0,
+            "spanMargin": round(span),
+            "marginBenefit": 0.0,
+            "deliveryMargin": round(delivery),
+            "nonNFOMargin": 0.0,
+            "totOptionsPremium": 0.0,
+        },
+    }))
+}
+
+pub async fn candle_data(Extract(state): Shared, headers: HeaderMap, body: Bytes) -> Response {
+    attempt!(authorize(&state, &headers, "getCandleData"));
+    let body = attempt!(parse_body(&body));
+    let exchange = attempt!(text(&body, "exchange"));
+    let token = attempt!(text(&body, "symboltoken"));
+    let interval = attempt!(text(&body, "interval"));
+    let (Some(from), Some(to)) = (
+        body["fromdate"].as_str().and_then(parse_ist),
+        body["todate"].as_str().and_then(parse_ist),
+    ) else {
+        return error("AB1004", "Invalid fromdate or todate");
+    };
+    let instrument = state
+        .scenario
+        .instrument(&exchange, &token)
+        .map(|(_, instrument)| instrument);
+    match candles::generate(instrument, &token, &interval, from, to) {
+        Ok(candles) => ok(candles),
+        Err(message) => error("AB1004", &message),
+    }
+}
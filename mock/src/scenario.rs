@@ -0,0 +1,198 @@
+//! Scripted market and account state the mock server plays back.
+//!
+//! Built in scenarios are picked by name, anything else is read as a JSON
+//! file whose missing fields fall back to the `default` scenario.
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::Path;
+
+pub const NAMES: [&str; 5] = [
+    "default",
+    "rejections",
+    "throttled",
+    "flaky-feed",
+    "short-session",
+];
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Scenario {
+    pub client_code: String,
+    pub name: String,
+    pub email: String,
+    /// Login only succeeds with exactly these credentials
+    pub password: String,
+    pub totp: String,
+    pub available_cash: f64,
+    pub instruments: Vec<MockInstrument>,
+    pub holdings: Vec<MockHolding>,
+    /// Reject every order with this reason
+    pub reject_orders: Option<String>,
+    /// Requests per second allowed per endpoint name, e.g. `getCandleData`
+    pub rate_limits: HashMap<String, u32>,
+    pub jwt_lifetime_secs: u64,
+    /// How often scripted prices move and the feed ticks
+    pub tick_interval_ms: u64,
+    /// Drop every feed connection after this many ticks
+    pub disconnect_after_ticks: Option<u64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MockInstrument {
+    pub exchange: String,
+    pub token: String,
+    pub symbol: String,
+    /// Previous day's close
+    pub close: f64,
+    /// Prices played back one per tick, looping at the end
+    pub prices: Vec<f64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MockHolding {
+    pub exchange: String,
+    pub token: String,
+    pub quantity: i64,
+    pub average_price: f64,
+}
+
+impl Default for Scenario {
+    fn default() -> Self {
+        Self {
+            client_code: "M123456".to_string(),
+            name: "Mock Trader".to_string(),
+            email: "trader@example.com".to_string(),
+            password: "1234".to_string(),
+            totp: "123456".to_string(),
+            available_cash: 500_000.0,
+            instruments: vec![
+                instrument(
+                    "NSE",
+                    "3045",
+                    "SBIN-EQ",
+                    806.10,
+                    &[812.35, 812.8, 813.45, 812.9, 811.6, 810.75, 811.2],
+                ),
+                instrument(
+                    "NSE",
+                    "2885",
+                    "RELIANCE-EQ",
+                    2742.55,
+                    &[2751.0, 2749.35, 2753.9, 2760.1, 2757.45],
+                ),
+                instrument(
+                    "NSE",
+                    "1333",
+                    "HDFCBANK-EQ",
+                    1668.4,
+                    &[1661.25, 1659.8, 1662.0, 1664.7],
+                ),
+                instrument(
+                    "NSE",
+                    "99926000",
+                    "Nifty 50",
+                    24971.3,
+                    &[25010.45, 25022.1, 25004.8, 24998.65],
+                ),
+                instrument(
+                    "NFO",
+                    "35003",
+                    "NIFTY24OCTFUT",
+                    25040.0,
+                    &[25081.2, 25094.0, 25070.55, 25066.3],
+                ),
+            ],
+            holdings: vec![
+                MockHolding {
+                    exchange: "NSE".to_string(),
+                    token: "3045".to_string(),
+                    quantity: 40,
+                    average_price: 742.3,
+                },
+                MockHolding {
+                    exchange: "NSE".to_string(),
+                    token: "1333".to_string(),
+                    quantity: 12,
+                    average_price: 1710.0,
+                },
+            ],
+            reject_orders: None,
+            rate_limits: HashMap::from([("getCandleData".to_string(), 3)]),
+            jwt_lifetime_secs: 24 * 60 * 60,
+            tick_interval_ms: 1000,
+            disconnect_after_ticks: None,
+        }
+    }
+}
+
+fn instrument(
+    exchange: &str,
+    token: &str,
+    symbol: &str,
+    close: f64,
+    prices: &[f64],
+) -> MockInstrument {
+    MockInstrument {
+        exchange: exchange.to_string(),
+        token: token.to_string(),
+        symbol: symbol.to_string(),
+        close,
+        prices: prices.to_vec(),
+    }
+}
+
+impl Scenario {
+    /// A built in scenario, see [`NAMES`].
+    pub fn named(name: &str) -> Option<Self> {
+        let default = Self::default();
+        match name {
+            "default" => Some(default),
+            "rejections" => Some(Self {
+                reject_orders: Some("Insufficient funds to place the order".to_string()),
+                ..default
+            }),
+            "throttled" => Some(Self {
+                rate_limits: [
+                    "getCandleData",
+                    "getOrderBook",
+                    "getPosition",
+                    "getRMS",
+                    "quote",
+                ]
+                .into_iter()
+                .map(|endpoint| (endpoint.to_string(), 1))
+                .collect(),
+                ..default
+            }),
+            "flaky-feed" => Some(Self {
+                disconnect_after_ticks: Some(10),
+                ..default
+            }),
+            "short-session" => Some(Self {
+                jwt_lifetime_secs: 120,
+                ..default
+            }),
+            _ => None,
+        }
+    }
+
+    /// A built in scenario by name, or a JSON scenario file.
+    pub fn load(name_or_path: &str) -> Result<Self, String> {
+        if let Some(scenario) = Self::named(name_or_path) {
+            return Ok(scenario);
+        }
+        let path = Path::new(name_or_path);
+        let contents = std::fs::read_to_string(path)
+            .map_err(|e| format!("unknown scenario {:?}: {}", name_or_path, e))?;
+        serde_json::from_str(&contents)
+            .map_err(|e| format!("invalid scenario {}: {}", path.display(), e))
+    }
+
+    pub fn instrument(&self, exchange: &str, token: &str) -> Option<(usize, &MockInstrument)> {
+        self.instruments
+            .iter()
+            .enumerate()
+            .find(|(_, instrument)| instrument.exchange == exchange && instrument.token == token)
+    }
+}
@@ -0,0 +1,453 @@
+//! In-memory account the mock server mutates as requests come in.
+
+use crate::clock::{now_secs, IstDateTime};
+use crate::scenario::{MockInstrument, Scenario};
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::sync::{Mutex, MutexGuard, PoisonError};
+use tokio::sync::broadcast;
+
+pub struct State {
+    pub scenario: Scenario,
+    account: Mutex<Account>,
+    /// Sends the new step every time scripted prices move
+    pub ticks: broadcast::Sender<u64>,
+}
+
+#[derive(Default)]
+pub struct Account {
+    pub step: u64,
+    sessions: Vec<Session>,
+    pub orders: Vec<OrderRecord>,
+    pub trades: Vec<TradeRecord>,
+    positions: Vec<PositionRecord>,
+    next_id: u64,
+    // Requests seen per endpoint in the current second
+    requests: HashMap<String, (i64, u32)>,
+}
+
+#[derive(Debug, Clone)]
+pub struct Session {
+    pub jwt_token: String,
+    pub refresh_token: String,
+    pub feed_token: String,
+    pub expires_at: i64,
+}
+
+/// An order, shaped like `getOrderBook` entries.
+#[derive(Debug, Clone, Serialize)]
+pub struct OrderRecord {
+    pub orderid: String,
+    pub uniqueorderid: String,
+    pub exchangeorderid: String,
+    pub variety: String,
+    pub tradingsymbol: String,
+    pub symboltoken: String,
+    pub transactiontype: String,
+    pub exchange: String,
+    pub ordertype: String,
+    pub producttype: String,
+    pub duration: String,
+    pub price: f64,
+    pub triggerprice: f64,
+    pub averageprice: f64,
+    pub quantity: String,
+    pub filledshares: String,
+    pub unfilledshares: String,
+    pub status: String,
+    pub orderstatus: String,
+    pub text: String,
+    pub updatetime: String,
+    pub exchorderupdatetime: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct TradeRecord {
+    pub orderid: String,
+    pub fillid: String,
+    pub filltime: String,
+    pub fillprice: f64,
+    pub fillsize: String,
+    pub tradevalue: f64,
+    pub tradingsymbol: String,
+    pub exchange: String,
+    pub producttype: String,
+    pub transactiontype: String,
+}
+
+#[derive(Debug, Clone, Default)]
+struct PositionRecord {
+    exchange: String,
+    symbol: String,
+    token: String,
+    product: String,
+    buy_quantity: i64,
+    sell_quantity: i64,
+    buy_value: f64,
+    sell_value: f64,
+}
+
+/// Fields of a new or modified order, already validated.
+pub struct OrderInput {
+    pub variety: String,
+    pub symbol: String,
+    pub token: String,
+    pub transaction_type: String,
+    pub exchange: String,
+    pub order_type: String,
+    pub product: String,
+    pub duration: String,
+    pub price: f64,
+    pub trigger_price: f64,
+    pub quantity: i64,
+}
+
+impl State {
+    pub fn new(scenario: Scenario) -> Self {
+        let (ticks, _) = broadcast::channel(16);
+        Self {
+            scenario,
+            account: Mutex::new(Account::default()),
+            ticks,
+        }
+    }
+
+    pub fn account(&self) -> MutexGuard<'_, Account> {
+        self.account.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Move every scripted price one step and fill orders the move crossed.
+    pub fn advance(&self) {
+        let step = {
+            let mut account = self.account();
+            account.step += 1;
+            account.match_orders(&self.scenario);
+            account.step
+        };
+        let _ = self.ticks.send(step);
+    }
+
+    /// Whether `endpoint` may serve another request this second.
+    pub fn allow_request(&self, endpoint: &str) -> bool {
+        let Some(&limit) = self.scenario.rate_limits.get(endpoint) else {
+            return true;
+        };
+        let now = now_secs();
+        let mut account = self.account();
+        let (second, count) = account
+            .requests
+            .entry(endpoint.to_string())
+            .or_insert((now, 0));
+        if *second != now {
+            *second = now;
+            *count = 0;
+        }
+        *count += 1;
+        *count <= limit
+    }
+}
+
+/// Price of an instrument at a step of its script.
+pub fn price_at(instrument: &MockInstrument, step: u64) -> f64 {
+    match instrument.prices.len() {
+        0 => instrument.close,
+        len => instrument.prices[step as usize % len],
+    }
+}
+
+/// Open, high and low of the day so far.
+pub fn day_range(instrument: &MockInstrument, step: u64) -> (f64, f64, f64) {
+    let seen = instrument.prices.len().min(step as usize + 1);
+    let prices = &instrument.prices[..seen];
+    let open = prices.first().copied().unwrap_or(instrument.close);
+    let high = prices.iter().copied().fold(open, f64::max);
+    let low = prices.iter().copied().fold(open, f64::min);
+    (open, high, low)
+}
+
+impl Account {
+    pub fn login(&mut self, scenario: &Scenario) -> Session {
+        self.next_id += 1;
+        let expires_at = now_secs() + scenario.jwt_lifetime_secs as i64;
+        let payload = serde_json::json!({
+            "username": scenario.client_code,
+            "iat": now_secs(),
+            "exp": expires_at,
+        });
+        let session = Session {
+            jwt_token: format!(
+                "{}.{}.mock{}",
+                URL_SAFE_NO_PAD.encode(r#"{"alg":"HS512","typ":"JWT"}"#),
+                URL_SAFE_NO_PAD.encode(payload.to_string()),
+                self.next_id
+            ),
+            refresh_token: format!("mock-refresh-{}", self.next_id),
+            feed_token: format!("mock-feed-{}", self.next_id),
+            expires_at,
+        };
+        self.sessions.push(session.clone());
+        session
+    }
+
+    pub fn session(&self, jwt_token: &str) -> Option<&Session> {
+        self.sessions
+            .iter()
+            .find(|session| session.jwt_token == jwt_token)
+    }
+
+    pub fn session_by_feed_token(&self, feed_token: &str) -> Option<&Session> {
+        self.sessions
+            .iter()
+            .find(|session| session.feed_token == feed_token)
+    }
+
+    pub fn refresh(&mut self, scenario: &Scenario, refresh_token: &str) -> Option<Session> {
+        let index = self
+            .sessions
+            .iter()
+            .position(|session| session.refresh_token == refresh_token)?;
+        self.sessions.remove(index);
+        Some(self.login(scenario))
+    }
+
+    pub fn logout(&mut self, jwt_token: &str) {
+        self.sessions
+            .retain(|session| session.jwt_token != jwt_token);
+    }
+
+    pub fn place(&mut self, scenario: &Scenario, input: OrderInput) -> OrderRecord {
+        self.next_id += 1;
+        let now = IstDateTime::from_unix(now_secs()).angel();
+        let mut order = OrderRecord {
+            orderid: format!("{:015}", 241016000000000 + self.next_id),
+            uniqueorderid: format!("mock-{:08x}-{:04}", self.next_id, self.next_id % 10_000),
+            exchangeorderid: String::new(),
+            variety: input.variety,
+            tradingsymbol: input.symbol,
+            symboltoken: input.token,
+            transactiontype: input.transaction_type,
+            exchange: input.exchange,
+            ordertype: input.order_type,
+            producttype: input.product,
+            duration: input.duration,
+            price: input.price,
+            triggerprice: input.trigger_price,
+            averageprice: 0.0,
+            quantity: input.quantity.to_string(),
+            filledshares: "0".to_string(),
+            unfilledshares: input.quantity.to_string(),
+            status: String::new(),
+            orderstatus: String::new(),
+            text: String::new(),
+            updatetime: now.clone(),
+            exchorderupdatetime: now,
+        };
+
+        match &scenario.reject_orders {
+            Some(reason) => {
+                set_status(&mut order, "rejected");
+                order.text = reason.clone();
+            }
+            None => {
+                order.exchangeorderid = format!("1100000{:09}", self.next_id);
+                set_status(&mut order, "open pending");
+            }
+        }
+        self.orders.push(order.clone());
+        self.match_orders(scenario);
+        self.orders.last().cloned().unwrap_or(order)
+    }
+
+    pub fn modify(
+        &mut self,
+        scenario: &Scenario,
+        order_id: &str,
+        input: OrderInput,
+    ) -> Result<OrderRecord, &'static str> {
+        let order = self
+            .orders
+            .iter_mut()
+            .find(|order| order.orderid == order_id)
+            .ok_or("Order not found")?;
+        if !is_working(order) {
+            return Err("Order is not open");
+        }
+        order.ordertype = input.order_type;
+        order.producttype = input.product;
+        order.duration = input.duration;
+        order.price = input.price;
+        order.triggerprice = input.trigger_price;
+        order.quantity = input.quantity.to_string();
+        order.unfilledshares = input.quantity.to_string();
+        order.updatetime = IstDateTime::from_unix(now_secs()).angel();
+        set_status(order, "modified");
+        let order = order.clone();
+        self.match_orders(scenario);
+        Ok(order)
+    }
+
+    pub fn cancel(&mut self, order_id: &str) -> Result<OrderRecord, &'static str> {
+        let order = self
+            .orders
+            .iter_mut()
+            .find(|order| order.orderid == order_id)
+            .ok_or("Order not found")?;
+        if !is_working(order) {
+            return Err("Order is not open");
+        }
+        set_status(order, "cancelled");
+        order.text = "Cancelled by user".to_string();
+        order.updatetime = IstDateTime::from_unix(now_secs()).angel();
+        Ok(order.clone())
+    }
+
+    // Fill or trigger working orders against the current prices
+    fn match_orders(&mut self, scenario: &Scenario) {
+        let step = self.step;
+        let mut fills = Vec::new();
+        for order in self.orders.iter_mut().filter(|order| is_working(order)) {
+            let Some((_, instrument)) = scenario.instrument(&order.exchange, &order.symboltoken)
+            else {
+                set_status(order, "rejected");
+                order.text = "Symbol not found in the mock scenario".to_string();
+                continue;
+            };
+            let ltp = price_at(instrument, step);
+            let buy = order.transactiontype == "BUY";
+            let crossed = |limit: f64| if buy { ltp <= limit } else { ltp >= limit };
+            let triggered = |trigger: f64| if buy { ltp >= trigger } else { ltp <= trigger };
+
+            let fill_price = match order.ordertype.as_str() {
+                "MARKET" => Some(ltp),
+                "LIMIT" => crossed(order.price).then_some(order.price),
+                "STOPLOSS_MARKET" => triggered(order.triggerprice).then_some(ltp),
+                "STOPLOSS_LIMIT" if triggered(order.triggerprice) => {
+                    crossed(order.price).then_some(order.price)
+                }
+                _ => None,
+            };
+            match fill_price {
+                Some(price) => {
+                    let quantity: i64 = order.quantity.parse().unwrap_or_default();
+                    order.averageprice = price;
+                    order.filledshares = quantity.to_string();
+                    order.unfilledshares = "0".to_string();
+                    set_status(order, "complete");
+                    fills.push((order.clone(), quantity, price));
+                }
+                None if order.ordertype.starts_with("STOPLOSS")
+                    && !triggered(order.triggerprice) =>
+                {
+                    set_status(order, "trigger pending");
+                }
+                None => set_status(order, "open"),
+            }
+        }
+
+        for (order, quantity, price) in fills {
+            self.next_id += 1;
+            self.trades.push(TradeRecord {
+                orderid: order.orderid.clone(),
+                fillid: format!("{}", 50_000_000 + self.next_id),
+                filltime: IstDateTime::from_unix(now_secs()).angel()[12..].to_string(),
+                fillprice: price,
+                fillsize: quantity.to_string(),
+                tradevalue: price * quantity as f64,
+                tradingsymbol: order.tradingsymbol.clone(),
+                exchange: order.exchange.clone(),
+                producttype: order.producttype.clone(),
+                transactiontype: order.transactiontype.clone(),
+            });
+            self.add_to_position(&order, quantity, price);
+        }
+    }
+
+    fn add_to_position(&mut self, order: &OrderRecord, quantity: i64, price: f64) {
+        let index = match self.positions.iter().position(|position| {
+            position.exchange == order.exchange
+                && position.token == order.symboltoken
+                && position.product == order.producttype
+        }) {
+            Some(index) => index,
+            None => {
+                self.positions.push(PositionRecord {
+                    exchange: order.exchange.clone(),
+                    symbol: order.tradingsymbol.clone(),
+                    token: order.symboltoken.clone(),
+                    product: order.producttype.clone(),
+                    ..Default::default()
+                });
+                self.positions.len() - 1
+            }
+        };
+        let position = &mut self.positions[index];
+        if order.transactiontype == "BUY" {
+            position.buy_quantity += quantity;
+            position.buy_value += price * quantity as f64;
+        } else {
+            position.sell_quantity += quantity;
+            position.sell_value += price * quantity as f64;
+        }
+    }
+
+    /// Positions shaped like `getPosition`, marked to the current prices.
+    pub fn positions(&self, scenario: &Scenario) -> Vec<serde_json::Value> {
+        self.positions
+            .iter()
+            .map(|position| {
+                let ltp = scenario
+                    .instrument(&position.exchange, &position.token)
+                    .map(|(_, instrument)| price_at(instrument, self.step))
+                    .unwrap_or_default();
+                let average = |value: f64, quantity: i64| {
+                    if quantity == 0 {
+                        0.0
+                    } else {
+                        value / quantity as f64
+                    }
+                };
+                let buy_average = average(position.buy_value, position.buy_quantity);
+                let sell_average = average(position.sell_value, position.sell_quantity);
+                let net_quantity = position.buy_quantity - position.sell_quantity;
+                let closed = position.buy_quantity.min(position.sell_quantity) as f64;
+                let realised = (sell_average - buy_average) * closed;
+                let net_average = if net_quantity > 0 {
+                    buy_average
+                } else {
+                    sell_average
+                };
+                let unrealised = (ltp - net_average) * net_quantity as f64;
+                serde_json::json!({
+                    "exchange": position.exchange,
+                    "tradingsymbol": position.symbol,
+                    "symboltoken": position.token,
+                    "producttype": position.product,
+                    "buyqty": position.buy_quantity.to_string(),
+                    "sellqty": position.sell_quantity.to_string(),
+                    "netqty": net_quantity.to_string(),
+                    "buyavgprice": format!("{:.2}", buy_average),
+                    "sellavgprice": format!("{:.2}", sell_average),
+                    "avgnetprice": format!("{:.2}", net_average),
+                    "ltp": format!("{:.2}", ltp),
+                    "pnl": format!("{:.2}", realised + unrealised),
+                    "realised": format!("{:.2}", realised),
+                    "unrealised": format!("{:.2}", unrealised),
+                })
+            })
+            .collect()
+    }
+}
+
+fn is_working(order: &OrderRecord) -> bool {
+    matches!(
+        order.status.as_str(),
+        "open" | "open pending" | "trigger pending" | "modified"
+    )
+}
+
+fn set_status(order: &mut OrderRecord, status: &str) {
+    order.status = status.to_string();
+    order.orderstatus = status.to_string();
+}
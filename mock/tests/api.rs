@@ -0,0 +1,249 @@
+use angel_mock::{MockServer, Scenario};
+use futures::{SinkExt, StreamExt};
+use serde_json::{json, Value};
+use tokio_tungstenite::tungstenite::client::IntoClientRequest;
+use tokio_tungstenite::tungstenite::Message;
+
+struct Client {
+    http: reqwest::Client,
+    base_url: String,
+    jwt_token: String,
+}
+
+impl Client {
+    async fn login(server: &MockServer) -> (Self, Value) {
+        let http = reqwest::Client::new();
+        let base_url = server.base_url();
+        let mut client = Self {
+            http,
+            base_url,
+            jwt_token: String::new(),
+        };
+        let tokens = client
+            .post(
+                "rest/auth/angelbroking/user/v1/loginByPassword",
+                json!({ "clientcode": "M123456", "password": "1234", "totp": "123456" }),
+            )
+            .await;
+        client.jwt_token = tokens["data"]["jwtToken"].as_str().unwrap().to_string();
+        (client, tokens["data"].clone())
+    }
+
+    async fn post(&self, path: &str, body: Value) -> Value {
+        self.http
+            .post(format!("{}{}", self.base_url, path))
+            .header("X-PrivateKey", "mock-key")
+            .bearer_auth(&self.jwt_token)
+            .json(&body)
+            .send()
+            .await
+            .unwrap()
+            .json()
+            .await
+            .unwrap()
+    }
+
+    async fn get(&self, path: &str) -> reqwest::Response {
+        self.http
+            .get(format!("{}{}", self.base_url, path))
+            .header("X-PrivateKey", "mock-key")
+            .bearer_auth(&self.jwt_token)
+            .send()
+            .await
+            .unwrap()
+    }
+}
+
+#[tokio::test]
+async fn rejects_bad_credentials() {
+    let server = MockServer::start(Scenario::default()).await.unwrap();
+    let (client, _) = Client::login(&server).await;
+    let response = client
+        .post(
+            "rest/auth/angelbroking/user/v1/loginByPassword",
+            json!({ "clientcode": "M123456", "password": "1234", "totp": "000000" }),
+        )
+        .await;
+    assert_eq!(response["errorcode"], "AB1050");
+
+    let response = Client {
+        jwt_token: "stale".to_string(),
+        ..client
+    }
+    .get("rest/secure/angelbroking/user/v1/getProfile")
+    .await;
+    let response: Value = response.json().await.unwrap();
+    assert_eq!(response["errorcode"], "AG8001");
+}
+
+#[tokio::test]
+async fn fills_market_orders_into_positions() {
+    let server = MockServer::start(Scenario::default()).await.unwrap();
+    let (client, _) = Client::login(&server).await;
+
+    let order = json!({
+        "variety": "NORMAL",
+        "tradingsymbol": "SBIN-EQ",
+        "symboltoken": "3045",
+        "transactiontype": "BUY",
+        "exchange": "NSE",
+        "ordertype": "MARKET",
+        "producttype": "INTRADAY",
+        "duration": "DAY",
+        "price": "0",
+        "quantity": "5",
+    });
+    let placed = client
+        .post("rest/secure/angelbroking/order/v1/placeOrder", order)
+        .await;
+    assert_eq!(placed["status"], true);
+
+    let book: Value = client
+        .get("rest/secure/angelbroking/order/v1/getOrderBook")
+        .await
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(book["data"][0]["orderid"], placed["data"]["orderid"]);
+    assert_eq!(book["data"][0]["status"], "complete");
+
+    let positions: Value = client
+        .get("rest/secure/angelbroking/order/v1/getPosition")
+        .await
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(positions["data"][0]["netqty"], "5");
+}
+
+#[tokio::test]
+async fn keeps_limit_orders_open_until_cancelled() {
+    let server = MockServer::start(Scenario::named("default").unwrap())
+        .await
+        .unwrap();
+    let (client, _) = Client::login(&server).await;
+
+    let order = json!({
+        "variety": "NORMAL",
+        "tradingsymbol": "SBIN-EQ",
+        "symboltoken": "3045",
+        "transactiontype": "BUY",
+        "exchange": "NSE",
+        "ordertype": "LIMIT",
+        "producttype": "DELIVERY",
+        "duration": "DAY",
+        "price": "700",
+        "quantity": "1",
+    });
+    let placed = client
+        .post("rest/secure/angelbroking/order/v1/placeOrder", order)
+        .await;
+    let order_id = placed["data"]["orderid"].clone();
+    let unique_order_id = placed["data"]["uniqueorderid"].as_str().unwrap();
+
+    let details: Value = client
+        .get(&format!(
+            "rest/secure/angelbroking/order/v1/details/{}",
+            unique_order_id
+        ))
+        .await
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(details["data"]["status"], "open");
+
+    let cancelled = client
+        .post(
+            "rest/secure/angelbroking/order/v1/cancelOrder",
+            json!({ "variety": "NORMAL", "orderid": order_id }),
+        )
+        .await;
+    assert_eq!(cancelled["status"], true);
+    let cancelled_again = client
+        .post(
+            "rest/secure/angelbroking/order/v1/cancelOrder",
+            json!({ "variety": "NORMAL", "orderid": order_id }),
+        )
+        .await;
+    assert_eq!(cancelled_again["status"], false);
+}
+
+#[tokio::test]
+async fn throttles_candle_requests() {
+    let server = MockServer::start(Scenario::default()).await.unwrap();
+    let (client, _) = Client::login(&server).await;
+
+    let mut statuses = Vec::new();
+    for _ in 0..6 {
+        let response = client
+            .http
+            .post(format!(
+                "{}rest/secure/angelbroking/historical/v1/getCandleData",
+                client.base_url
+            ))
+            .header("X-PrivateKey", "mock-key")
+            .bearer_auth(&client.jwt_token)
+            .json(&json!({
+                "exchange": "NSE",
+                "symboltoken": "3045",
+                "interval": "ONE_HOUR",
+                "fromdate": "2024-10-14 09:15",
+                "todate": "2024-10-16 15:30",
+            }))
+            .send()
+            .await
+            .unwrap();
+        statuses.push(response.status().as_u16());
+    }
+    // Three a second are allowed, the burst may straddle a second boundary
+    assert!(statuses.iter().filter(|&&status| status == 200).count() >= 3);
+    assert!(statuses.contains(&403));
+}
+
+#[tokio::test]
+async fn streams_ticks_for_subscribed_tokens() {
+    let server = MockServer::start(Scenario {
+        tick_interval_ms: 50,
+        ..Scenario::default()
+    })
+    .await
+    .unwrap();
+    let (client, tokens) = Client::login(&server).await;
+
+    let mut request = server.stream_url().into_client_request().unwrap();
+    let headers = request.headers_mut();
+    headers.insert("Authorization", client.jwt_token.parse().unwrap());
+    headers.insert("x-api-key", "mock-key".parse().unwrap());
+    headers.insert("x-client-code", "M123456".parse().unwrap());
+    headers.insert(
+        "x-feed-token",
+        tokens["feedToken"].as_str().unwrap().parse().unwrap(),
+    );
+    let (mut socket, _) = tokio_tungstenite::connect_async(request).await.unwrap();
+
+    let subscribe = json!({
+        "action": 1,
+        "params": { "mode": 2, "tokenList": [{ "exchangeType": 1, "tokens": ["3045"] }] },
+    });
+    socket
+        .send(Message::Text(subscribe.to_string()))
+        .await
+        .unwrap();
+    socket
+        .send(Message::Text("ping".to_string()))
+        .await
+        .unwrap();
+
+    let (mut packets, mut pong) = (0, false);
+    while packets < 3 || !pong {
+        match socket.next().await.unwrap().unwrap() {
+            Message::Binary(packet) => {
+                assert_eq!(packet.len(), 123);
+                assert_eq!(&packet[2..6], b"3045");
+                packets += 1;
+            }
+            Message::Text(text) => pong |= text == "pong",
+            _ => {}
+        }
+    }
+}
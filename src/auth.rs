@@ -1,13 +1,13 @@
+use crate::secret::REDACTED;
+use crate::smartapi::{SmartApiError, UserProfile};
+use crate::storage::{token_store, StoredSession};
+use crate::time::{next_ist_midnight, now_secs, sleep};
 use dioxus::prelude::*;
 use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::time::Duration;
 
-#[cfg(target_arch = "wasm32")]
-use web_sys::{window, Storage};
-
-#[cfg(target_arch = "wasm32")]
-use wasm_bindgen::prelude::*;
-
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[derive(Clone, Serialize, Deserialize, PartialEq)]
 pub struct AuthTokens {
     pub jwt_token: String,
     pub refresh_token: String,
@@ -15,6 +15,18 @@ pub struct AuthTokens {
     pub user_id: String,
 }
 
+// Tokens are bearer credentials, keep them out of logs
+impl fmt::Debug for AuthTokens {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("AuthTokens")
+            .field("jwt_token", &format_args!("{}", REDACTED))
+            .field("refresh_token", &format_args!("{}", REDACTED))
+            .field("feed_token", &format_args!("{}", REDACTED))
+            .field("user_id", &self.user_id)
+            .finish()
+    }
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub enum AuthState {
     Loading,
@@ -22,19 +34,23 @@ pub enum AuthState {
     Unauthenticated,
 }
 
-const TOKEN_STORAGE_KEY: &str = "angel_trading_auth_tokens";
-const TOKEN_EXPIRY_KEY: &str = "angel_trading_auth_expiry";
+// Refresh the session this long before the stored expiry
+const REFRESH_LEEWAY_SECS: u64 = 10 * 60;
+// How often the provider checks whether a refresh is due
+const REFRESH_CHECK_INTERVAL: Duration = Duration::from_secs(60);
 
 // Context for auth state
 #[derive(Clone, Copy)]
 pub struct AuthContext {
     pub state: Signal<AuthState>,
+    pub profile: Signal<Option<UserProfile>>,
 }
 
 impl AuthContext {
     pub fn new() -> Self {
         Self {
             state: Signal::new(AuthState::Loading),
+            profile: Signal::new(None),
         }
     }
 
@@ -63,62 +79,103 @@ impl AuthContext {
     }
 
     pub async fn logout(&mut self) -> Result<(), String> {
+        // End the Angel session, local tokens are cleared even if this fails
+        if let (Some(tokens), Some((_, authorization))) =
+            (self.get_tokens(), self.get_auth_header())
+        {
+            if let Err(e) = logout_server(authorization, tokens.user_id).await {
+                tracing::warn!("Server logout failed: {}", e);
+            }
+        }
+
+        self.clear_session().await
+    }
+
+    async fn clear_session(&mut self) -> Result<(), String> {
         // Clear tokens from storage
         clear_auth_tokens().await?;
 
         // Update auth state
         self.state.set(AuthState::Unauthenticated);
+        self.profile.set(None);
         Ok(())
     }
 
+    pub fn get_profile(&self) -> Option<UserProfile> {
+        self.profile.read().clone()
+    }
+
+    // Fetch the trader profile once per session and cache it in the context
+    pub async fn load_profile(&mut self) -> Result<(), String> {
+        let Some((_, authorization)) = self.get_auth_header() else {
+            return Ok(());
+        };
+
+        match get_profile_server(authorization).await {
+            Ok(profile) => {
+                self.profile.set(Some(profile));
+                Ok(())
+            }
+            Err(e) => {
+                tracing::error!("Failed to load profile: {}", e);
+                Err(crate::smartapi::error_message(&e))
+            }
+        }
+    }
+
     pub fn get_auth_header(&self) -> Option<(String, String)> {
         self.get_tokens()
             .map(|tokens| ("Authorization".to_string(), format!("Bearer {}", tokens.jwt_token)))
     }
 
     pub async fn refresh_tokens_if_needed(&mut self) -> Result<(), String> {
-        if let Some(tokens) = self.get_tokens() {
-            // In a real app, you'd check token expiry and call refresh endpoint
-            // For now, we'll just validate that tokens exist
-            if self.is_token_expired(&tokens).await {
-                // Clear expired tokens
-                self.logout().await?;
-                return Err("Tokens expired".to_string());
-            }
+        let Some(tokens) = self.get_tokens() else {
+            return Ok(());
+        };
+        let Some(expiry) = load_token_expiry() else {
+            return Ok(());
+        };
+        let now = now_secs();
+
+        // Nothing to do while the session is comfortably valid
+        if now + REFRESH_LEEWAY_SECS < expiry {
+            return Ok(());
+        }
+        // Past the daily cutoff Angel has ended the session, let it go
+        if now > expiry {
+            self.clear_session().await?;
+            return Err("Tokens expired".to_string());
+        }
+        // New tokens end at the same cutoff, so only a JWT running out first is worth refreshing
+        if !refresh_extends_session(decode_jwt_expiry(&tokens.jwt_token), now) {
+            return Ok(());
         }
-        Ok(())
-    }
 
-    async fn is_token_expired(&self, _tokens: &AuthTokens) -> bool {
-        // Check if tokens are expired based on storage expiry
-        #[cfg(target_arch = "wasm32")]
-        {
-            if let Ok(storage) = get_local_storage() {
-                if let Ok(Some(expiry_str)) = storage.get_item(TOKEN_EXPIRY_KEY) {
-                    if let Ok(expiry) = expiry_str.parse::<f64>() {
-                        return js_sys::Date::now() > expiry;
-                    }
-                }
+        match refresh_tokens_server(tokens).await {
+            Ok(tokens) => {
+                tracing::info!("Tokens refreshed successfully");
+                store_auth_tokens(&tokens).await?;
+                self.state.set(AuthState::Authenticated(tokens));
+                Ok(())
             }
-        }
-        
-        #[cfg(not(target_arch = "wasm32"))]
-        {
-            if let Ok(app_dir) = get_app_data_dir() {
-                let expiry_path = app_dir.join("auth_expiry.txt");
-                if let Ok(expiry_str) = std::fs::read_to_string(&expiry_path) {
-                    if let Ok(expiry) = expiry_str.trim().parse::<u64>() {
-                        let now = std::time::SystemTime::now()
-                            .duration_since(std::time::UNIX_EPOCH)
-                            .unwrap()
-                            .as_secs();
-                        return now > expiry;
-                    }
+            Err(e) => {
+                tracing::error!("Token refresh failed: {}", e);
+                let rejected = matches!(
+                    &e,
+                    ServerFnError::WrappedServerError(e) if e.is_session_error()
+                );
+                // Keep the session until it actually expires, the next check retries
+                if rejected || self.is_token_expired() {
+                    self.clear_session().await?;
+                    return Err("Tokens expired".to_string());
                 }
+                Err(format!("Token refresh failed: {}", e))
             }
         }
-        
-        false
+    }
+
+    fn is_token_expired(&self) -> bool {
+        load_token_expiry().is_some_and(|expiry| now_secs() > expiry)
     }
 
     pub fn validate_and_get_tokens(&self) -> Option<AuthTokens> {
@@ -170,254 +227,91 @@ pub fn use_redirect_if_authenticated() {
     });
 }
 
-// Storage utilities with better error handling and expiry support
+// Storage utilities with expiry support
 async fn store_auth_tokens(tokens: &AuthTokens) -> Result<(), String> {
-    #[cfg(target_arch = "wasm32")]
-    {
-        let storage = get_local_storage()?;
-
-        let tokens_json = serde_json::to_string(tokens)
-            .map_err(|e| format!("Failed to serialize tokens: {}", e))?;
-
-        // Store tokens
-        storage
-            .set_item(TOKEN_STORAGE_KEY, &tokens_json)
-            .map_err(|e| format!("Failed to store tokens: {:?}", e))?;
-
-        // Store expiry timestamp (24 hours from now)
-        let expiry = js_sys::Date::now() + (24.0 * 60.0 * 60.0 * 1000.0);
-        storage
-            .set_item(TOKEN_EXPIRY_KEY, &expiry.to_string())
-            .map_err(|e| format!("Failed to store token expiry: {:?}", e))?;
-
-        tracing::info!("Tokens stored successfully with expiry");
-    }
-
-    #[cfg(not(target_arch = "wasm32"))]
-    {
-        // For mobile platforms, use file-based storage
-        store_tokens_file(tokens).await?;
-    }
-
-    Ok(())
+    let session = StoredSession {
+        tokens: tokens.clone(),
+        expires_at: token_expiry(tokens),
+    };
+    token_store()?.save(&session)
 }
 
 async fn load_auth_from_storage() -> AuthState {
-    #[cfg(target_arch = "wasm32")]
-    {
-        match get_local_storage() {
-            Ok(storage) => {
-                // Check if tokens have expired
-                if let Ok(Some(expiry_str)) = storage.get_item(TOKEN_EXPIRY_KEY) {
-                    if let Ok(expiry) = expiry_str.parse::<f64>() {
-                        let now = js_sys::Date::now();
-                        if now > expiry {
-                            tracing::info!("Tokens expired, clearing storage");
-                            let _ = clear_auth_tokens_internal(&storage);
-                            return AuthState::Unauthenticated;
-                        }
-                    }
-                }
-
-                // Load tokens if not expired
-                if let Ok(Some(tokens_json)) = storage.get_item(TOKEN_STORAGE_KEY) {
-                    match serde_json::from_str::<AuthTokens>(&tokens_json) {
-                        Ok(tokens) => {
-                            tracing::info!("Loaded valid tokens from storage");
-                            return AuthState::Authenticated(tokens);
-                        }
-                        Err(e) => {
-                            tracing::error!("Failed to parse stored tokens: {}", e);
-                            let _ = clear_auth_tokens_internal(&storage);
-                        }
-                    }
-                }
-            }
-            Err(e) => {
-                tracing::error!("Failed to access storage: {}", e);
-            }
+    let session = match token_store().and_then(|store| store.load()) {
+        Ok(Some(session)) => session,
+        Ok(None) => {
+            tracing::info!("No stored tokens found");
+            return AuthState::Unauthenticated;
         }
-    }
-
-    #[cfg(not(target_arch = "wasm32"))]
-    {
-        // For mobile platforms, try loading from file
-        match load_tokens_file().await {
-            Ok(tokens) => return AuthState::Authenticated(tokens),
-            Err(e) => tracing::info!("No valid tokens found in file storage: {}", e),
+        Err(e) => {
+            tracing::error!("Failed to load stored tokens: {}", e);
+            let _ = clear_auth_tokens().await;
+            return AuthState::Unauthenticated;
         }
+    };
+
+    // Expiries stored before the JWT was decoded may outlive the token
+    if now_secs() > session.expires_at || is_jwt_expired(&session.tokens) {
+        tracing::info!("Tokens expired, clearing storage");
+        let _ = clear_auth_tokens().await;
+        return AuthState::Unauthenticated;
     }
 
-    AuthState::Unauthenticated
+    tracing::info!("Loaded valid tokens from storage");
+    AuthState::Authenticated(session.tokens)
 }
 
 async fn clear_auth_tokens() -> Result<(), String> {
-    #[cfg(target_arch = "wasm32")]
-    {
-        let storage = get_local_storage()?;
-        clear_auth_tokens_internal(&storage)?;
-        tracing::info!("Tokens cleared from storage");
-    }
-
-    #[cfg(not(target_arch = "wasm32"))]
-    {
-        clear_tokens_file().await?;
-    }
-
-    Ok(())
+    token_store()?.clear()
 }
 
-#[cfg(target_arch = "wasm32")]
-fn get_local_storage() -> Result<Storage, String> {
-    let window = window().ok_or("No window available")?;
-    window
-        .local_storage()
-        .map_err(|e| format!("Failed to access localStorage: {:?}", e))?
-        .ok_or("localStorage not available".to_string())
+#[derive(Deserialize)]
+struct JwtClaims {
+    exp: Option<u64>,
 }
 
-#[cfg(target_arch = "wasm32")]
-fn clear_auth_tokens_internal(storage: &Storage) -> Result<(), String> {
-    storage
-        .remove_item(TOKEN_STORAGE_KEY)
-        .map_err(|e| format!("Failed to clear tokens: {:?}", e))?;
+// Read the `exp` claim from a JWT without verifying its signature
+fn decode_jwt_expiry(jwt: &str) -> Option<u64> {
+    use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
 
-    storage
-        .remove_item(TOKEN_EXPIRY_KEY)
-        .map_err(|e| format!("Failed to clear token expiry: {:?}", e))?;
+    let jwt = jwt.strip_prefix("Bearer ").unwrap_or(jwt);
+    let payload = jwt.split('.').nth(1)?;
+    let bytes = URL_SAFE_NO_PAD.decode(payload.trim_end_matches('=')).ok()?;
+    serde_json::from_slice::<JwtClaims>(&bytes).ok()?.exp
+}
 
-    Ok(())
+// Effective expiry of a session: the JWT `exp` or midnight IST, when Angel ends
+// every session regardless of the JWT lifetime, whichever comes first
+fn token_expiry(tokens: &AuthTokens) -> u64 {
+    let jwt_expiry = decode_jwt_expiry(&tokens.jwt_token);
+    if jwt_expiry.is_none() {
+        tracing::warn!("JWT has no readable exp claim, using the daily session cutoff");
+    }
+    session_expiry(jwt_expiry, now_secs())
 }
 
-// File-based storage for mobile/desktop platforms
-#[cfg(not(target_arch = "wasm32"))]
-async fn store_tokens_file(tokens: &AuthTokens) -> Result<(), String> {
-    use std::fs;
-
-
-    let app_dir = get_app_data_dir()?;
-    fs::create_dir_all(&app_dir).map_err(|e| format!("Failed to create app directory: {}", e))?;
-    
-    let tokens_path = app_dir.join("auth_tokens.json");
-    let expiry_path = app_dir.join("auth_expiry.txt");
-    
-    // Store tokens
-    let tokens_json = serde_json::to_string(tokens)
-        .map_err(|e| format!("Failed to serialize tokens: {}", e))?;
-    fs::write(&tokens_path, tokens_json)
-        .map_err(|e| format!("Failed to write tokens file: {}", e))?;
-    
-    // Store expiry (24 hours from now)
-    let now = std::time::SystemTime::now()
-        .duration_since(std::time::UNIX_EPOCH)
-        .unwrap()
-        .as_secs();
-    let expiry = now + (24 * 60 * 60); // 24 hours
-    fs::write(&expiry_path, expiry.to_string())
-        .map_err(|e| format!("Failed to write expiry file: {}", e))?;
-    
-    tracing::info!("Tokens stored to file successfully");
-    Ok(())
+fn session_expiry(jwt_expiry: Option<u64>, now: u64) -> u64 {
+    let cutoff = next_ist_midnight(now);
+    jwt_expiry.map_or(cutoff, |exp| exp.min(cutoff))
 }
 
-#[cfg(not(target_arch = "wasm32"))]
-async fn load_tokens_file() -> Result<AuthTokens, String> {
-    use std::fs;
-    
-    let app_dir = get_app_data_dir()?;
-    let tokens_path = app_dir.join("auth_tokens.json");
-    let expiry_path = app_dir.join("auth_expiry.txt");
-    
-    // Check expiry first
-    if expiry_path.exists() {
-        let expiry_str = fs::read_to_string(&expiry_path)
-            .map_err(|e| format!("Failed to read expiry file: {}", e))?;
-        let expiry: u64 = expiry_str.trim().parse()
-            .map_err(|e| format!("Failed to parse expiry: {}", e))?;
-        
-        let now = std::time::SystemTime::now()
-            .duration_since(std::time::UNIX_EPOCH)
-            .unwrap()
-            .as_secs();
-        
-        if now > expiry {
-            // Tokens expired, clean up
-            let _ = fs::remove_file(&tokens_path);
-            let _ = fs::remove_file(&expiry_path);
-            return Err("Tokens expired".to_string());
-        }
-    }
-    
-    // Load tokens
-    if !tokens_path.exists() {
-        return Err("No tokens file found".to_string());
-    }
-    
-    let tokens_json = fs::read_to_string(&tokens_path)
-        .map_err(|e| format!("Failed to read tokens file: {}", e))?;
-    
-    let tokens: AuthTokens = serde_json::from_str(&tokens_json)
-        .map_err(|e| format!("Failed to parse tokens: {}", e))?;
-    
-    Ok(tokens)
+// Whether fresh tokens would outlast the current ones, they can't pass the daily cutoff
+fn refresh_extends_session(jwt_expiry: Option<u64>, now: u64) -> bool {
+    jwt_expiry.is_some_and(|exp| exp < next_ist_midnight(now))
 }
 
-#[cfg(not(target_arch = "wasm32"))]
-async fn clear_tokens_file() -> Result<(), String> {
-    use std::fs;
-    
-    let app_dir = get_app_data_dir()?;
-    let tokens_path = app_dir.join("auth_tokens.json");
-    let expiry_path = app_dir.join("auth_expiry.txt");
-    
-    if tokens_path.exists() {
-        fs::remove_file(&tokens_path)
-            .map_err(|e| format!("Failed to remove tokens file: {}", e))?;
-    }
-    
-    if expiry_path.exists() {
-        fs::remove_file(&expiry_path)
-            .map_err(|e| format!("Failed to remove expiry file: {}", e))?;
-    }
-    
-    tracing::info!("Token files cleared");
-    Ok(())
+fn is_jwt_expired(tokens: &AuthTokens) -> bool {
+    decode_jwt_expiry(&tokens.jwt_token).is_some_and(|exp| now_secs() > exp)
 }
 
-#[cfg(not(target_arch = "wasm32"))]
-fn get_app_data_dir() -> Result<std::path::PathBuf, String> {
-    use std::env;
-    
-    #[cfg(target_os = "android")]
-    {
-        // For Android, use app-specific storage
-        env::var("ANDROID_DATA")
-            .map(|data| std::path::PathBuf::from(data).join("angel_trading"))
-            .or_else(|_| {
-                env::var("HOME")
-                    .map(|home| std::path::PathBuf::from(home).join(".angel_trading"))
-            })
-            .map_err(|_| "Failed to determine app data directory".to_string())
-    }
-    
-    #[cfg(target_os = "ios")]
-    {
-        // For iOS, use Documents directory
-        env::var("HOME")
-            .map(|home| std::path::PathBuf::from(home).join("Documents").join("angel_trading"))
-            .map_err(|_| "Failed to determine app data directory".to_string())
-    }
-    
-    #[cfg(not(any(target_os = "android", target_os = "ios")))]
-    {
-        // For desktop platforms
-        env::var("HOME")
-            .map(|home| std::path::PathBuf::from(home).join(".angel_trading"))
-            .or_else(|_| {
-                env::var("USERPROFILE")
-                    .map(|profile| std::path::PathBuf::from(profile).join(".angel_trading"))
-            })
-            .map_err(|_| "Failed to determine app data directory".to_string())
+// Stored session expiry as unix seconds
+fn load_token_expiry() -> Option<u64> {
+    match token_store().and_then(|store| store.load()) {
+        Ok(session) => session.map(|session| session.expires_at),
+        Err(e) => {
+            tracing::error!("Failed to read token expiry: {}", e);
+            None
+        }
     }
 }
 
@@ -428,7 +322,7 @@ pub fn AuthProvider(children: Element) -> Element {
 
     // Initialize auth state from storage on mount
     use_effect({
-        let mut auth_context = auth_context.clone();
+        let mut auth_context = auth_context;
         move || {
             spawn(async move {
                 tracing::info!("Initializing auth from storage...");
@@ -438,9 +332,153 @@ pub fn AuthProvider(children: Element) -> Element {
         }
     });
 
+    // Load the profile whenever a session starts
+    use_effect(move || {
+        let mut auth_context = auth_context;
+        if auth_context.is_authenticated() && auth_context.profile.peek().is_none() {
+            spawn(async move {
+                let _ = auth_context.load_profile().await;
+            });
+        }
+    });
+
+    // Refresh tokens in the background before they expire
+    use_future(move || {
+        let mut auth_context = auth_context;
+        async move {
+            loop {
+                sleep(REFRESH_CHECK_INTERVAL).await;
+                if auth_context.is_authenticated() {
+                    if let Err(e) = auth_context.refresh_tokens_if_needed().await {
+                        tracing::warn!("Background token refresh: {}", e);
+                    }
+                }
+            }
+        }
+    });
+
     use_context_provider(|| auth_context);
 
     rsx! {
         {children}
     }
 }
+
+#[server(RefreshTokensServer)]
+async fn refresh_tokens_server(
+    tokens: AuthTokens,
+) -> Result<AuthTokens, ServerFnError<SmartApiError>> {
+    use crate::smartapi::SmartApiClient;
+
+    let data = SmartApiClient::shared()?
+        .generate_tokens(&tokens.jwt_token, &tokens.refresh_token)
+        .await?;
+
+    Ok(AuthTokens {
+        jwt_token: data.jwt_token.into_inner(),
+        refresh_token: data.refresh_token.into_inner(),
+        feed_token: data.feed_token.into_inner(),
+        user_id: tokens.user_id,
+    })
+}
+
+#[server(LogoutServer)]
+async fn logout_server(
+    authorization: String,
+    user_id: String,
+) -> Result<(), ServerFnError<SmartApiError>> {
+    use crate::smartapi::{bearer_token, SmartApiClient};
+
+    SmartApiClient::shared()?
+        .logout(bearer_token(&authorization), &user_id)
+        .await?;
+    Ok(())
+}
+
+#[server(GetProfileServer)]
+async fn get_profile_server(
+    authorization: String,
+) -> Result<UserProfile, ServerFnError<SmartApiError>> {
+    use crate::smartapi::{bearer_token, SmartApiClient};
+
+    Ok(SmartApiClient::shared()?
+        .get_profile(bearer_token(&authorization))
+        .await?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn auth_tokens_debug_is_redacted() {
+        let tokens = AuthTokens {
+            jwt_token: "jwt-secret".to_string(),
+            refresh_token: "refresh-secret".to_string(),
+            feed_token: "feed-secret".to_string(),
+            user_id: "A123456".to_string(),
+        };
+
+        for output in [
+            format!("{:?}", tokens),
+            format!("{:?}", AuthState::Authenticated(tokens.clone())),
+        ] {
+            assert!(!output.contains("secret"), "leaked: {}", output);
+            assert!(output.contains(REDACTED));
+            assert!(output.contains("A123456"));
+        }
+    }
+
+    fn jwt(claims: &str) -> String {
+        use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+
+        format!(
+            "{}.{}.signature",
+            URL_SAFE_NO_PAD.encode(r#"{"alg":"HS512"}"#),
+            URL_SAFE_NO_PAD.encode(claims)
+        )
+    }
+
+    #[test]
+    fn decodes_jwt_expiry() {
+        let token = jwt(r#"{"sub":"A123456","exp":1729103000}"#);
+        assert_eq!(decode_jwt_expiry(&token), Some(1_729_103_000));
+        assert_eq!(
+            decode_jwt_expiry(&format!("Bearer {}", token)),
+            Some(1_729_103_000)
+        );
+        assert_eq!(decode_jwt_expiry(&jwt(r#"{"sub":"A123456"}"#)), None);
+    }
+
+    #[test]
+    fn rejects_malformed_jwts() {
+        for token in ["", "not-a-jwt", "header.!!!.signature", "Bearer "] {
+            assert_eq!(decode_jwt_expiry(token), None, "{:?}", token);
+        }
+        assert_eq!(decode_jwt_expiry(&jwt("not json")), None);
+        assert_eq!(decode_jwt_expiry(&jwt(r#"{"exp":"tomorrow"}"#)), None);
+    }
+
+    #[test]
+    fn session_ends_at_the_jwt_expiry_or_midnight_ist() {
+        // 2024-10-16 15:00 IST, the cutoff is 2024-10-17 00:00 IST
+        let now = 1_729_071_000;
+        let cutoff = 1_729_103_400;
+
+        assert_eq!(session_expiry(Some(now + 3600), now), now + 3600);
+        assert_eq!(session_expiry(Some(cutoff + 3600), now), cutoff);
+        assert_eq!(session_expiry(None, now), cutoff);
+    }
+
+    #[test]
+    fn refreshes_only_when_the_jwt_ends_before_the_cutoff() {
+        // 2024-10-16 23:50 IST, ten minutes before the cutoff
+        let now = 1_729_102_800;
+        let cutoff = 1_729_103_400;
+
+        assert!(refresh_extends_session(Some(now + 300), now));
+        assert!(!refresh_extends_session(Some(cutoff), now));
+        assert!(!refresh_extends_session(Some(cutoff + 3600), now));
+        assert!(!refresh_extends_session(None, now));
+    }
+}
@@ -0,0 +1,700 @@
+//! Candlestick chart drawn as SVG, so the web, desktop and mobile renderers
+//! all show the same thing.
+//!
+//! History comes from [`get_candles_server`] and the newest bar follows the
+//! live feed through [`LiveSeries`].
+
+use crate::auth::use_auth;
+use crate::components::ErrorMessage;
+use crate::format::compact_quantity;
+use crate::history::{get_candles_server, CandleRequest};
+use crate::smartapi::{error_message, Candle, CandleInterval};
+use crate::smartstream::{use_feed_subscription, use_market_feed, ExchangeType, SubscriptionMode};
+use crate::time::{format_ist_minute, now_secs};
+use dioxus::prelude::*;
+use std::ops::Range;
+
+mod overlay;
+use overlay::Overlay;
+
+mod series;
+use series::LiveSeries;
+
+mod study;
+use study::{Study, StudyPlot};
+
+mod tracked;
+use tracked::ChartIndicators;
+
+mod viewport;
+use viewport::{price_ticks, Frame, Viewport};
+
+const HEIGHT: f64 = 360.0;
+// Price pane on top, volume or another study under it and the time axis at the bottom
+const PRICE_TOP: f64 = 8.0;
+const PRICE_BOTTOM: f64 = 256.0;
+const STUDY_TOP: f64 = 272.0;
+const STUDY_BOTTOM: f64 = 336.0;
+// Right gutter holding the price labels
+const AXIS_WIDTH: f64 = 64.0;
+// Used until the first resize event reports the real width
+const DEFAULT_WIDTH: f64 = 640.0;
+const PRICE_LABELS: usize = 5;
+const TIME_LABEL_SPACING: f64 = 120.0;
+const ZOOM_STEP: f64 = 1.25;
+const SECS_PER_DAY: u64 = 24 * 60 * 60;
+
+// Calendar days of history to load, a few hundred bars at each interval
+fn lookback_days(interval: CandleInterval) -> u64 {
+    match interval {
+        CandleInterval::OneMinute => 3,
+        CandleInterval::ThreeMinute => 7,
+        CandleInterval::FiveMinute => 10,
+        CandleInterval::TenMinute => 20,
+        CandleInterval::FifteenMinute => 30,
+        CandleInterval::ThirtyMinute => 60,
+        CandleInterval::OneHour => 120,
+        CandleInterval::OneDay => 730,
+    }
+}
+
+/// Candles for one instrument with overlays, a study pane and a crosshair.
+///
+/// Drag or swipe to pan, scroll or pinch to zoom.
+#[component]
+pub fn CandleChart(exchange: String, token: String) -> Element {
+    let auth = use_auth();
+    let feed = use_market_feed();
+    let instrument = use_memo(use_reactive!(|(exchange, token)| (exchange, token)));
+    let instruments = use_memo(move || {
+        let (exchange, token) = instrument();
+        ExchangeType::from_exchange(&exchange)
+            .map(|exchange_type| vec![(exchange_type, token)])
+            .unwrap_or_default()
+    });
+    use_feed_subscription(SubscriptionMode::Quote, instruments);
+
+    let mut interval = use_signal(|| CandleInterval::FiveMinute);
+    let mut series = use_signal(LiveSeries::default);
+    let mut indicators = use_signal(ChartIndicators::default);
+    let mut viewport = use_signal(Viewport::default);
+    let mut overlays = use_signal(|| vec![Overlay::Sma]);
+    let mut study = use_signal(|| Study::Volume);
+    let mut error = use_signal(|| None::<String>);
+    let mut is_loading = use_signal(|| false);
+    let mut width = use_signal(|| DEFAULT_WIDTH);
+    // Pointer position over the chart, for the crosshair
+    let mut pointer = use_signal(|| None::<(f64, f64)>);
+    // Last horizontal position while dragging, and finger spread while pinching
+    let mut drag_x = use_signal(|| None::<f64>);
+    let mut pinch = use_signal(|| None::<f64>);
+
+    // Reload history whenever the instrument or interval changes
+    use_effect(move || {
+        let (exchange, symbol_token) = instrument();
+        let selected = interval();
+        if !auth.is_authenticated() {
+            return;
+        }
+        spawn(async move {
+            let Some((_, authorization)) = auth.get_auth_header() else {
+                return;
+            };
+            is_loading.set(true);
+            let to = now_secs();
+            let request = CandleRequest {
+                exchange: exchange.clone(),
+                symbol_token: symbol_token.clone(),
+                interval: selected,
+                from: to.saturating_sub(lookback_days(selected) * SECS_PER_DAY),
+                to,
+            };
+            let result = get_candles_server(authorization, request).await;
+            // A newer request owns the chart once the user has switched away
+            if *instrument.peek() != (exchange, symbol_token) || *interval.peek() != selected {
+                return;
+            }
+            match result {
+                Ok(candles) => {
+                    indicators.set(ChartIndicators::new(&candles));
+                    series.set(LiveSeries::new(candles));
+                    viewport.set(Viewport::default());
+                    error.set(None);
+                }
+                Err(e) => {
+                    tracing::error!("Failed to load candles: {}", e);
+                    error.set(Some(error_message(&e)));
+                }
+            }
+            is_loading.set(false);
+        });
+    });
+
+    // Fold live ticks into the newest bar, this runs for every instrument's ticks
+    use_effect(move || {
+        let Some((exchange_type, token)) = instruments().first().cloned() else {
+            return;
+        };
+        let Some(tick) = feed.tick(exchange_type, &token) else {
+            return;
+        };
+        if series.peek().is_fresh(&tick) {
+            let mut series = series.write();
+            if series.apply(&tick, *interval.peek()) {
+                indicators.write().sync(&series.candles);
+            }
+        }
+    });
+
+    let current = series.read();
+    let candles = &current.candles;
+    let view = viewport();
+    let visible = view.range(candles.len());
+    let chart_width = width().max(AXIS_WIDTH + 1.0);
+    let bar_width = (chart_width - AXIS_WIDTH) / view.count.round().max(1.0);
+    let current_indicators = indicators.read();
+    let lines: Vec<_> = overlays()
+        .into_iter()
+        .flat_map(|overlay| overlay.lines(&current_indicators, visible.clone()))
+        .collect();
+
+    let price = price_frame(candles, &lines, visible.clone(), bar_width);
+    let max_volume = candles[visible.clone()]
+        .iter()
+        .map(|candle| candle.volume)
+        .max()
+        .unwrap_or(0);
+    let volume = Frame {
+        low: 0.0,
+        high: max_volume as f64,
+        top: STUDY_TOP,
+        bottom: STUDY_BOTTOM,
+        ..price
+    };
+
+    let bars = bar_shapes(candles, visible.clone(), &price, &volume);
+    let paths: Vec<(&'static str, String)> = lines
+        .iter()
+        .map(|(class, values)| (*class, line_path(&price, visible.start, values)))
+        .collect();
+    let show_volume = study() == Study::Volume;
+    let lower_pane = study()
+        .plot(&current_indicators, visible.clone())
+        .map(|plot| study_shapes(&plot, visible.start, &price))
+        .unwrap_or_default();
+    let price_labels: Vec<(f64, String)> = price_ticks(price.low, price.high, PRICE_LABELS)
+        .into_iter()
+        .map(|value| (price.y(value), format!("{:.2}", value)))
+        .collect();
+    let time_labels = time_labels(candles, visible.clone(), &price, interval());
+
+    let hovered = pointer()
+        .filter(|_| !visible.is_empty())
+        .map(|(x, y)| (price.index_at(x).min(visible.end - 1), y));
+    let legend = hovered
+        .map(|(index, _)| index)
+        .or(visible.end.checked_sub(1))
+        .and_then(|index| candles.get(index))
+        .copied();
+    let last = candles.last().filter(|_| !visible.is_empty()).copied();
+
+    rsx! {
+        div { class: "mb-4 rounded-lg border border-zinc-100 p-4 dark:border-zinc-800",
+            div { class: "mb-2 flex flex-wrap items-center gap-1",
+                for option in CandleInterval::ALL {
+                    button {
+                        class: if interval() == option { "btn btn-xs btn-primary" } else { "btn btn-xs btn-ghost" },
+                        onclick: move |_| interval.set(option),
+                        "{option.label()}"
+                    }
+                }
+                span { class: "mx-1 h-4 border-l border-zinc-200 dark:border-zinc-700" }
+                for overlay in Overlay::ALL {
+                    button {
+                        class: if overlays().contains(&overlay) { "btn btn-xs btn-outline" } else { "btn btn-xs btn-ghost" },
+                        onclick: move |_| {
+                            let mut overlays = overlays.write();
+                            match overlays.iter().position(|selected| *selected == overlay) {
+                                Some(i) => {
+                                    overlays.remove(i);
+                                }
+                                None => overlays.push(overlay),
+                            }
+                        },
+                        "{overlay.label()}"
+                    }
+                }
+                span { class: "mx-1 h-4 border-l border-zinc-200 dark:border-zinc-700" }
+                for option in Study::ALL {
+                    button {
+                        class: if study() == option { "btn btn-xs btn-outline" } else { "btn btn-xs btn-ghost" },
+                        onclick: move |_| study.set(option),
+                        "{option.label()}"
+                    }
+                }
+                div { class: "ml-auto flex gap-1",
+                    button {
+                        class: "btn btn-xs btn-ghost",
+                        "aria-label": "Zoom out",
+                        onclick: move |_| viewport.write().zoom(ZOOM_STEP, series.peek().candles.len()),
+                        "−"
+                    }
+                    button {
+                        class: "btn btn-xs btn-ghost",
+                        "aria-label": "Zoom in",
+                        onclick: move |_| viewport.write().zoom(1.0 / ZOOM_STEP, series.peek().candles.len()),
+                        "+"
+                    }
+                    if !view.is_following() {
+                        button {
+                            class: "btn btn-xs btn-ghost",
+                            onclick: move |_| viewport.write().offset = 0.0,
+                            "Latest"
+                        }
+                    }
+                }
+            }
+
+            ErrorMessage { message: error() }
+
+            if let Some(candle) = legend {
+                div { class: "mb-1 flex flex-wrap gap-x-3 text-xs text-gray-500",
+                    span { "{format_ist_minute(candle.time)}" }
+                    span { "O {candle.open:.2}" }
+                    span { "H {candle.high:.2}" }
+                    span { "L {candle.low:.2}" }
+                    span { class: if candle.close >= candle.open { "text-green-600" } else { "text-red-600" },
+                        "C {candle.close:.2}"
+                    }
+                    span { "V {compact_quantity(candle.volume)}" }
+                }
+            }
+
+            div {
+                class: "relative w-full",
+                onresize: move |event: ResizeEvent| {
+                    if let Ok(size) = event.get_content_box_size() {
+                        width.set(size.width);
+                    }
+                },
+                if visible.is_empty() {
+                    div { class: "flex h-40 items-center justify-center text-sm text-gray-500",
+                        if is_loading() { "Loading candles..." } else { "No candles for this period" }
+                    }
+                } else {
+                    svg {
+                        class: "block w-full touch-none select-none",
+                        height: "{HEIGHT}",
+                        "viewBox": "0 0 {chart_width} {HEIGHT}",
+
+                        for (y, label) in price_labels {
+                            line {
+                                class: "stroke-zinc-100 dark:stroke-zinc-800",
+                                x1: "0",
+                                y1: "{y}",
+                                x2: "{chart_width - AXIS_WIDTH}",
+                                y2: "{y}",
+                            }
+                            text {
+                                class: "fill-gray-500 text-[10px]",
+                                x: "{chart_width - 4.0}",
+                                y: "{y + 3.0}",
+                                "text-anchor": "end",
+                                "{label}"
+                            }
+                        }
+                        for (x, label) in time_labels {
+                            text {
+                                class: "fill-gray-500 text-[10px]",
+                                x: "{x}",
+                                y: "{HEIGHT - 8.0}",
+                                "text-anchor": "middle",
+                                "{label}"
+                            }
+                        }
+
+                        for (y, label) in lower_pane.guides {
+                            line {
+                                class: "stroke-zinc-200 dark:stroke-zinc-700",
+                                x1: "0",
+                                y1: "{y}",
+                                x2: "{chart_width - AXIS_WIDTH}",
+                                y2: "{y}",
+                                "stroke-dasharray": "2 3",
+                            }
+                            text {
+                                class: "fill-gray-500 text-[10px]",
+                                x: "{chart_width - 4.0}",
+                                y: "{y + 3.0}",
+                                "text-anchor": "end",
+                                "{label}"
+                            }
+                        }
+                        for (x, y, height, positive) in lower_pane.bars {
+                            rect {
+                                class: if positive { "fill-green-600 opacity-40" } else { "fill-red-600 opacity-40" },
+                                x: "{x}",
+                                y: "{y}",
+                                width: "{bar_width * 0.7}",
+                                height: "{height}",
+                            }
+                        }
+                        for (class, d) in lower_pane.paths {
+                            path {
+                                class: "{class}",
+                                d: "{d}",
+                                fill: "none",
+                                "stroke-width": "1.5",
+                            }
+                        }
+
+                        for bar in bars {
+                            if show_volume {
+                                rect {
+                                    class: if bar.rising { "fill-green-600 opacity-40" } else { "fill-red-600 opacity-40" },
+                                    x: "{bar.x - bar.half_width}",
+                                    y: "{bar.volume_top}",
+                                    width: "{bar.half_width * 2.0}",
+                                    height: "{STUDY_BOTTOM - bar.volume_top}",
+                                }
+                            }
+                            line {
+                                class: if bar.rising { "stroke-green-600" } else { "stroke-red-600" },
+                                x1: "{bar.x}",
+                                y1: "{bar.high}",
+                                x2: "{bar.x}",
+                                y2: "{bar.low}",
+                            }
+                            rect {
+                                class: if bar.rising { "fill-green-600" } else { "fill-red-600" },
+                                x: "{bar.x - bar.half_width}",
+                                y: "{bar.body_top}",
+                                width: "{bar.half_width * 2.0}",
+                                height: "{bar.body_height}",
+                            }
+                        }
+
+                        for (class, d) in paths {
+                            path {
+                                class: "{class}",
+                                d: "{d}",
+                                fill: "none",
+                                "stroke-width": "1.5",
+                            }
+                        }
+
+                        if let Some(candle) = last {
+                            line {
+                                class: if candle.close >= candle.open { "stroke-green-600" } else { "stroke-red-600" },
+                                x1: "0",
+                                y1: "{price.y(candle.close)}",
+                                x2: "{chart_width - AXIS_WIDTH}",
+                                y2: "{price.y(candle.close)}",
+                                "stroke-dasharray": "2 3",
+                            }
+                            rect {
+                                class: if candle.close >= candle.open { "fill-green-600" } else { "fill-red-600" },
+                                x: "{chart_width - AXIS_WIDTH}",
+                                y: "{price.y(candle.close) - 8.0}",
+                                width: "{AXIS_WIDTH}",
+                                height: "16",
+                            }
+                            text {
+                                class: "fill-white text-[10px]",
+                                x: "{chart_width - 4.0}",
+                                y: "{price.y(candle.close) + 3.0}",
+                                "text-anchor": "end",
+                                "{candle.close:.2}"
+                            }
+                        }
+
+                        if let Some((index, y)) = hovered {
+                            line {
+                                class: "stroke-gray-400",
+                                x1: "{price.x(index)}",
+                                y1: "{PRICE_TOP}",
+                                x2: "{price.x(index)}",
+                                y2: "{STUDY_BOTTOM}",
+                                "stroke-dasharray": "4 4",
+                            }
+                            if (PRICE_TOP..=PRICE_BOTTOM).contains(&y) {
+                                line {
+                                    class: "stroke-gray-400",
+                                    x1: "0",
+                                    y1: "{y}",
+                                    x2: "{chart_width - AXIS_WIDTH}",
+                                    y2: "{y}",
+                                    "stroke-dasharray": "4 4",
+                                }
+                                rect {
+                                    class: "fill-gray-700",
+                                    x: "{chart_width - AXIS_WIDTH}",
+                                    y: "{y - 8.0}",
+                                    width: "{AXIS_WIDTH}",
+                                    height: "16",
+                                }
+                                text {
+                                    class: "fill-white text-[10px]",
+                                    x: "{chart_width - 4.0}",
+                                    y: "{y + 3.0}",
+                                    "text-anchor": "end",
+                                    "{price.value_at(y):.2}"
+                                }
+                            }
+                        }
+
+                        // Catches the pointer on top of everything, so coordinates are relative to the chart
+                        rect {
+                            x: "0",
+                            y: "0",
+                            width: "{chart_width}",
+                            height: "{HEIGHT}",
+                            fill: "transparent",
+                            onmousedown: move |event: MouseEvent| drag_x.set(Some(event.client_coordinates().x)),
+                            onmousemove: move |event: MouseEvent| {
+                                let point = event.element_coordinates();
+                                pointer.set(Some((point.x, point.y)));
+                                if let Some(last_x) = drag_x() {
+                                    let x = event.client_coordinates().x;
+                                    viewport.write().pan((x - last_x) / bar_width, series.peek().candles.len());
+                                    drag_x.set(Some(x));
+                                }
+                            },
+                            onmouseup: move |_| drag_x.set(None),
+                            onmouseleave: move |_| {
+                                drag_x.set(None);
+                                pointer.set(None);
+                            },
+                            onwheel: move |event: WheelEvent| {
+                                event.prevent_default();
+                                let factor = if event.delta().strip_units().y > 0.0 { ZOOM_STEP } else { 1.0 / ZOOM_STEP };
+                                viewport.write().zoom(factor, series.peek().candles.len());
+                            },
+                            ontouchstart: move |event: TouchEvent| {
+                                let points = touch_points(&event);
+                                drag_x.set(points.first().map(|point| point.0));
+                                pinch.set(spread(&points));
+                            },
+                            ontouchmove: move |event: TouchEvent| {
+                                let points = touch_points(&event);
+                                let len = series.peek().candles.len();
+                                if let (Some(previous), Some(current)) = (pinch(), spread(&points)) {
+                                    viewport.write().zoom(previous / current, len);
+                                    pinch.set(Some(current));
+                                } else if let (Some(last_x), Some(point)) = (drag_x(), points.first()) {
+                                    viewport.write().pan((point.0 - last_x) / bar_width, len);
+                                    drag_x.set(Some(point.0));
+                                }
+                            },
+                            ontouchend: move |_| {
+                                drag_x.set(None);
+                                pinch.set(None);
+                            },
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+// One candle and its volume bar in SVG coordinates
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct BarShape {
+    x: f64,
+    half_width: f64,
+    high: f64,
+    low: f64,
+    body_top: f64,
+    body_height: f64,
+    volume_top: f64,
+    rising: bool,
+}
+
+// Fits the visible candles and their overlay lines, with a little room above and below
+fn price_frame(
+    candles: &[Candle],
+    lines: &[(&'static str, Vec<Option<f64>>)],
+    visible: Range<usize>,
+    bar_width: f64,
+) -> Frame {
+    let (mut low, mut high) = (f64::MAX, f64::MIN);
+    for candle in &candles[visible.clone()] {
+        low = low.min(candle.low);
+        high = high.max(candle.high);
+    }
+    let overlay_values = lines.iter().flat_map(|(_, line)| line.iter().flatten());
+    for value in overlay_values {
+        low = low.min(*value);
+        high = high.max(*value);
+    }
+    let margin = if high > low { (high - low) * 0.05 } else { 1.0 };
+    Frame {
+        start: visible.start,
+        bar_width,
+        low: low - margin,
+        high: high + margin,
+        top: PRICE_TOP,
+        bottom: PRICE_BOTTOM,
+    }
+}
+
+fn bar_shapes(
+    candles: &[Candle],
+    visible: Range<usize>,
+    price: &Frame,
+    volume: &Frame,
+) -> Vec<BarShape> {
+    let half_width = (price.bar_width * 0.35).max(0.5);
+    visible
+        .map(|index| {
+            let candle = &candles[index];
+            let body_top = price.y(candle.open.max(candle.close));
+            let body_bottom = price.y(candle.open.min(candle.close));
+            BarShape {
+                x: price.x(index),
+                half_width,
+                high: price.y(candle.high),
+                low: price.y(candle.low),
+                body_top,
+                // Doji still get a visible line
+                body_height: (body_bottom - body_top).max(1.0),
+                volume_top: volume.y(candle.volume as f64),
+                rising: candle.close >= candle.open,
+            }
+        })
+        .collect()
+}
+
+// A study's lines, histogram and guides in SVG coordinates
+#[derive(Debug, Clone, Default, PartialEq)]
+struct StudyShapes {
+    paths: Vec<(&'static str, String)>,
+    // Left edge, top, height and whether the bar is above zero
+    bars: Vec<(f64, f64, f64, bool)>,
+    guides: Vec<(f64, String)>,
+}
+
+// Fits the study's visible values unless it has a fixed scale
+fn study_shapes(plot: &StudyPlot, start: usize, price: &Frame) -> StudyShapes {
+    let (low, high) = plot.range.unwrap_or_else(|| {
+        let (mut low, mut high) = (f64::MAX, f64::MIN);
+        let values = plot
+            .lines
+            .iter()
+            .flat_map(|(_, line)| line.iter())
+            .chain(&plot.histogram)
+            .flatten()
+            .chain(&plot.guides);
+        for value in values {
+            low = low.min(*value);
+            high = high.max(*value);
+        }
+        if low > high {
+            (0.0, 1.0)
+        } else {
+            (low, high)
+        }
+    });
+    let frame = Frame {
+        low,
+        high,
+        top: STUDY_TOP,
+        bottom: STUDY_BOTTOM,
+        ..*price
+    };
+    let zero = frame.y(0.0);
+
+    StudyShapes {
+        paths: plot
+            .lines
+            .iter()
+            .map(|(class, values)| (*class, line_path(&frame, start, values)))
+            .collect(),
+        bars: plot
+            .histogram
+            .iter()
+            .enumerate()
+            .filter_map(|(i, value)| {
+                let value = (*value)?;
+                let y = frame.y(value);
+                let x = frame.x(start + i) - frame.bar_width * 0.35;
+                Some((x, y.min(zero), (y - zero).abs(), value >= 0.0))
+            })
+            .collect(),
+        guides: plot
+            .guides
+            .iter()
+            .map(|guide| (frame.y(*guide), guide.to_string()))
+            .collect(),
+    }
+}
+
+// SVG path through the values, lifting the pen over gaps
+fn line_path(frame: &Frame, start: usize, values: &[Option<f64>]) -> String {
+    let mut d = String::new();
+    let mut pen_down = false;
+    for (i, value) in values.iter().enumerate() {
+        match value {
+            Some(value) => {
+                let command = if pen_down { 'L' } else { 'M' };
+                d.push_str(&format!(
+                    "{}{:.1},{:.1} ",
+                    command,
+                    frame.x(start + i),
+                    frame.y(*value)
+                ));
+                pen_down = true;
+            }
+            None => pen_down = false,
+        }
+    }
+    d
+}
+
+// Labels on a fixed bar grid so they slide with the candles when panning
+fn time_labels(
+    candles: &[Candle],
+    visible: Range<usize>,
+    frame: &Frame,
+    interval: CandleInterval,
+) -> Vec<(f64, String)> {
+    let step = (TIME_LABEL_SPACING / frame.bar_width).ceil().max(1.0) as usize;
+    let mut previous_day = None;
+    visible
+        .filter(|index| index % step == 0)
+        .map(|index| {
+            // `YYYY-MM-DD HH:MM`
+            let stamp = format_ist_minute(candles[index].time);
+            let (day, minute) = stamp.split_at(10);
+            let label = if interval == CandleInterval::OneDay {
+                day.to_string()
+            } else if previous_day.as_deref() != Some(day) {
+                day[5..].to_string()
+            } else {
+                minute.trim().to_string()
+            };
+            previous_day = Some(day.to_string());
+            (frame.x(index), label)
+        })
+        .collect()
+}
+
+fn touch_points(event: &TouchEvent) -> Vec<(f64, f64)> {
+    event
+        .touches()
+        .iter()
+        .map(|touch| {
+            let point = touch.client_coordinates();
+            (point.x, point.y)
+        })
+        .collect()
+}
+
+// Distance between the first two fingers
+fn spread(points: &[(f64, f64)]) -> Option<f64> {
+    match points {
+        [a, b, ..] => Some((a.0 - b.0).hypot(a.1 - b.1)),
+        _ => None,
+    }
+}
@@ -0,0 +1,93 @@
+use super::tracked::ChartIndicators;
+use crate::indicators::Trend;
+use std::ops::Range;
+
+/// Studies drawn over the price pane.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Overlay {
+    Sma,
+    Ema,
+    Vwap,
+    Bollinger,
+    Supertrend,
+    Pivots,
+}
+
+impl Overlay {
+    pub const ALL: [Self; 6] = [
+        Self::Sma,
+        Self::Ema,
+        Self::Vwap,
+        Self::Bollinger,
+        Self::Supertrend,
+        Self::Pivots,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Sma => "SMA 20",
+            Self::Ema => "EMA 20",
+            Self::Vwap => "VWAP",
+            Self::Bollinger => "BB 20,2",
+            Self::Supertrend => "ST 10,3",
+            Self::Pivots => "Pivots",
+        }
+    }
+
+    /// The overlay's lines over the bars in `range`, each with its Tailwind
+    /// stroke colour and `None` where the line breaks.
+    pub fn lines(
+        self,
+        indicators: &ChartIndicators,
+        range: Range<usize>,
+    ) -> Vec<(&'static str, Vec<Option<f64>>)> {
+        match self {
+            Self::Sma => vec![("stroke-amber-500", indicators.sma.values(range).to_vec())],
+            Self::Ema => vec![("stroke-sky-500", indicators.ema.values(range).to_vec())],
+            Self::Vwap => vec![("stroke-fuchsia-500", indicators.vwap.values(range).to_vec())],
+            Self::Bollinger => {
+                let bands = indicators.bollinger.values(range);
+                vec![
+                    ("stroke-violet-400", line(bands, |band| band.upper)),
+                    ("stroke-violet-400", line(bands, |band| band.middle)),
+                    ("stroke-violet-400", line(bands, |band| band.lower)),
+                ]
+            }
+            Self::Supertrend => {
+                let values = indicators.supertrend.values(range);
+                // Split by trend so each stretch gets its own colour
+                let stretch = |trend: Trend| {
+                    values
+                        .iter()
+                        .map(|value| {
+                            value
+                                .filter(|value| value.trend == trend)
+                                .map(|value| value.value)
+                        })
+                        .collect()
+                };
+                vec![
+                    ("stroke-green-600", stretch(Trend::Up)),
+                    ("stroke-red-600", stretch(Trend::Down)),
+                ]
+            }
+            Self::Pivots => {
+                let levels = indicators.pivots.values(range);
+                vec![
+                    ("stroke-gray-500", line(levels, |levels| levels.pivot)),
+                    ("stroke-red-400", line(levels, |levels| levels.r1)),
+                    ("stroke-red-400", line(levels, |levels| levels.r2)),
+                    ("stroke-red-400", line(levels, |levels| levels.r3)),
+                    ("stroke-green-500", line(levels, |levels| levels.s1)),
+                    ("stroke-green-500", line(levels, |levels| levels.s2)),
+                    ("stroke-green-500", line(levels, |levels| levels.s3)),
+                ]
+            }
+        }
+    }
+}
+
+/// One field of an indicator with several outputs, as a line.
+pub fn line<T: Copy>(values: &[Option<T>], field: impl Fn(T) -> f64) -> Vec<Option<f64>> {
+    values.iter().map(|value| value.map(&field)).collect()
+}
@@ -0,0 +1,181 @@
+use crate::smartapi::{Candle, CandleInterval};
+use crate::smartstream::Tick;
+
+/// Historical candles with the newest bar kept current from live ticks.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct LiveSeries {
+    pub candles: Vec<Candle>,
+    // Ticks carry the day's running volume, so bars grow by the difference
+    day_volume: Option<i64>,
+    // Exchange timestamp and sequence number of the last tick merged
+    last_tick: Option<(i64, i64)>,
+}
+
+impl LiveSeries {
+    pub fn new(candles: Vec<Candle>) -> Self {
+        Self {
+            candles,
+            ..Self::default()
+        }
+    }
+
+    /// Whether `tick` has not been merged yet.
+    pub fn is_fresh(&self, tick: &Tick) -> bool {
+        self.last_tick != Some((tick.exchange_timestamp, tick.sequence_number))
+    }
+
+    /// Merge a tick into the last bar, opening a new bar once the interval rolls over.
+    ///
+    /// New bars stay on the grid of the loaded ones, so nothing is drawn until
+    /// history has arrived. Ticks older than the last bar are ignored.
+    pub fn apply(&mut self, tick: &Tick, interval: CandleInterval) -> bool {
+        let Some(last) = self.candles.last().copied() else {
+            return false;
+        };
+        let time = (tick.exchange_timestamp / 1000).max(0) as u64;
+        if !self.is_fresh(tick) || tick.ltp <= 0.0 || time < last.time {
+            return false;
+        }
+        self.last_tick = Some((tick.exchange_timestamp, tick.sequence_number));
+
+        let day_volume = tick.quote.as_ref().map(|quote| quote.volume);
+        let traded = match (self.day_volume, day_volume) {
+            (Some(previous), Some(current)) if current >= previous => current - previous,
+            // The running total restarts with each session
+            (Some(_), Some(current)) => current,
+            // History already counts everything up to the first tick
+            _ => 0,
+        };
+        if day_volume.is_some() {
+            self.day_volume = day_volume;
+        }
+
+        let elapsed = (time - last.time) / interval.secs();
+        if elapsed == 0 {
+            if let Some(bar) = self.candles.last_mut() {
+                bar.high = bar.high.max(tick.ltp);
+                bar.low = bar.low.min(tick.ltp);
+                bar.close = tick.ltp;
+                bar.volume += traded;
+            }
+        } else {
+            self.candles.push(Candle {
+                time: last.time + elapsed * interval.secs(),
+                open: tick.ltp,
+                high: tick.ltp,
+                low: tick.ltp,
+                close: tick.ltp,
+                volume: traded,
+            });
+        }
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // 2024-01-02 09:15 IST
+    const OPEN: u64 = 1_704_167_100;
+
+    fn candle(time: u64, close: f64, volume: i64) -> Candle {
+        Candle {
+            time,
+            open: close,
+            high: close,
+            low: close,
+            close,
+            volume,
+        }
+    }
+
+    fn tick(secs: u64, sequence_number: i64, ltp: f64, volume: i64) -> Tick {
+        serde_json::from_value(serde_json::json!({
+            "mode": "Quote",
+            "exchange_type": "NseCm",
+            "token": "3045",
+            "sequence_number": sequence_number,
+            "exchange_timestamp": secs * 1000,
+            "ltp": ltp,
+            "quote": {
+                "last_traded_quantity": 1,
+                "average_price": ltp,
+                "volume": volume,
+                "total_buy_quantity": 0.0,
+                "total_sell_quantity": 0.0,
+                "open": ltp,
+                "high": ltp,
+                "low": ltp,
+                "close": ltp
+            },
+            "snap_quote": null
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn tick_updates_the_last_bar() {
+        let mut series = LiveSeries::new(vec![candle(OPEN, 100.0, 500)]);
+        assert!(series.apply(
+            &tick(OPEN + 10, 1, 101.0, 10_000),
+            CandleInterval::FiveMinute
+        ));
+        assert!(series.apply(
+            &tick(OPEN + 20, 2, 99.5, 10_250),
+            CandleInterval::FiveMinute
+        ));
+
+        assert_eq!(series.candles.len(), 1);
+        let bar = series.candles[0];
+        assert_eq!(
+            (bar.open, bar.high, bar.low, bar.close),
+            (100.0, 101.0, 99.5, 99.5)
+        );
+        // The first tick only sets the baseline for volume
+        assert_eq!(bar.volume, 750);
+    }
+
+    #[test]
+    fn tick_opens_a_bar_on_the_same_grid() {
+        let mut series = LiveSeries::new(vec![candle(OPEN, 100.0, 500)]);
+        series.apply(
+            &tick(OPEN + 10, 1, 100.0, 10_000),
+            CandleInterval::FiveMinute,
+        );
+        // Nothing traded for a whole bar, this tick lands in the third one
+        series.apply(
+            &tick(OPEN + 640, 2, 102.0, 10_100),
+            CandleInterval::FiveMinute,
+        );
+
+        assert_eq!(series.candles.len(), 2);
+        assert_eq!(
+            series.candles[1],
+            Candle {
+                time: OPEN + 600,
+                open: 102.0,
+                high: 102.0,
+                low: 102.0,
+                close: 102.0,
+                volume: 100,
+            }
+        );
+    }
+
+    #[test]
+    fn ignores_stale_and_repeated_ticks() {
+        let mut series = LiveSeries::new(vec![candle(OPEN, 100.0, 500)]);
+        let live = tick(OPEN + 10, 1, 101.0, 10_000);
+        assert!(series.apply(&live, CandleInterval::OneMinute));
+        assert!(!series.is_fresh(&live));
+        assert!(!series.apply(&live, CandleInterval::OneMinute));
+        assert!(!series.apply(&tick(OPEN - 60, 2, 90.0, 9_000), CandleInterval::OneMinute));
+        assert_eq!(series.candles[0].low, 100.0);
+
+        // Without history there is no grid to place bars on
+        let mut empty = LiveSeries::default();
+        assert!(!empty.apply(&live, CandleInterval::OneMinute));
+        assert!(empty.candles.is_empty());
+    }
+}
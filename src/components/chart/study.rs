@@ -0,0 +1,68 @@
+use super::overlay::line;
+use super::tracked::ChartIndicators;
+use std::ops::Range;
+
+/// What the pane under the prices shows.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Study {
+    Volume,
+    Rsi,
+    Macd,
+    Atr,
+}
+
+/// Lines, bars and reference levels of a study other than volume.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct StudyPlot {
+    /// Tailwind stroke colour and one value per visible candle
+    pub lines: Vec<(&'static str, Vec<Option<f64>>)>,
+    /// Bars up or down from zero, one per visible candle
+    pub histogram: Vec<Option<f64>>,
+    /// Dashed levels, like the RSI's 30 and 70
+    pub guides: Vec<f64>,
+    /// Fixed scale, otherwise the pane fits the visible values
+    pub range: Option<(f64, f64)>,
+}
+
+impl Study {
+    pub const ALL: [Self; 4] = [Self::Volume, Self::Rsi, Self::Macd, Self::Atr];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Volume => "Volume",
+            Self::Rsi => "RSI 14",
+            Self::Macd => "MACD",
+            Self::Atr => "ATR 14",
+        }
+    }
+
+    /// The study over the bars in `range`, `None` for volume, which the chart
+    /// draws in the candles' colours.
+    pub fn plot(self, indicators: &ChartIndicators, range: Range<usize>) -> Option<StudyPlot> {
+        match self {
+            Self::Volume => None,
+            Self::Rsi => Some(StudyPlot {
+                lines: vec![("stroke-violet-500", indicators.rsi.values(range).to_vec())],
+                guides: vec![30.0, 70.0],
+                range: Some((0.0, 100.0)),
+                ..StudyPlot::default()
+            }),
+            Self::Macd => {
+                let values = indicators.macd.values(range);
+                Some(StudyPlot {
+                    lines: vec![
+                        ("stroke-sky-500", line(values, |value| value.macd)),
+                        ("stroke-amber-500", line(values, |value| value.signal)),
+                    ],
+                    histogram: line(values, |value| value.histogram),
+                    guides: vec![0.0],
+                    range: None,
+                })
+            }
+            Self::Atr => Some(StudyPlot {
+                lines: vec![("stroke-teal-500", indicators.atr.values(range).to_vec())],
+                ..StudyPlot::default()
+            }),
+        }
+    }
+}
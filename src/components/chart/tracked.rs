@@ -0,0 +1,135 @@
+use crate::indicators::{
+    Atr, Bollinger, Ema, Indicator, Live, Macd, Pivots, Rsi, Sma, Supertrend, Vwap,
+};
+use crate::smartapi::Candle;
+use std::ops::Range;
+
+const MOVING_AVERAGE_PERIOD: usize = 20;
+const RSI_PERIOD: usize = 14;
+const ATR_PERIOD: usize = 14;
+
+/// An indicator's value at every candle, kept current as ticks move the newest bar.
+#[derive(Debug, Clone)]
+pub struct Tracked<I: Indicator> {
+    live: Live<I>,
+    values: Vec<Option<I::Output>>,
+}
+
+impl<I: Indicator> Tracked<I> {
+    fn new(mut indicator: I, candles: &[Candle]) -> Self {
+        // History in one pass, the newest bar may still be forming
+        let (closed, newest) = candles.split_at(candles.len().saturating_sub(1));
+        let mut values = indicator.over(closed);
+        let mut live = Live::new(indicator);
+        values.extend(newest.iter().map(|candle| live.update(candle)));
+        Self { live, values }
+    }
+
+    // Redoes the newest bar seen so far, it may have moved since, then adds any new ones
+    fn sync(&mut self, candles: &[Candle]) {
+        let from = self.values.len().saturating_sub(1).min(candles.len());
+        self.values.truncate(from);
+        self.values.extend(
+            candles[from..]
+                .iter()
+                .map(|candle| self.live.update(candle)),
+        );
+    }
+
+    /// Values of the bars in `range`, fewer where it runs past the series.
+    pub fn values(&self, range: Range<usize>) -> &[Option<I::Output>] {
+        let end = range.end.min(self.values.len());
+        &self.values[range.start.min(end)..end]
+    }
+}
+
+/// Every indicator the chart can draw, over the chart's candles.
+#[derive(Debug, Clone)]
+pub struct ChartIndicators {
+    pub sma: Tracked<Sma>,
+    pub ema: Tracked<Ema>,
+    pub vwap: Tracked<Vwap>,
+    pub bollinger: Tracked<Bollinger>,
+    pub supertrend: Tracked<Supertrend>,
+    pub pivots: Tracked<Pivots>,
+    pub rsi: Tracked<Rsi>,
+    pub macd: Tracked<Macd>,
+    pub atr: Tracked<Atr>,
+}
+
+impl ChartIndicators {
+    pub fn new(candles: &[Candle]) -> Self {
+        Self {
+            sma: Tracked::new(Sma::new(MOVING_AVERAGE_PERIOD), candles),
+            ema: Tracked::new(Ema::new(MOVING_AVERAGE_PERIOD), candles),
+            vwap: Tracked::new(Vwap::new(), candles),
+            bollinger: Tracked::new(Bollinger::default(), candles),
+            supertrend: Tracked::new(Supertrend::default(), candles),
+            pivots: Tracked::new(Pivots::new(), candles),
+            rsi: Tracked::new(Rsi::new(RSI_PERIOD), candles),
+            macd: Tracked::new(Macd::default(), candles),
+            atr: Tracked::new(Atr::new(ATR_PERIOD), candles),
+        }
+    }
+
+    /// Catch up after ticks changed the newest bar or opened new ones.
+    ///
+    /// Only bars from the last one seen onwards are computed, so `candles`
+    /// must be the series this was created with, grown at the end.
+    pub fn sync(&mut self, candles: &[Candle]) {
+        self.sma.sync(candles);
+        self.ema.sync(candles);
+        self.vwap.sync(candles);
+        self.bollinger.sync(candles);
+        self.supertrend.sync(candles);
+        self.pivots.sync(candles);
+        self.rsi.sync(candles);
+        self.macd.sync(candles);
+        self.atr.sync(candles);
+    }
+}
+
+impl Default for ChartIndicators {
+    fn default() -> Self {
+        Self::new(&[])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn candle(time: u64, close: f64) -> Candle {
+        Candle {
+            time,
+            open: close,
+            high: close + 1.0,
+            low: close - 1.0,
+            close,
+            volume: 100,
+        }
+    }
+
+    #[test]
+    fn sync_follows_the_forming_bar() {
+        let mut candles: Vec<Candle> = (0..30)
+            .map(|i| candle(1_704_167_100 + i * 60, 100.0 + i as f64))
+            .collect();
+        let mut indicators = ChartIndicators::new(&candles);
+
+        // Ticks move the last bar, then a new one opens
+        candles.last_mut().unwrap().close = 90.0;
+        indicators.sync(&candles);
+        candles.push(candle(1_704_167_100 + 30 * 60, 95.0));
+        indicators.sync(&candles);
+
+        let expected = ChartIndicators::new(&candles);
+        assert_eq!(indicators.sma.values(0..31), expected.sma.values(0..31));
+        assert_eq!(indicators.rsi.values(0..31), expected.rsi.values(0..31));
+        assert_eq!(
+            indicators.supertrend.values(0..31),
+            expected.supertrend.values(0..31)
+        );
+        assert_eq!(indicators.sma.values(25..40).len(), 6);
+    }
+}
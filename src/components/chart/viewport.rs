@@ -0,0 +1,185 @@
+use std::ops::Range;
+
+// Fewest bars a zoom can show, below this candles turn into blocks
+const MIN_BARS: f64 = 20.0;
+const DEFAULT_BARS: f64 = 80.0;
+
+/// Which bars are on screen: `count` bars ending `offset` bars before the newest.
+///
+/// Kept fractional so slow drags and pinches still move the chart.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Viewport {
+    pub offset: f64,
+    pub count: f64,
+}
+
+impl Default for Viewport {
+    fn default() -> Self {
+        Self {
+            offset: 0.0,
+            count: DEFAULT_BARS,
+        }
+    }
+}
+
+impl Viewport {
+    /// Indices of the visible bars in a series of `len`.
+    pub fn range(&self, len: usize) -> Range<usize> {
+        let end = len.saturating_sub(self.offset.round() as usize);
+        end.saturating_sub(self.count.round() as usize)..end
+    }
+
+    /// Scroll back in time by `bars`, or forward when negative.
+    pub fn pan(&mut self, bars: f64, len: usize) {
+        self.offset = (self.offset + bars).clamp(0.0, max_offset(len));
+    }
+
+    /// Show `factor` times as many bars, keeping the newest visible bar in place.
+    pub fn zoom(&mut self, factor: f64, len: usize) {
+        self.count = (self.count * factor).clamp(MIN_BARS, (len as f64).max(MIN_BARS));
+        self.offset = self.offset.min(max_offset(len));
+    }
+
+    /// Whether new bars scroll into view as they open.
+    pub fn is_following(&self) -> bool {
+        self.offset < 0.5
+    }
+}
+
+// Always leave a screenful of bars when scrolled all the way back
+fn max_offset(len: usize) -> f64 {
+    (len as f64 - MIN_BARS).max(0.0)
+}
+
+/// Maps bars and prices to SVG coordinates for one pane.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Frame {
+    pub start: usize,
+    pub bar_width: f64,
+    pub low: f64,
+    pub high: f64,
+    pub top: f64,
+    pub bottom: f64,
+}
+
+impl Frame {
+    /// Horizontal centre of the bar at `index`.
+    pub fn x(&self, index: usize) -> f64 {
+        (index.saturating_sub(self.start) as f64 + 0.5) * self.bar_width
+    }
+
+    pub fn y(&self, value: f64) -> f64 {
+        if self.high <= self.low {
+            return (self.top + self.bottom) / 2.0;
+        }
+        self.top + (self.high - value) / (self.high - self.low) * (self.bottom - self.top)
+    }
+
+    /// Bar under a horizontal position, before clamping to the series.
+    pub fn index_at(&self, x: f64) -> usize {
+        self.start + (x.max(0.0) / self.bar_width) as usize
+    }
+
+    /// Value at a vertical position, the inverse of [`Frame::y`].
+    pub fn value_at(&self, y: f64) -> f64 {
+        if self.bottom <= self.top {
+            return self.low;
+        }
+        self.high - (y - self.top) / (self.bottom - self.top) * (self.high - self.low)
+    }
+}
+
+/// Round-numbered axis labels between `low` and `high`, about `target` of them.
+pub fn price_ticks(low: f64, high: f64, target: usize) -> Vec<f64> {
+    if high.is_nan() || low.is_nan() || high <= low || target == 0 {
+        return Vec::new();
+    }
+    let step = nice_step((high - low) / target as f64);
+    let first = (low / step).ceil() as i64;
+    let last = (high / step).floor() as i64;
+    (first..=last).map(|i| i as f64 * step).collect()
+}
+
+// Smallest of 1, 2, 5 or 10 times a power of ten covering `raw`
+fn nice_step(raw: f64) -> f64 {
+    let magnitude = 10f64.powf(raw.log10().floor());
+    let residual = raw / magnitude;
+    let nice = if residual <= 1.0 {
+        1.0
+    } else if residual <= 2.0 {
+        2.0
+    } else if residual <= 5.0 {
+        5.0
+    } else {
+        10.0
+    };
+    nice * magnitude
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn range_ends_offset_bars_before_the_newest() {
+        let viewport = Viewport {
+            offset: 10.0,
+            count: 30.0,
+        };
+        assert_eq!(viewport.range(100), 60..90);
+        assert_eq!(viewport.range(25), 0..15);
+        assert_eq!(viewport.range(0), 0..0);
+    }
+
+    #[test]
+    fn pan_stops_at_both_ends() {
+        let mut viewport = Viewport::default();
+        viewport.pan(-5.0, 100);
+        assert_eq!(viewport.offset, 0.0);
+
+        viewport.pan(500.0, 100);
+        assert_eq!(viewport.offset, 80.0);
+        assert_eq!(viewport.range(100).start, 0);
+        assert!(!viewport.is_following());
+    }
+
+    #[test]
+    fn zoom_clamps_the_bar_count() {
+        let mut viewport = Viewport::default();
+        viewport.zoom(0.01, 500);
+        assert_eq!(viewport.count, MIN_BARS);
+
+        viewport.zoom(1000.0, 500);
+        assert_eq!(viewport.count, 500.0);
+
+        // Short series still show the minimum width
+        viewport.zoom(1000.0, 5);
+        assert_eq!(viewport.count, MIN_BARS);
+    }
+
+    #[test]
+    fn frame_maps_both_ways() {
+        let frame = Frame {
+            start: 10,
+            bar_width: 8.0,
+            low: 100.0,
+            high: 200.0,
+            top: 0.0,
+            bottom: 200.0,
+        };
+        assert_eq!(frame.x(10), 4.0);
+        assert_eq!(frame.index_at(17.0), 12);
+        assert_eq!(frame.y(150.0), 100.0);
+        assert_eq!(frame.value_at(frame.y(175.0)), 175.0);
+    }
+
+    #[test]
+    fn price_ticks_are_round_numbers() {
+        assert_eq!(
+            price_ticks(101.3, 148.9, 5),
+            vec![110.0, 120.0, 130.0, 140.0]
+        );
+        assert_eq!(price_ticks(0.42, 0.58, 4), vec![0.45, 0.5, 0.55]);
+        assert!(price_ticks(5.0, 5.0, 4).is_empty());
+    }
+}
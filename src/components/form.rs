@@ -1,4 +1,10 @@
+use crate::instruments::{search_instruments_server, Instrument, InstrumentQuery};
+use crate::time::sleep;
 use dioxus::prelude::*;
+use std::time::Duration;
+
+// Pause in typing before the instrument index is queried
+const SYMBOL_SEARCH_DEBOUNCE: Duration = Duration::from_millis(200);
 
 #[component]
 pub fn SimpleForm(
@@ -59,12 +65,174 @@ pub fn Input(
     }
 }
 
+/// Autocompleting instrument picker backed by the server side instrument index.
+#[component]
+pub fn SymbolSearch(
+    field_name: String,
+    #[props(default = "Search symbol".to_string())] placeholder: String,
+    /// Initial text, e.g. the symbol already selected
+    #[props(default = "".to_string())]
+    value: String,
+    /// Only offer instruments from this exchange
+    #[props(default = None)]
+    exchange: Option<String>,
+    onselect: EventHandler<Instrument>,
+) -> Element {
+    let mut text = use_signal(|| value.clone());
+    let mut results = use_signal(Vec::<Instrument>::new);
+    let mut highlighted = use_signal(|| 0usize);
+    let mut is_open = use_signal(|| false);
+    // Bumped on every keystroke so only the latest search updates the list
+    let mut generation = use_signal(|| 0u64);
+
+    let mut select = move |instrument: Instrument| {
+        text.set(instrument.symbol.clone());
+        is_open.set(false);
+        results.set(Vec::new());
+        onselect.call(instrument);
+    };
+
+    let exchange_filter = exchange.clone();
+    let mut search = move |query_text: String| {
+        generation += 1;
+        let current = generation();
+        let exchange = exchange_filter.clone();
+        spawn(async move {
+            sleep(SYMBOL_SEARCH_DEBOUNCE).await;
+            if generation() != current || query_text.trim().is_empty() {
+                return;
+            }
+            let query = InstrumentQuery {
+                exchange,
+                ..InstrumentQuery::new(&query_text)
+            };
+            match search_instruments_server(query).await {
+                Ok(found) if generation() == current => {
+                    highlighted.set(0);
+                    is_open.set(!found.is_empty());
+                    results.set(found);
+                }
+                Ok(_) => {}
+                Err(e) => tracing::error!("Symbol search failed: {}", e),
+            }
+        });
+    };
+
+    rsx! {
+        div { class: "relative",
+            input {
+                r#type: "text",
+                name: field_name.clone(),
+                id: field_name,
+                class: "input",
+                placeholder: placeholder,
+                value: text(),
+                autocomplete: "off",
+                role: "combobox",
+                "aria-expanded": is_open(),
+                oninput: move |evt: FormEvent| {
+                    let value = evt.value();
+                    text.set(value.clone());
+                    if value.trim().is_empty() {
+                        is_open.set(false);
+                        results.set(Vec::new());
+                    }
+                    search(value);
+                },
+                onkeydown: move |evt: KeyboardEvent| {
+                    if !is_open() {
+                        return;
+                    }
+                    let count = results.peek().len();
+                    match evt.key() {
+                        Key::ArrowDown => {
+                            evt.prevent_default();
+                            highlighted.set((highlighted() + 1) % count);
+                        }
+                        Key::ArrowUp => {
+                            evt.prevent_default();
+                            highlighted.set((highlighted() + count - 1) % count);
+                        }
+                        Key::Enter => {
+                            // Pick the suggestion instead of submitting the surrounding form
+                            evt.prevent_default();
+                            let chosen = results.peek().get(highlighted()).cloned();
+                            if let Some(instrument) = chosen {
+                                select(instrument);
+                            }
+                        }
+                        Key::Escape => is_open.set(false),
+                        _ => {}
+                    }
+                },
+            }
+            if is_open() {
+                ul {
+                    role: "listbox",
+                    class: "menu absolute z-20 mt-1 max-h-72 w-full flex-nowrap overflow-y-auto rounded-box bg-base-100 shadow",
+                    for (i, instrument) in results().into_iter().enumerate() {
+                        li { key: "{instrument.exchange}:{instrument.token}",
+                            role: "option",
+                            "aria-selected": i == highlighted(),
+                            a {
+                                class: if i == highlighted() { "flex items-center gap-2 active" } else { "flex items-center gap-2" },
+                                onmouseenter: move |_| highlighted.set(i),
+                                // Mouse down fires before the input loses focus
+                                onmousedown: move |evt: MouseEvent| {
+                                    evt.prevent_default();
+                                    select(instrument.clone());
+                                },
+                                span { class: "flex-1",
+                                    div { class: "font-medium", "{instrument.symbol}" }
+                                    div { class: "text-xs text-gray-500", "{instrument.name}" }
+                                }
+                                span { class: "badge badge-outline badge-sm", "{instrument.exchange}" }
+                                span { class: "badge badge-ghost badge-sm", "{instrument.segment()}" }
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[component]
+pub fn Select(
+    field_name: String,
+    value: String,
+    /// `(value, label)` pairs
+    options: Vec<(String, String)>,
+    #[props(default = false)] disabled: bool,
+    onchange: EventHandler<FormEvent>,
+) -> Element {
+    rsx! {
+        div {
+            select {
+                name: field_name.clone(),
+                id: field_name,
+                class: "select",
+                disabled: disabled,
+                onchange: move |evt| onchange.call(evt),
+                for (option_value, label) in options {
+                    option {
+                        selected: option_value == value,
+                        value: option_value,
+                        "{label}"
+                    }
+                }
+            }
+        }
+    }
+}
+
 #[component]
 pub fn Button(
     children: Element,
     #[props(default = "button".to_string())] button_type: String,
     #[props(default = "".to_string())] class: String,
     #[props(default = false)] disabled: bool,
+    #[props(default = None)] onclick: Option<EventHandler<MouseEvent>>,
 ) -> Element {
     let base_classes = "phx-submit-loading:opacity-75 rounded-lg bg-zinc-900 hover:bg-zinc-700 py-2 px-3 text-sm font-semibold leading-6 text-white active:text-white/80";
     let combined_classes = if class.is_empty() {
@@ -78,6 +246,11 @@ pub fn Button(
             r#type: button_type,
             class: combined_classes,
             disabled: disabled,
+            onclick: move |evt| {
+                if let Some(onclick) = onclick {
+                    onclick.call(evt);
+                }
+            },
             {children}
         }
     }
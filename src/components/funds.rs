@@ -0,0 +1,112 @@
+use crate::auth::use_auth;
+use crate::components::ErrorMessage;
+use crate::format::{inr, pnl_class, signed_inr};
+use crate::smartapi::{error_message, Funds, SmartApiError};
+use crate::time::sleep;
+use dioxus::prelude::*;
+use std::time::Duration;
+
+const FUNDS_POLL_INTERVAL: Duration = Duration::from_secs(30);
+// Pull distance in pixels that triggers a refresh
+const PULL_TO_REFRESH_THRESHOLD: f64 = 80.0;
+
+/// Available funds and margin usage, refreshed periodically and on pull-down.
+#[component]
+pub fn FundsPanel() -> Element {
+    let auth = use_auth();
+    let mut funds = use_signal(|| None::<Funds>);
+    let mut error = use_signal(|| None::<String>);
+    let mut is_loading = use_signal(|| false);
+    let mut pull_start = use_signal(|| None::<f64>);
+
+    let refresh = move || {
+        spawn(async move {
+            let Some((_, authorization)) = auth.get_auth_header() else {
+                return;
+            };
+            is_loading.set(true);
+            match get_funds_server(authorization).await {
+                Ok(latest) => {
+                    funds.set(Some(latest));
+                    error.set(None);
+                }
+                Err(e) => {
+                    tracing::error!("Failed to load funds: {}", e);
+                    error.set(Some(error_message(&e)));
+                }
+            }
+            is_loading.set(false);
+        });
+    };
+
+    // Initial load and periodic polling
+    use_future(move || async move {
+        loop {
+            if auth.is_authenticated() {
+                refresh();
+            }
+            sleep(FUNDS_POLL_INTERVAL).await;
+        }
+    });
+
+    rsx! {
+        div {
+            class: "mb-4 rounded-lg border border-zinc-100 p-4 dark:border-zinc-800",
+            ontouchstart: move |event: TouchEvent| {
+                if let Some(touch) = event.touches().first() {
+                    pull_start.set(Some(touch.client_coordinates().y));
+                }
+            },
+            ontouchend: move |event: TouchEvent| {
+                let start = pull_start.take();
+                let end = event.touches_changed().first().map(|touch| touch.client_coordinates().y);
+                if let (Some(start), Some(end)) = (start, end) {
+                    if end - start > PULL_TO_REFRESH_THRESHOLD {
+                        refresh();
+                    }
+                }
+            },
+            div { class: "mb-3 flex items-center justify-between",
+                h2 { class: "text-lg font-semibold text-gray-900", "Funds" }
+                button {
+                    class: "btn btn-sm btn-ghost",
+                    disabled: is_loading(),
+                    onclick: move |_| refresh(),
+                    if is_loading() { "Refreshing..." } else { "Refresh" }
+                }
+            }
+
+            ErrorMessage { message: error() }
+
+            if let Some(funds) = funds() {
+                div { class: "grid grid-cols-2 gap-2 text-sm",
+                    span { class: "text-gray-500", "Available cash" }
+                    span { class: "text-right font-medium", "{inr(funds.available_cash)}" }
+                    span { class: "text-gray-500", "Available margin" }
+                    span { class: "text-right", "{inr(funds.net)}" }
+                    span { class: "text-gray-500", "Used margin" }
+                    span { class: "text-right", "{inr(funds.used_margin())}" }
+                    span { class: "text-gray-500", "Collateral" }
+                    span { class: "text-right", "{inr(funds.collateral)}" }
+                    span { class: "text-gray-500", "Realised M2M" }
+                    span { class: "text-right {pnl_class(funds.m2m_realized)}", "{signed_inr(funds.m2m_realized)}" }
+                    span { class: "text-gray-500", "Unrealised M2M" }
+                    span { class: "text-right {pnl_class(funds.m2m_unrealized)}", "{signed_inr(funds.m2m_unrealized)}" }
+                }
+            } else if is_loading() {
+                p { class: "text-sm text-gray-500", "Loading funds..." }
+            }
+        }
+    }
+}
+
+#[server(GetFundsServer)]
+pub async fn get_funds_server(
+    authorization: String,
+) -> Result<Funds, ServerFnError<SmartApiError>> {
+    use crate::smartapi::{bearer_token, SmartApiClient};
+
+    Ok(SmartApiClient::shared()?
+        .get_funds(bearer_token(&authorization))
+        .await?)
+}
@@ -1,11 +1,17 @@
-mod hero;
-pub use hero::Hero;
-
 mod navbar;
 pub use navbar::Navbar;
 
-mod echo;
-pub use echo::Echo;
+mod funds;
+pub use funds::{get_funds_server, FundsPanel};
+
+mod watchlist;
+pub use watchlist::WatchlistPanel;
+
+mod chart;
+pub use chart::CandleChart;
 
 mod form;
-pub use form::{Button, ErrorMessage, FormActions, Input, Label, SimpleForm};
+pub use form::{
+    Button, ErrorMessage, FormActions, Input, Label, Select, SimpleForm, SuccessMessage,
+    SymbolSearch, WarningMessage,
+};
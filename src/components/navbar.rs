@@ -7,18 +7,21 @@ pub fn Navbar() -> Element {
     let nav = use_navigator();
 
     let handle_logout = move |_| {
-        let mut auth_state = auth_state.clone();
-        let nav = nav.clone();
+        let mut auth_state = auth_state;
         spawn(async move {
+            // Local tokens are gone even when the server call fails, so always leave
             if let Err(e) = auth_state.logout().await {
                 tracing::error!("Logout failed: {}", e);
-            } else {
-                let _ = nav.push("/login");
             }
+            let _ = nav.push("/login");
         });
     };
 
     let is_authenticated = auth_state.is_authenticated();
+    let display_name = auth_state
+        .get_profile()
+        .map(|profile| profile.name)
+        .or_else(|| auth_state.get_tokens().map(|tokens| tokens.user_id));
 
     rsx! {
         header { class: "sticky top-0 z-10 bg-white dark:bg-gray-950 m-auto max-w-6xl px-4 sm:px-6 lg:px-8",
@@ -26,6 +29,16 @@ pub fn Navbar() -> Element {
                 div { class: "flex items-center gap-4 text-xl font-medium bg-clip-text bg-gradient-to-r from-red-400 to-blue-500 text-transparent",
                     a { href: "/", "Smartrade" }
                 }
+                if is_authenticated {
+                    nav { class: "flex items-center gap-3 font-medium text-zinc-700 dark:text-zinc-300",
+                        Link { to: "/dashboard", "Dashboard" }
+                        Link { to: "/holdings", "Holdings" }
+                        Link { to: "/positions", "Positions" }
+                        Link { to: "/orders", "Orders" }
+                        Link { to: "/trades", "Trades" }
+                        Link { to: "/order", "Trade" }
+                    }
+                }
                 div { class: "flex items-center gap-4 font-semibold leading-6 text-zinc-900",
                     if !is_authenticated {
                         a {
@@ -35,6 +48,10 @@ pub fn Navbar() -> Element {
                         }
                     }
 
+                    if let Some(name) = display_name {
+                        span { class: "px-2 py-1 text-zinc-600 dark:text-zinc-300", "{name}" }
+                    }
+
                     if is_authenticated {
                         button {
                             class: "px-2 py-1 text-red-500 hover:text-red-700 cursor-pointer",
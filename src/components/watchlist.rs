@@ -0,0 +1,386 @@
+use crate::auth::use_auth;
+use crate::components::{ErrorMessage, SymbolSearch};
+use crate::format::{inr, percent, pnl_class, signed_inr};
+use crate::instruments::Instrument;
+use crate::smartapi::{error_message, Quote, QuoteMode, QuoteRequest, SmartApiError};
+use crate::smartstream::{
+    use_feed_subscription, use_market_feed, ExchangeType, SubscriptionMode, Tick,
+};
+use crate::storage::{load_watchlists, save_watchlists};
+use crate::time::sleep;
+use crate::watchlists::{Watchlist, WatchlistItem};
+use crate::Route;
+use dioxus::prelude::*;
+use std::collections::HashMap;
+use std::time::Duration;
+
+const QUOTE_POLL_INTERVAL: Duration = Duration::from_secs(5);
+const DEFAULT_WATCHLIST_NAME: &str = "Watchlist 1";
+
+#[derive(Debug, Clone, PartialEq)]
+enum ListAction {
+    None,
+    Create(String),
+    Rename(String),
+    ConfirmDelete,
+}
+
+// Quotes are keyed by exchange and token since tokens repeat across exchanges
+fn quote_key(exchange: &str, token: &str) -> String {
+    format!("{}:{}", exchange, token)
+}
+
+/// Named watchlists with live quotes, saved on this device.
+#[component]
+pub fn WatchlistPanel() -> Element {
+    let auth = use_auth();
+    // Saved lists are read on mount, server rendering and hydration see the default
+    let mut lists = use_signal(|| vec![Watchlist::new(DEFAULT_WATCHLIST_NAME)]);
+    let mut active = use_signal(|| 0usize);
+    let mut action = use_signal(|| ListAction::None);
+    let mut quotes = use_signal(HashMap::<String, Quote>::new);
+    let mut error = use_signal(|| None::<String>);
+    let feed = use_market_feed();
+
+    // Stream prices for the open list, quotes fill in the day's change
+    let live = use_memo(move || {
+        lists()
+            .get(active())
+            .map(|list| {
+                list.items
+                    .iter()
+                    .filter_map(|item| {
+                        Some((
+                            ExchangeType::from_exchange(&item.exchange)?,
+                            item.token.clone(),
+                        ))
+                    })
+                    .collect()
+            })
+            .unwrap_or_default()
+    });
+    use_feed_subscription(SubscriptionMode::Ltp, live);
+
+    // Save after every change, keeping the selected tab in range
+    let mut persist = move || {
+        if let Err(e) = save_watchlists(&lists.peek()) {
+            tracing::error!("Failed to save watchlists: {}", e);
+            error.set(Some(e));
+        }
+        let count = lists.peek().len();
+        if active() >= count {
+            active.set(count.saturating_sub(1));
+        }
+    };
+
+    let refresh_quotes = move || {
+        spawn(async move {
+            let Some((_, authorization)) = auth.get_auth_header() else {
+                return;
+            };
+            let request = {
+                let lists = lists.peek();
+                let Some(list) = lists.get(*active.peek()) else {
+                    return;
+                };
+                QuoteRequest::new(
+                    QuoteMode::Full,
+                    list.items
+                        .iter()
+                        .map(|item| (item.exchange.as_str(), item.token.as_str())),
+                )
+            };
+            if request.is_empty() {
+                return;
+            }
+            match get_quotes_server(authorization, request).await {
+                Ok(latest) => {
+                    let mut quotes = quotes.write();
+                    for quote in latest {
+                        quotes.insert(quote_key(&quote.exchange, &quote.symbol_token), quote);
+                    }
+                }
+                Err(e) => {
+                    tracing::error!("Failed to load quotes: {}", e);
+                    error.set(Some(error_message(&e)));
+                }
+            }
+        });
+    };
+
+    // Load saved lists from this device on mount
+    use_effect(move || match load_watchlists() {
+        Ok(saved) if !saved.is_empty() => {
+            lists.set(saved);
+            refresh_quotes();
+        }
+        Ok(_) => {}
+        Err(e) => tracing::error!("Failed to load watchlists: {}", e),
+    });
+
+    use_future(move || async move {
+        loop {
+            if auth.is_authenticated() {
+                refresh_quotes();
+            }
+            sleep(QUOTE_POLL_INTERVAL).await;
+        }
+    });
+
+    let current = lists().get(active()).cloned();
+
+    rsx! {
+        div { class: "mb-4 rounded-lg border border-zinc-100 p-4 dark:border-zinc-800",
+            div { class: "mb-3 flex flex-wrap items-center justify-between gap-2",
+                div { class: "join",
+                    for (i, list) in lists().into_iter().enumerate() {
+                        button {
+                            key: "{i}",
+                            class: if i == active() { "btn btn-sm join-item btn-active" } else { "btn btn-sm join-item" },
+                            onclick: move |_| {
+                                active.set(i);
+                                action.set(ListAction::None);
+                                refresh_quotes();
+                            },
+                            "{list.name}"
+                        }
+                    }
+                    button {
+                        class: "btn btn-sm join-item",
+                        "aria-label": "New watchlist",
+                        onclick: move |_| action.set(ListAction::Create(String::new())),
+                        "+"
+                    }
+                }
+                if let Some(list) = &current {
+                    div { class: "flex items-center gap-1",
+                        if !live().is_empty() {
+                            if feed.is_connected() {
+                                span { class: "badge badge-success badge-sm", "Live" }
+                            } else {
+                                span { class: "badge badge-warning badge-sm", "Reconnecting" }
+                            }
+                        }
+                        button {
+                            class: "btn btn-xs btn-ghost",
+                            onclick: {
+                                let name = list.name.clone();
+                                move |_| action.set(ListAction::Rename(name.clone()))
+                            },
+                            "Rename"
+                        }
+                        if lists().len() > 1 {
+                            button {
+                                class: "btn btn-xs btn-ghost",
+                                onclick: move |_| action.set(ListAction::ConfirmDelete),
+                                "Delete"
+                            }
+                        }
+                    }
+                }
+            }
+
+            match action() {
+                ListAction::Create(name) | ListAction::Rename(name) => rsx! {
+                    form {
+                        class: "mb-3 flex gap-2",
+                        onsubmit: move |evt: FormEvent| {
+                            evt.prevent_default();
+                            let (creating, name) = match action() {
+                                ListAction::Create(name) => (true, name),
+                                ListAction::Rename(name) => (false, name),
+                                _ => return,
+                            };
+                            let name = name.trim().to_string();
+                            if name.is_empty() {
+                                return;
+                            }
+                            if creating {
+                                lists.write().push(Watchlist::new(&name));
+                                active.set(lists.peek().len() - 1);
+                            } else if let Some(list) = lists.write().get_mut(active()) {
+                                list.name = name;
+                            }
+                            persist();
+                            action.set(ListAction::None);
+                        },
+                        input {
+                            class: "input input-sm flex-1",
+                            placeholder: "Watchlist name",
+                            maxlength: "24",
+                            value: "{name}",
+                            oninput: move |evt: FormEvent| {
+                                let value = evt.value();
+                                action.set(match action() {
+                                    ListAction::Create(_) => ListAction::Create(value),
+                                    _ => ListAction::Rename(value),
+                                });
+                            },
+                        }
+                        button { r#type: "submit", class: "btn btn-sm", "Save" }
+                        button {
+                            r#type: "button",
+                            class: "btn btn-sm btn-ghost",
+                            onclick: move |_| action.set(ListAction::None),
+                            "Cancel"
+                        }
+                    }
+                },
+                ListAction::ConfirmDelete => rsx! {
+                    div { class: "mb-3 flex items-center gap-2 text-sm",
+                        span { "Delete this watchlist?" }
+                        button {
+                            class: "btn btn-xs btn-error",
+                            onclick: move |_| {
+                                if active() < lists.peek().len() {
+                                    lists.write().remove(active());
+                                }
+                                persist();
+                                action.set(ListAction::None);
+                            },
+                            "Delete"
+                        }
+                        button {
+                            class: "btn btn-xs btn-ghost",
+                            onclick: move |_| action.set(ListAction::None),
+                            "Cancel"
+                        }
+                    }
+                },
+                ListAction::None => rsx! {},
+            }
+
+            SymbolSearch {
+                field_name: "watchlist_search",
+                placeholder: "Add instrument",
+                onselect: move |instrument: Instrument| {
+                    let added = match lists.write().get_mut(active()) {
+                        Some(list) => list.add(WatchlistItem::from(instrument)),
+                        None => Ok(()),
+                    };
+                    error.set(added.err());
+                    persist();
+                    refresh_quotes();
+                },
+            }
+
+            ErrorMessage { message: error() }
+
+            if let Some(list) = current {
+                if list.items.is_empty() {
+                    p { class: "mt-3 text-sm text-gray-500", "Search for an instrument to add it" }
+                }
+                table { class: "table table-sm mt-2",
+                    tbody {
+                        for (i, item) in list.items.iter().enumerate() {
+                            WatchlistRow {
+                                key: "{item.exchange}:{item.token}",
+                                item: item.clone(),
+                                quote: quotes().get(&quote_key(&item.exchange, &item.token)).cloned(),
+                                tick: ExchangeType::from_exchange(&item.exchange)
+                                    .and_then(|exchange_type| feed.tick(exchange_type, &item.token)),
+                                on_move: move |offset: isize| {
+                                    if let Some(list) = lists.write().get_mut(active()) {
+                                        list.move_item(i, offset);
+                                    }
+                                    persist();
+                                },
+                                on_remove: move |_| {
+                                    if let Some(list) = lists.write().get_mut(active()) {
+                                        list.remove(i);
+                                    }
+                                    persist();
+                                },
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[component]
+fn WatchlistRow(
+    item: WatchlistItem,
+    quote: Option<Quote>,
+    tick: Option<Tick>,
+    on_move: EventHandler<isize>,
+    on_remove: EventHandler<()>,
+) -> Element {
+    // Prefer the streamed price, measuring the change from the quote's previous close
+    let ltp = tick
+        .as_ref()
+        .map(|tick| tick.ltp)
+        .or(quote.as_ref().map(|quote| quote.ltp));
+    let change = match (&quote, &tick) {
+        (Some(quote), Some(tick)) if quote.close > 0.0 => {
+            let change = tick.ltp - quote.close;
+            Some((change, change / quote.close * 100.0))
+        }
+        (Some(quote), _) => Some((quote.net_change, quote.percent_change)),
+        (None, _) => None,
+    };
+
+    rsx! {
+        tr {
+            td {
+                Link {
+                    class: "block font-medium hover:underline",
+                    to: Route::Chart {
+                        exchange: item.exchange.clone(),
+                        token: item.token.clone(),
+                        symbol: item.symbol.clone(),
+                    },
+                    "{item.symbol}"
+                }
+                span { class: "badge badge-outline badge-xs", "{item.exchange}" }
+            }
+            if let Some(ltp) = ltp {
+                td { class: "text-right", "{inr(ltp)}" }
+            } else {
+                td { class: "text-right text-gray-400", "—" }
+            }
+            if let Some((change, change_percent)) = change {
+                td { class: "text-right {pnl_class(change)}",
+                    div { "{signed_inr(change)}" }
+                    div { class: "text-xs", "{percent(change_percent)}" }
+                }
+            } else {
+                td {}
+            }
+            td { class: "text-right whitespace-nowrap",
+                button {
+                    class: "btn btn-xs btn-ghost",
+                    "aria-label": "Move up",
+                    onclick: move |_| on_move.call(-1),
+                    "↑"
+                }
+                button {
+                    class: "btn btn-xs btn-ghost",
+                    "aria-label": "Move down",
+                    onclick: move |_| on_move.call(1),
+                    "↓"
+                }
+                button {
+                    class: "btn btn-xs btn-ghost",
+                    "aria-label": "Remove",
+                    onclick: move |_| on_remove.call(()),
+                    "×"
+                }
+            }
+        }
+    }
+}
+
+#[server(GetQuotesServer)]
+async fn get_quotes_server(
+    authorization: String,
+    request: QuoteRequest,
+) -> Result<Vec<Quote>, ServerFnError<SmartApiError>> {
+    use crate::smartapi::{bearer_token, SmartApiClient};
+
+    Ok(SmartApiClient::shared()?
+        .get_quotes(bearer_token(&authorization), &request)
+        .await?)
+}
@@ -0,0 +1,320 @@
+//! Server side configuration for talking to SmartAPI.
+//!
+//! Values come from an optional TOML file (`ANGEL_CONFIG`, or
+//! `angel_trading.toml` in the working directory) overridden by environment
+//! variables. The config is validated once at startup so a bad value fails
+//! loudly instead of inside a server function.
+
+use crate::secret::{Secret, REDACTED};
+use serde::Deserialize;
+use std::env;
+use std::fmt;
+use std::net::{IpAddr, UdpSocket};
+use std::path::PathBuf;
+use std::sync::OnceLock;
+
+const DEFAULT_CONFIG_FILE: &str = "angel_trading.toml";
+const DEFAULT_API_BASE_URL: &str = "https://apiconnect.angelbroking.com/";
+const DEFAULT_STREAM_URL: &str = "wss://smartapisocket.angelone.in/smart-stream";
+
+static SERVER_CONFIG: OnceLock<ServerConfig> = OnceLock::new();
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ServerConfig {
+    pub api_key: Secret<String>,
+    pub local_ip: IpAddr,
+    pub public_ip: IpAddr,
+    pub mac_address: String,
+    /// Local path or URL of the instrument master, Angel's published file when unset
+    pub instrument_master: Option<String>,
+    /// Root of the REST API with a trailing slash, point it at the mock server to work offline
+    pub api_base_url: String,
+    /// SmartStream WebSocket URL
+    pub stream_url: String,
+}
+
+#[derive(Debug)]
+pub enum ConfigError {
+    File(PathBuf, String),
+    Missing(&'static str),
+    Invalid(&'static str, String),
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::File(path, e) => write!(f, "failed to read {}: {}", path.display(), e),
+            Self::Missing(key) => write!(f, "{} is not set", key),
+            Self::Invalid(key, value) => write!(f, "{} has an invalid value {:?}", key, value),
+        }
+    }
+}
+
+impl std::error::Error for ConfigError {}
+
+// Shape of the optional TOML file, keys mirror the environment variables
+#[derive(Debug, Default, Deserialize)]
+struct FileConfig {
+    api_key: Option<String>,
+    local_ip: Option<String>,
+    public_ip: Option<String>,
+    mac_address: Option<String>,
+    instrument_master: Option<String>,
+    api_base_url: Option<String>,
+    stream_url: Option<String>,
+}
+
+impl ServerConfig {
+    /// Load and validate the config, keeping it for the lifetime of the server.
+    pub fn init() -> Result<&'static ServerConfig, ConfigError> {
+        if let Some(config) = SERVER_CONFIG.get() {
+            return Ok(config);
+        }
+        let config = Self::load()?;
+        tracing::info!(
+            "Server config loaded (API {}, local IP {}, public IP {}, MAC {})",
+            config.api_base_url,
+            config.local_ip,
+            config.public_ip,
+            config.mac_address
+        );
+        Ok(SERVER_CONFIG.get_or_init(|| config))
+    }
+
+    fn load() -> Result<Self, ConfigError> {
+        Self::resolve(
+            load_file_config()?,
+            |key| env::var(key).ok(),
+            detect_local_ip,
+            detect_mac_address,
+        )
+    }
+
+    // Environment values win over the file, detection fills in what neither sets
+    fn resolve(
+        file: FileConfig,
+        env_var: impl Fn(&str) -> Option<String>,
+        detect_local_ip: fn() -> Option<IpAddr>,
+        detect_mac_address: fn() -> Option<String>,
+    ) -> Result<Self, ConfigError> {
+        let value = |key: &'static str, file_value: Option<String>| {
+            env_var(key)
+                .filter(|value| !value.trim().is_empty())
+                .or(file_value)
+                .map(|value| value.trim().to_string())
+        };
+
+        let api_key = value("API_KEY", file.api_key).ok_or(ConfigError::Missing("API_KEY"))?;
+        if !is_header_safe(&api_key) {
+            return Err(ConfigError::Invalid("API_KEY", REDACTED.to_string()));
+        }
+
+        let local_ip = match value("LOCAL_IP", file.local_ip) {
+            Some(ip) => parse_ip("LOCAL_IP", ip)?,
+            None => detect_local_ip().ok_or(ConfigError::Missing("LOCAL_IP"))?,
+        };
+
+        // Angel only logs the public IP, fall back to the local one when unknown
+        let public_ip = match value("PUBLIC_IP", file.public_ip) {
+            Some(ip) => parse_ip("PUBLIC_IP", ip)?,
+            None => {
+                tracing::warn!("PUBLIC_IP is not set, using the local IP");
+                local_ip
+            }
+        };
+
+        let mac_address = match value("MAC_ADDRESS", file.mac_address) {
+            Some(mac) if is_mac_address(&mac) => mac,
+            Some(mac) => return Err(ConfigError::Invalid("MAC_ADDRESS", mac)),
+            None => detect_mac_address().ok_or(ConfigError::Missing("MAC_ADDRESS"))?,
+        };
+
+        let api_base_url = value("API_BASE_URL", file.api_base_url)
+            .unwrap_or_else(|| DEFAULT_API_BASE_URL.to_string());
+        if !has_scheme(&api_base_url, &["http://", "https://"]) {
+            return Err(ConfigError::Invalid("API_BASE_URL", api_base_url));
+        }
+        let stream_url =
+            value("STREAM_URL", file.stream_url).unwrap_or_else(|| DEFAULT_STREAM_URL.to_string());
+        if !has_scheme(&stream_url, &["ws://", "wss://"]) {
+            return Err(ConfigError::Invalid("STREAM_URL", stream_url));
+        }
+
+        Ok(Self {
+            api_key: Secret::new(api_key),
+            local_ip,
+            public_ip,
+            mac_address,
+            instrument_master: value("INSTRUMENT_MASTER", file.instrument_master),
+            api_base_url: with_trailing_slash(api_base_url),
+            stream_url,
+        })
+    }
+}
+
+fn load_file_config() -> Result<FileConfig, ConfigError> {
+    let (path, required) = match env::var("ANGEL_CONFIG") {
+        Ok(path) => (PathBuf::from(path), true),
+        Err(_) => (PathBuf::from(DEFAULT_CONFIG_FILE), false),
+    };
+
+    if !path.exists() {
+        return if required {
+            Err(ConfigError::File(path, "file not found".to_string()))
+        } else {
+            Ok(FileConfig::default())
+        };
+    }
+
+    let contents = std::fs::read_to_string(&path)
+        .map_err(|e| ConfigError::File(path.clone(), e.to_string()))?;
+    toml::from_str(&contents).map_err(|e| ConfigError::File(path, e.to_string()))
+}
+
+fn parse_ip(key: &'static str, value: String) -> Result<IpAddr, ConfigError> {
+    value.parse().map_err(|_| ConfigError::Invalid(key, value))
+}
+
+fn is_header_safe(value: &str) -> bool {
+    !value.is_empty() && value.bytes().all(|b| b.is_ascii_graphic())
+}
+
+fn has_scheme(url: &str, schemes: &[&str]) -> bool {
+    schemes
+        .iter()
+        .any(|scheme| url.starts_with(scheme) && url.len() > scheme.len())
+}
+
+// Endpoint paths are appended directly to the base URL
+fn with_trailing_slash(mut url: String) -> String {
+    if !url.ends_with('/') {
+        url.push('/');
+    }
+    url
+}
+
+fn is_mac_address(value: &str) -> bool {
+    let parts: Vec<&str> = value.split([':', '-']).collect();
+    parts.len() == 6
+        && parts
+            .iter()
+            .all(|part| part.len() == 2 && part.chars().all(|c| c.is_ascii_hexdigit()))
+}
+
+// Address of the interface used for outbound traffic, no packets are sent
+fn detect_local_ip() -> Option<IpAddr> {
+    let socket = UdpSocket::bind("0.0.0.0:0").ok()?;
+    socket.connect("8.8.8.8:80").ok()?;
+    let ip = socket.local_addr().ok()?.ip();
+    tracing::info!("Detected local IP {}", ip);
+    Some(ip)
+}
+
+fn detect_mac_address() -> Option<String> {
+    let mac = mac_address::get_mac_address().ok()??.to_string();
+    tracing::info!("Detected MAC address {}", mac);
+    Some(mac)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    const FILE: &str = r#"
+        api_key = "file-key"
+        local_ip = "192.168.1.10"
+        public_ip = "203.0.113.7"
+        mac_address = "aa:bb:cc:dd:ee:ff"
+        api_base_url = "http://localhost:8080"
+    "#;
+
+    fn resolve(file: &str, env: &[(&str, &str)]) -> Result<ServerConfig, ConfigError> {
+        let env: HashMap<String, String> = env
+            .iter()
+            .map(|(key, value)| (key.to_string(), value.to_string()))
+            .collect();
+        ServerConfig::resolve(
+            toml::from_str(file).unwrap(),
+            |key| env.get(key).cloned(),
+            || Some(IpAddr::from([10, 0, 0, 2])),
+            || Some("02:00:00:00:00:01".to_string()),
+        )
+    }
+
+    #[test]
+    fn reads_the_toml_file() {
+        let config = resolve(FILE, &[]).unwrap();
+        assert_eq!(config.api_key.expose(), "file-key");
+        assert_eq!(config.local_ip, IpAddr::from([192, 168, 1, 10]));
+        assert_eq!(config.public_ip, IpAddr::from([203, 0, 113, 7]));
+        assert_eq!(config.mac_address, "aa:bb:cc:dd:ee:ff");
+        assert_eq!(config.api_base_url, "http://localhost:8080/");
+        assert_eq!(config.stream_url, DEFAULT_STREAM_URL);
+    }
+
+    #[test]
+    fn env_overrides_the_toml_file() {
+        let config = resolve(
+            FILE,
+            &[
+                ("API_KEY", "env-key"),
+                ("PUBLIC_IP", "198.51.100.1"),
+                ("STREAM_URL", "ws://localhost:8080/smart-stream"),
+                // Blank values don't hide the file's
+                ("LOCAL_IP", "  "),
+            ],
+        )
+        .unwrap();
+        assert_eq!(config.api_key.expose(), "env-key");
+        assert_eq!(config.public_ip, IpAddr::from([198, 51, 100, 1]));
+        assert_eq!(config.stream_url, "ws://localhost:8080/smart-stream");
+        assert_eq!(config.local_ip, IpAddr::from([192, 168, 1, 10]));
+    }
+
+    #[test]
+    fn requires_an_api_key() {
+        assert!(matches!(
+            resolve("", &[]),
+            Err(ConfigError::Missing("API_KEY"))
+        ));
+        assert!(matches!(
+            resolve("", &[("API_KEY", "has space")]),
+            Err(ConfigError::Invalid("API_KEY", _))
+        ));
+    }
+
+    #[test]
+    fn rejects_invalid_values() {
+        for (key, value) in [
+            ("API_BASE_URL", "apiconnect.angelbroking.com"),
+            ("API_BASE_URL", "https://"),
+            ("STREAM_URL", "https://smartapisocket.angelone.in"),
+            ("LOCAL_IP", "192.168.1"),
+            ("MAC_ADDRESS", "aa:bb:cc:dd:ee"),
+        ] {
+            match resolve(FILE, &[(key, value)]) {
+                Err(ConfigError::Invalid(invalid, _)) => assert_eq!(invalid, key),
+                other => panic!("{}={} gave {:?}", key, value, other),
+            }
+        }
+    }
+
+    #[test]
+    fn detects_missing_addresses() {
+        let config = resolve(r#"api_key = "file-key""#, &[]).unwrap();
+        assert_eq!(config.local_ip, IpAddr::from([10, 0, 0, 2]));
+        // Without a public IP the local one is reported
+        assert_eq!(config.public_ip, config.local_ip);
+        assert_eq!(config.mac_address, "02:00:00:00:00:01");
+        assert_eq!(config.api_base_url, DEFAULT_API_BASE_URL);
+
+        let undetected = ServerConfig::resolve(
+            toml::from_str(r#"api_key = "file-key""#).unwrap(),
+            |_| None,
+            || None,
+            || None,
+        );
+        assert!(matches!(undetected, Err(ConfigError::Missing("LOCAL_IP"))));
+    }
+}
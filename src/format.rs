@@ -0,0 +1,84 @@
+// Display helpers for money and percentages
+
+/// Format an amount in rupees with Indian digit grouping, e.g. `₹1,23,456.78`.
+pub fn inr(value: f64) -> String {
+    let sign = if value < 0.0 { "-" } else { "" };
+    let fixed = format!("{:.2}", value.abs());
+    let (whole, fraction) = fixed.split_once('.').unwrap_or((&fixed, "00"));
+
+    let mut grouped = String::new();
+    let (head, last_three) = whole.split_at(whole.len().saturating_sub(3));
+    for (i, digit) in head.chars().enumerate() {
+        if i > 0 && (head.len() - i) % 2 == 0 {
+            grouped.push(',');
+        }
+        grouped.push(digit);
+    }
+    if !head.is_empty() {
+        grouped.push(',');
+    }
+    grouped.push_str(last_three);
+
+    format!("{}₹{}.{}", sign, grouped, fraction)
+}
+
+/// Like [`inr`] but always shows the sign, for P&L figures.
+pub fn signed_inr(value: f64) -> String {
+    if value > 0.0 {
+        format!("+{}", inr(value))
+    } else {
+        inr(value)
+    }
+}
+
+pub fn percent(value: f64) -> String {
+    format!("{:+.2}%", value)
+}
+
+/// Short share count in lakhs and crores, e.g. `12.5L` for 12,50,000.
+pub fn compact_quantity(value: i64) -> String {
+    let amount = value.unsigned_abs() as f64;
+    let sign = if value < 0 { "-" } else { "" };
+    if amount >= 1e7 {
+        format!("{}{:.2}Cr", sign, amount / 1e7)
+    } else if amount >= 1e5 {
+        format!("{}{:.2}L", sign, amount / 1e5)
+    } else if amount >= 1e3 {
+        format!("{}{:.1}K", sign, amount / 1e3)
+    } else {
+        value.to_string()
+    }
+}
+
+/// Tailwind text colour for a P&L value.
+pub fn pnl_class(value: f64) -> &'static str {
+    if value > 0.0 {
+        "text-green-600"
+    } else if value < 0.0 {
+        "text-red-600"
+    } else {
+        "text-gray-600"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn inr_uses_indian_grouping() {
+        assert_eq!(inr(0.0), "₹0.00");
+        assert_eq!(inr(999.5), "₹999.50");
+        assert_eq!(inr(123456.78), "₹1,23,456.78");
+        assert_eq!(inr(-12345678.0), "-₹1,23,45,678.00");
+        assert_eq!(signed_inr(1500.0), "+₹1,500.00");
+    }
+
+    #[test]
+    fn compact_quantity_uses_lakhs_and_crores() {
+        assert_eq!(compact_quantity(950), "950");
+        assert_eq!(compact_quantity(12_300), "12.3K");
+        assert_eq!(compact_quantity(1_250_000), "12.50L");
+        assert_eq!(compact_quantity(-34_500_000), "-3.45Cr");
+    }
+}
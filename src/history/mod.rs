@@ -0,0 +1,42 @@
+//! Historical candles for charts.
+//!
+//! `getCandleData` serves a limited range per request and is throttled hard,
+//! so the server splits long ranges, paces its requests and caches finished
+//! days under the app data directory, keeping recently viewed series in
+//! memory so repeat chart views never reach Angel.
+
+use crate::smartapi::{Candle, CandleInterval, SmartApiError};
+use dioxus::prelude::*;
+use serde::{Deserialize, Serialize};
+
+#[cfg(feature = "server")]
+mod store;
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct CandleRequest {
+    pub exchange: String,
+    pub symbol_token: String,
+    pub interval: CandleInterval,
+    /// Unix time in seconds, inclusive
+    pub from: u64,
+    /// Unix time in seconds, inclusive
+    pub to: u64,
+}
+
+/// Candles opening between `from` and `to`, oldest first.
+#[server(GetCandlesServer)]
+pub async fn get_candles_server(
+    authorization: String,
+    request: CandleRequest,
+) -> Result<Vec<Candle>, ServerFnError<SmartApiError>> {
+    use crate::smartapi::bearer_token;
+
+    if request.from > request.to {
+        return Err(SmartApiError::Api {
+            code: "INVALID_RANGE".to_string(),
+            message: "The start of the range is after its end".to_string(),
+        }
+        .into());
+    }
+    Ok(store::candles(bearer_token(&authorization), &request).await?)
+}
@@ -0,0 +1,374 @@
+use super::CandleRequest;
+use crate::smartapi::{Candle, CandleApiRequest, CandleInterval, SmartApiClient, SmartApiError};
+use crate::storage::get_app_data_dir;
+use crate::time::{format_ist_minute, ist_day, ist_day_start, now_secs};
+use lru::LruCache;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, VecDeque};
+use std::num::NonZeroUsize;
+use std::path::{Path, PathBuf};
+use std::sync::LazyLock;
+use std::time::{Duration, Instant};
+use tokio::sync::Mutex;
+
+const CACHE_DIR: &str = "candles";
+// A minute series for a month is ~11k candles, this keeps memory in the tens of MB
+const MEMORY_SERIES: NonZeroUsize = NonZeroUsize::new(32).unwrap();
+// Today's candles are still forming, reuse them for this long
+const TODAY_TTL: Duration = Duration::from_secs(60);
+// Angel's published getCandleData limits, requests per window
+const RATE_LIMITS: [(Duration, usize); 2] =
+    [(Duration::from_secs(1), 3), (Duration::from_secs(60), 180)];
+const THROTTLED_RETRIES: usize = 3;
+const THROTTLED_BACKOFF: Duration = Duration::from_secs(1);
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+struct SeriesKey {
+    exchange: String,
+    token: String,
+    interval: CandleInterval,
+}
+
+impl SeriesKey {
+    fn file_name(&self) -> String {
+        let clean =
+            |value: &str| -> String { value.chars().filter(char::is_ascii_alphanumeric).collect() };
+        format!(
+            "{}-{}-{}.json",
+            clean(&self.exchange),
+            clean(&self.token),
+            self.interval.as_str()
+        )
+    }
+}
+
+/// Candles of one instrument and interval, bucketed by IST day.
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct Series {
+    /// Finished days only, an empty day had no trading
+    days: BTreeMap<u64, Vec<Candle>>,
+    #[serde(skip)]
+    today: Option<Today>,
+}
+
+#[derive(Debug)]
+struct Today {
+    day: u64,
+    fetched_at: Instant,
+    candles: Vec<Candle>,
+}
+
+impl Series {
+    // Days in the range that need fetching, today included when stale
+    fn missing(&self, first_day: u64, last_day: u64, today: u64) -> Vec<u64> {
+        let today_is_fresh = self
+            .today
+            .as_ref()
+            .is_some_and(|cached| cached.day == today && cached.fetched_at.elapsed() < TODAY_TTL);
+        (first_day..=last_day)
+            .filter(|day| {
+                if *day == today {
+                    !today_is_fresh
+                } else {
+                    !self.days.contains_key(day)
+                }
+            })
+            .collect()
+    }
+
+    fn candles(&self, request: &CandleRequest, first_day: u64, last_day: u64) -> Vec<Candle> {
+        let today = self.today.iter().map(|today| (today.day, &today.candles));
+        self.days
+            .range(first_day..=last_day)
+            .map(|(day, candles)| (*day, candles))
+            .chain(today.filter(|(day, _)| (first_day..=last_day).contains(day)))
+            .flat_map(|(_, candles)| candles.iter().copied())
+            .filter(|candle| (request.from..=request.to).contains(&candle.time))
+            .collect()
+    }
+}
+
+static SERIES: LazyLock<Mutex<LruCache<SeriesKey, Series>>> =
+    LazyLock::new(|| Mutex::new(LruCache::new(MEMORY_SERIES)));
+
+/// Candles for the request, from memory, disk or Angel in that order.
+pub async fn candles(
+    jwt_token: &str,
+    request: &CandleRequest,
+) -> Result<Vec<Candle>, SmartApiError> {
+    let key = SeriesKey {
+        exchange: request.exchange.clone(),
+        token: request.symbol_token.clone(),
+        interval: request.interval,
+    };
+    let today = ist_day(now_secs());
+    let first_day = ist_day(request.from);
+    let last_day = ist_day(request.to).min(today);
+    if first_day > last_day {
+        return Ok(Vec::new());
+    }
+
+    let missing = {
+        let mut series = SERIES.lock().await;
+        cached_series(&mut series, &key)
+            .await
+            .missing(first_day, last_day, today)
+    };
+
+    // Fetch without holding the cache, other charts keep reading meanwhile
+    let mut fetched = BTreeMap::new();
+    for (from_day, to_day) in plan_ranges(&missing, request.interval.max_days()) {
+        let candles = fetch(jwt_token, &key, from_day, to_day).await?;
+        for day in from_day..=to_day {
+            fetched.insert(day, Vec::new());
+        }
+        for candle in candles {
+            fetched
+                .entry(ist_day(candle.time))
+                .or_insert_with(Vec::new)
+                .push(candle);
+        }
+    }
+
+    let mut cache = SERIES.lock().await;
+    let series = cached_series(&mut cache, &key).await;
+    let mut finished_days = false;
+    for (day, candles) in fetched {
+        if day == today {
+            series.today = Some(Today {
+                day,
+                fetched_at: Instant::now(),
+                candles,
+            });
+        } else if missing.contains(&day) {
+            series.days.insert(day, candles);
+            finished_days = true;
+        }
+    }
+    if finished_days {
+        if let Err(e) = save(&key, series).await {
+            tracing::warn!("Failed to cache candles: {}", e);
+        }
+    }
+    Ok(series.candles(request, first_day, last_day))
+}
+
+// The in-memory series, loading it from disk when it isn't there
+async fn cached_series<'a>(
+    cache: &'a mut LruCache<SeriesKey, Series>,
+    key: &SeriesKey,
+) -> &'a mut Series {
+    if !cache.contains(key) {
+        let series = match load(key).await {
+            Ok(series) => series,
+            Err(e) => {
+                tracing::warn!("Ignoring the candle cache for {}: {}", key.file_name(), e);
+                Series::default()
+            }
+        };
+        cache.put(key.clone(), series);
+    }
+    cache.get_or_insert_mut(key.clone(), Series::default)
+}
+
+/// Group days into contiguous ranges of at most `max_days` days.
+fn plan_ranges(days: &[u64], max_days: u64) -> Vec<(u64, u64)> {
+    let mut ranges: Vec<(u64, u64)> = Vec::new();
+    for &day in days {
+        match ranges.last_mut() {
+            Some((start, end)) if day == *end + 1 && day - *start < max_days => *end = day,
+            _ => ranges.push((day, day)),
+        }
+    }
+    ranges
+}
+
+async fn fetch(
+    jwt_token: &str,
+    key: &SeriesKey,
+    from_day: u64,
+    to_day: u64,
+) -> Result<Vec<Candle>, SmartApiError> {
+    let request = CandleApiRequest {
+        exchange: key.exchange.clone(),
+        symbol_token: key.token.clone(),
+        interval: key.interval,
+        from_date: format_ist_minute(ist_day_start(from_day)),
+        // The last minute of the last day
+        to_date: format_ist_minute(ist_day_start(to_day + 1) - 60),
+    };
+    let client = SmartApiClient::shared()?;
+
+    let mut attempt = 0;
+    loop {
+        throttle().await;
+        match client.get_candle_data(jwt_token, &request).await {
+            Err(SmartApiError::RateLimited) if attempt < THROTTLED_RETRIES => {
+                attempt += 1;
+                let delay = THROTTLED_BACKOFF * attempt as u32;
+                tracing::warn!("Candle requests throttled, retrying in {:?}", delay);
+                tokio::time::sleep(delay).await;
+            }
+            result => return result,
+        }
+    }
+}
+
+/// Times of recent requests, to stay inside every rate limit window.
+#[derive(Default)]
+struct RateLimiter {
+    sent: VecDeque<Instant>,
+}
+
+impl RateLimiter {
+    // How long until another request fits in every window
+    fn delay(&self, now: Instant) -> Duration {
+        RATE_LIMITS
+            .iter()
+            .filter_map(|&(window, limit)| {
+                let recent: Vec<&Instant> = self
+                    .sent
+                    .iter()
+                    .filter(|sent| now.duration_since(**sent) < window)
+                    .collect();
+                // Wait for the request that makes room to age out of the window
+                let blocking = recent.len().checked_sub(limit)?;
+                Some(window - now.duration_since(*recent[blocking]))
+            })
+            .max()
+            .unwrap_or_default()
+    }
+
+    fn record(&mut self, now: Instant) {
+        let longest = RATE_LIMITS
+            .iter()
+            .map(|(window, _)| *window)
+            .max()
+            .unwrap_or_default();
+        self.sent.retain(|sent| now.duration_since(*sent) < longest);
+        self.sent.push_back(now);
+    }
+}
+
+static LIMITER: LazyLock<Mutex<RateLimiter>> = LazyLock::new(Default::default);
+
+// Requests queue up here, so concurrent charts share the budget
+async fn throttle() {
+    let mut limiter = LIMITER.lock().await;
+    loop {
+        let delay = limiter.delay(Instant::now());
+        if delay.is_zero() {
+            break;
+        }
+        tokio::time::sleep(delay).await;
+    }
+    limiter.record(Instant::now());
+}
+
+fn cache_path(key: &SeriesKey) -> Result<PathBuf, String> {
+    Ok(get_app_data_dir()?.join(CACHE_DIR).join(key.file_name()))
+}
+
+async fn load(key: &SeriesKey) -> Result<Series, String> {
+    let path = cache_path(key)?;
+    blocking(move || {
+        if !path.exists() {
+            return Ok(Series::default());
+        }
+        let bytes = std::fs::read(&path)
+            .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+        serde_json::from_slice(&bytes)
+            .map_err(|e| format!("Failed to parse {}: {}", path.display(), e))
+    })
+    .await
+}
+
+async fn save(key: &SeriesKey, series: &Series) -> Result<(), String> {
+    let path = cache_path(key)?;
+    let bytes =
+        serde_json::to_vec(series).map_err(|e| format!("Failed to encode candles: {}", e))?;
+    blocking(move || write_atomic(&path, &bytes)).await
+}
+
+async fn blocking<T, F>(f: F) -> Result<T, String>
+where
+    F: FnOnce() -> Result<T, String> + Send + 'static,
+    T: Send + 'static,
+{
+    tokio::task::spawn_blocking(f)
+        .await
+        .map_err(|e| format!("Candle cache task failed: {}", e))?
+}
+
+// Write then rename so a crash never leaves a truncated cache behind
+fn write_atomic(path: &Path, bytes: &[u8]) -> Result<(), String> {
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir)
+            .map_err(|e| format!("Failed to create {}: {}", dir.display(), e))?;
+    }
+    let tmp_path = path.with_extension("tmp");
+    std::fs::write(&tmp_path, bytes)
+        .map_err(|e| format!("Failed to write {}: {}", tmp_path.display(), e))?;
+    std::fs::rename(&tmp_path, path)
+        .map_err(|e| format!("Failed to write {}: {}", path.display(), e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn splits_missing_days_into_allowed_ranges() {
+        let days: Vec<u64> = (100..=170).chain([175, 176, 180]).collect();
+        assert_eq!(
+            plan_ranges(&days, 30),
+            vec![(100, 129), (130, 159), (160, 170), (175, 176), (180, 180)]
+        );
+        assert!(plan_ranges(&[], 30).is_empty());
+    }
+
+    #[test]
+    fn paces_requests_within_every_window() {
+        let mut limiter = RateLimiter::default();
+        let start = Instant::now();
+        for _ in 0..3 {
+            assert_eq!(limiter.delay(start), Duration::ZERO);
+            limiter.record(start);
+        }
+        assert_eq!(
+            limiter.delay(start + Duration::from_millis(400)),
+            Duration::from_millis(600)
+        );
+        assert_eq!(
+            limiter.delay(start + Duration::from_secs(1)),
+            Duration::ZERO
+        );
+    }
+
+    #[test]
+    fn returns_only_candles_in_the_range() {
+        let candle = |time| Candle {
+            time,
+            open: 1.0,
+            high: 1.0,
+            low: 1.0,
+            close: 1.0,
+            volume: 0,
+        };
+        let mut series = Series::default();
+        let open = ist_day_start(10) + 33_300;
+        series
+            .days
+            .insert(10, vec![candle(open), candle(open + 300)]);
+        series.days.insert(11, vec![]);
+        let request = CandleRequest {
+            exchange: "NSE".to_string(),
+            symbol_token: "3045".to_string(),
+            interval: CandleInterval::FiveMinute,
+            from: open + 100,
+            to: ist_day_start(12),
+        };
+        assert_eq!(series.candles(&request, 10, 12), vec![candle(open + 300)]);
+        assert_eq!(series.missing(10, 13, 13), vec![12, 13]);
+    }
+}
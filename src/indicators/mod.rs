@@ -0,0 +1,154 @@
+//! Technical indicators over candle series.
+//!
+//! Every indicator is a small state machine fed one closed bar at a time, so
+//! the same code runs over loaded history with [`Indicator::over`] and over
+//! live data with [`Live`], which keeps the value of the still-forming bar
+//! current as ticks move it.
+
+use crate::smartapi::Candle;
+
+mod moving_average;
+pub use moving_average::{Ema, Sma};
+
+mod momentum;
+pub use momentum::{Macd, Rsi};
+
+mod volatility;
+pub use volatility::{Atr, Bollinger, Supertrend, Trend};
+
+mod vwap;
+pub use vwap::Vwap;
+
+mod pivots;
+pub use pivots::Pivots;
+
+pub trait Indicator: Clone {
+    type Output: Copy;
+
+    /// Add the next closed bar, returning the value as of that bar or `None`
+    /// while there are too few bars.
+    fn next(&mut self, candle: &Candle) -> Option<Self::Output>;
+
+    /// Add a run of closed bars, returning the value as of each. The
+    /// indicator carries on from the last of them, so history can be loaded
+    /// in one go before following a live bar.
+    fn over(&mut self, candles: &[Candle]) -> Vec<Option<Self::Output>> {
+        candles.iter().map(|candle| self.next(candle)).collect()
+    }
+}
+
+/// Runs an indicator over bars that keep changing until they close.
+///
+/// Push the forming bar after every tick. The indicator only advances past a
+/// bar once one with a later `time` arrives, so repeated updates of the same
+/// bar never count twice.
+#[derive(Debug, Clone)]
+pub struct Live<I> {
+    closed: I,
+    forming: Option<Candle>,
+}
+
+impl<I: Indicator> Live<I> {
+    pub fn new(indicator: I) -> Self {
+        Self {
+            closed: indicator,
+            forming: None,
+        }
+    }
+
+    /// Value as of `candle`, the newest bar so far. Bars older than it are ignored.
+    pub fn update(&mut self, candle: &Candle) -> Option<I::Output> {
+        match self.forming {
+            Some(forming) if candle.time < forming.time => return None,
+            Some(forming) if candle.time > forming.time => {
+                self.closed.next(&forming);
+            }
+            _ => {}
+        }
+        self.forming = Some(*candle);
+        self.closed.clone().next(candle)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // 2024-01-02 09:15 IST
+    const OPEN: u64 = 1_704_167_100;
+    const HOUR: u64 = 60 * 60;
+    const DAY: u64 = 24 * HOUR;
+
+    // Closes from Wilder's RSI worked example, six hourly bars a day
+    const CLOSES: [f64; 33] = [
+        44.3389, 44.0902, 44.1497, 43.6124, 44.3278, 44.8264, 45.0955, 45.4245, 45.8433, 46.0826,
+        45.8931, 46.0328, 45.6140, 46.2820, 46.2820, 46.0028, 46.0328, 46.4116, 46.2222, 45.6439,
+        46.2122, 46.2521, 45.7137, 46.4515, 45.7835, 45.3548, 44.0288, 44.1783, 44.2181, 44.5672,
+        43.4205, 42.6628, 43.1314,
+    ];
+
+    pub fn candles() -> Vec<Candle> {
+        let mut open = CLOSES[0];
+        CLOSES
+            .iter()
+            .enumerate()
+            .map(|(i, &close)| {
+                let candle = Candle {
+                    time: OPEN + (i as u64 / 6) * DAY + (i as u64 % 6) * HOUR,
+                    open,
+                    high: open.max(close) + 0.25 + (i % 3) as f64 * 0.1,
+                    low: open.min(close) - 0.2 - (i % 4) as f64 * 0.05,
+                    close,
+                    volume: 1000 + (i as i64 * 37 % 11) * 100,
+                };
+                open = close;
+                candle
+            })
+            .collect()
+    }
+
+    pub fn assert_close(actual: Option<f64>, expected: f64) {
+        let actual = actual.expect("indicator has warmed up");
+        assert!(
+            (actual - expected).abs() < 1e-4,
+            "expected {}, got {}",
+            expected,
+            actual
+        );
+    }
+
+    #[test]
+    fn live_matches_closed_bars() {
+        let candles = candles();
+        let expected = Rsi::new(14).over(&candles);
+
+        let mut live = Live::new(Rsi::new(14));
+        for (candle, expected) in candles.iter().zip(&expected) {
+            // The bar opens flat and reaches its close over a few ticks
+            let mut forming = Candle {
+                high: candle.open,
+                low: candle.open,
+                close: candle.open,
+                ..*candle
+            };
+            for price in [candle.high, candle.low, candle.close] {
+                forming.high = forming.high.max(price);
+                forming.low = forming.low.min(price);
+                forming.close = price;
+                live.update(&forming);
+            }
+            assert_eq!(live.update(candle), *expected);
+        }
+    }
+
+    #[test]
+    fn live_ignores_older_bars() {
+        let candles = candles();
+        let mut live = Live::new(Sma::new(2));
+        live.update(&candles[0]);
+        let value = live.update(&candles[1]);
+        assert!(value.is_some());
+        assert_eq!(live.update(&candles[0]), None);
+        assert_eq!(live.update(&candles[1]), value);
+    }
+}
@@ -0,0 +1,148 @@
+use super::{Ema, Indicator};
+use crate::smartapi::Candle;
+
+/// Wilder's relative strength index, 0 to 100.
+#[derive(Debug, Clone)]
+pub struct Rsi {
+    period: usize,
+    previous_close: Option<f64>,
+    // Sums of the first `period` gains and losses, then Wilder's running averages
+    gain: f64,
+    loss: f64,
+    changes: usize,
+}
+
+impl Rsi {
+    pub fn new(period: usize) -> Self {
+        Self {
+            period: period.max(1),
+            previous_close: None,
+            gain: 0.0,
+            loss: 0.0,
+            changes: 0,
+        }
+    }
+}
+
+impl Indicator for Rsi {
+    type Output = f64;
+
+    fn next(&mut self, candle: &Candle) -> Option<f64> {
+        let previous = self.previous_close.replace(candle.close)?;
+        let change = candle.close - previous;
+        let (gain, loss) = (change.max(0.0), (-change).max(0.0));
+        let period = self.period as f64;
+
+        self.changes += 1;
+        if self.changes <= self.period {
+            self.gain += gain;
+            self.loss += loss;
+            if self.changes < self.period {
+                return None;
+            }
+            self.gain /= period;
+            self.loss /= period;
+        } else {
+            self.gain = (self.gain * (period - 1.0) + gain) / period;
+            self.loss = (self.loss * (period - 1.0) + loss) / period;
+        }
+
+        Some(if self.loss == 0.0 {
+            // A flat market sits in the middle rather than at the top
+            if self.gain == 0.0 {
+                50.0
+            } else {
+                100.0
+            }
+        } else {
+            100.0 - 100.0 / (1.0 + self.gain / self.loss)
+        })
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MacdValue {
+    pub macd: f64,
+    pub signal: f64,
+    pub histogram: f64,
+}
+
+/// Moving average convergence divergence of closes.
+#[derive(Debug, Clone)]
+pub struct Macd {
+    fast: Ema,
+    slow: Ema,
+    signal: Ema,
+}
+
+impl Macd {
+    pub fn new(fast: usize, slow: usize, signal: usize) -> Self {
+        Self {
+            fast: Ema::new(fast),
+            slow: Ema::new(slow),
+            signal: Ema::new(signal),
+        }
+    }
+}
+
+impl Default for Macd {
+    /// The usual 12, 26, 9.
+    fn default() -> Self {
+        Self::new(12, 26, 9)
+    }
+}
+
+impl Indicator for Macd {
+    type Output = MacdValue;
+
+    fn next(&mut self, candle: &Candle) -> Option<MacdValue> {
+        let fast = self.fast.push(candle.close);
+        let slow = self.slow.push(candle.close);
+        let macd = fast? - slow?;
+        let signal = self.signal.push(macd)?;
+        Some(MacdValue {
+            macd,
+            signal,
+            histogram: macd - signal,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::indicators::tests::{assert_close, candles};
+
+    #[test]
+    fn rsi_matches_wilders_example() {
+        let values = Rsi::new(14).over(&candles());
+        assert_eq!(values[13], None);
+        assert_close(values[14], 70.53279);
+        assert_close(values[15], 66.31856);
+        assert_close(values[16], 66.54983);
+        assert_close(values[32], 37.77295);
+    }
+
+    #[test]
+    fn rsi_of_a_flat_series_is_50() {
+        let mut flat = candles();
+        for candle in &mut flat {
+            candle.close = 100.0;
+        }
+        assert_eq!(Rsi::new(5).over(&flat)[10], Some(50.0));
+    }
+
+    #[test]
+    fn macd_matches_reference_values() {
+        let values = Macd::new(5, 10, 4).over(&candles());
+        assert_eq!(values[11], None);
+        let first = values[12].expect("signal has warmed up");
+        assert_close(Some(first.macd), 0.45901);
+        assert_close(Some(first.signal), 0.60068);
+        assert_close(Some(first.histogram), -0.14167);
+        let last = values[32].expect("signal has warmed up");
+        assert_close(Some(last.macd), -0.60822);
+        assert_close(Some(last.signal), -0.54135);
+        assert_close(Some(last.histogram), -0.06687);
+    }
+}
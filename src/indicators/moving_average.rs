@@ -0,0 +1,102 @@
+use super::Indicator;
+use crate::smartapi::Candle;
+use std::collections::VecDeque;
+
+/// Simple moving average of closes.
+#[derive(Debug, Clone)]
+pub struct Sma {
+    period: usize,
+    window: VecDeque<f64>,
+    sum: f64,
+}
+
+impl Sma {
+    /// A zero period is treated as one.
+    pub fn new(period: usize) -> Self {
+        let period = period.max(1);
+        Self {
+            period,
+            window: VecDeque::with_capacity(period + 1),
+            sum: 0.0,
+        }
+    }
+
+    /// Add a value rather than a bar, for averaging other indicators.
+    pub fn push(&mut self, value: f64) -> Option<f64> {
+        self.window.push_back(value);
+        self.sum += value;
+        if self.window.len() > self.period {
+            self.sum -= self.window.pop_front().unwrap_or_default();
+        }
+        (self.window.len() == self.period).then(|| self.sum / self.period as f64)
+    }
+}
+
+impl Indicator for Sma {
+    type Output = f64;
+
+    fn next(&mut self, candle: &Candle) -> Option<f64> {
+        self.push(candle.close)
+    }
+}
+
+/// Exponential moving average of closes, seeded with the SMA of the first `period`.
+#[derive(Debug, Clone)]
+pub struct Ema {
+    alpha: f64,
+    seed: Sma,
+    value: Option<f64>,
+}
+
+impl Ema {
+    pub fn new(period: usize) -> Self {
+        let seed = Sma::new(period);
+        Self {
+            alpha: 2.0 / (seed.period as f64 + 1.0),
+            seed,
+            value: None,
+        }
+    }
+
+    /// Add a value rather than a bar, for smoothing other indicators.
+    pub fn push(&mut self, value: f64) -> Option<f64> {
+        self.value = match self.value {
+            Some(previous) => Some(previous + self.alpha * (value - previous)),
+            None => self.seed.push(value),
+        };
+        self.value
+    }
+}
+
+impl Indicator for Ema {
+    type Output = f64;
+
+    fn next(&mut self, candle: &Candle) -> Option<f64> {
+        self.push(candle.close)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::indicators::tests::{assert_close, candles};
+
+    #[test]
+    fn sma_averages_the_last_period_closes() {
+        let values = Sma::new(10).over(&candles());
+        assert_eq!(values[8], None);
+        assert_close(values[9], 44.77913);
+        assert_close(values[20], 46.07363);
+        assert_close(values[32], 44.37969);
+    }
+
+    #[test]
+    fn ema_is_seeded_with_the_sma() {
+        let values = Ema::new(10).over(&candles());
+        assert_eq!(values[8], None);
+        // Seeded with the SMA
+        assert_close(values[9], 44.77913);
+        assert_close(values[10], 44.98167);
+        assert_close(values[32], 44.12015);
+    }
+}
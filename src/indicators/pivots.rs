@@ -0,0 +1,99 @@
+use super::Indicator;
+use crate::smartapi::Candle;
+use crate::time::ist_day;
+
+/// Classic floor pivot levels.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PivotLevels {
+    pub pivot: f64,
+    pub r1: f64,
+    pub r2: f64,
+    pub r3: f64,
+    pub s1: f64,
+    pub s2: f64,
+    pub s3: f64,
+}
+
+impl PivotLevels {
+    /// Levels for the next session from one session's high, low and close.
+    pub fn classic(high: f64, low: f64, close: f64) -> Self {
+        let pivot = (high + low + close) / 3.0;
+        Self {
+            pivot,
+            r1: 2.0 * pivot - low,
+            r2: pivot + (high - low),
+            r3: high + 2.0 * (pivot - low),
+            s1: 2.0 * pivot - high,
+            s2: pivot - (high - low),
+            s3: low - 2.0 * (high - pivot),
+        }
+    }
+}
+
+/// Pivot levels from the previous IST day, for bars of any interval.
+///
+/// Daily bars get the levels from the bar before them.
+#[derive(Debug, Clone, Default)]
+pub struct Pivots {
+    day: Option<u64>,
+    // High, low and close of the day so far
+    session: Option<(f64, f64, f64)>,
+    levels: Option<PivotLevels>,
+}
+
+impl Pivots {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl Indicator for Pivots {
+    type Output = PivotLevels;
+
+    fn next(&mut self, candle: &Candle) -> Option<PivotLevels> {
+        let day = ist_day(candle.time);
+        if self.day != Some(day) {
+            self.day = Some(day);
+            if let Some((high, low, close)) = self.session.take() {
+                self.levels = Some(PivotLevels::classic(high, low, close));
+            }
+        }
+        self.session = Some(match self.session {
+            Some((high, low, _)) => (high.max(candle.high), low.min(candle.low), candle.close),
+            None => (candle.high, candle.low, candle.close),
+        });
+        self.levels
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::indicators::tests::{assert_close, candles};
+
+    #[test]
+    fn computes_classic_levels() {
+        let levels = PivotLevels::classic(110.0, 90.0, 105.0);
+        assert_close(Some(levels.pivot), 101.66667);
+        assert_close(Some(levels.r1), 113.33333);
+        assert_close(Some(levels.r2), 121.66667);
+        assert_close(Some(levels.r3), 133.33333);
+        assert_close(Some(levels.s1), 93.33333);
+        assert_close(Some(levels.s2), 81.66667);
+        assert_close(Some(levels.s3), 73.33333);
+    }
+
+    #[test]
+    fn pivots_come_from_the_previous_day() {
+        let values = Pivots::new().over(&candles());
+        // Nothing until a whole day has passed
+        assert!(values[..6].iter().all(Option::is_none));
+        let day_two = values[6].expect("first day is over");
+        assert_close(Some(day_two.pivot), 44.45507);
+        assert_eq!(values[11], values[6]);
+        let last = values[32].expect("first day is over");
+        assert_close(Some(last.pivot), 44.9825);
+        assert_close(Some(last.r1), 46.2862);
+        assert_close(Some(last.s1), 43.2635);
+    }
+}
@@ -0,0 +1,244 @@
+use super::Indicator;
+use crate::smartapi::Candle;
+use std::collections::VecDeque;
+
+/// Wilder's average true range.
+#[derive(Debug, Clone)]
+pub struct Atr {
+    period: usize,
+    previous_close: Option<f64>,
+    // Sum of the first `period` true ranges, then the running average
+    range: f64,
+    bars: usize,
+}
+
+impl Atr {
+    pub fn new(period: usize) -> Self {
+        Self {
+            period: period.max(1),
+            previous_close: None,
+            range: 0.0,
+            bars: 0,
+        }
+    }
+}
+
+impl Indicator for Atr {
+    type Output = f64;
+
+    fn next(&mut self, candle: &Candle) -> Option<f64> {
+        let true_range = match self.previous_close.replace(candle.close) {
+            Some(close) => (candle.high - candle.low)
+                .max((candle.high - close).abs())
+                .max((candle.low - close).abs()),
+            None => candle.high - candle.low,
+        };
+        let period = self.period as f64;
+
+        self.bars += 1;
+        if self.bars <= self.period {
+            self.range += true_range;
+            if self.bars < self.period {
+                return None;
+            }
+            self.range /= period;
+        } else {
+            self.range = (self.range * (period - 1.0) + true_range) / period;
+        }
+        Some(self.range)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BollingerValue {
+    pub upper: f64,
+    pub middle: f64,
+    pub lower: f64,
+}
+
+/// Bollinger Bands, `width` population standard deviations around the SMA of closes.
+#[derive(Debug, Clone)]
+pub struct Bollinger {
+    period: usize,
+    width: f64,
+    window: VecDeque<f64>,
+}
+
+impl Bollinger {
+    pub fn new(period: usize, width: f64) -> Self {
+        let period = period.max(1);
+        Self {
+            period,
+            width,
+            window: VecDeque::with_capacity(period + 1),
+        }
+    }
+}
+
+impl Default for Bollinger {
+    /// The usual 20 bars at 2 deviations.
+    fn default() -> Self {
+        Self::new(20, 2.0)
+    }
+}
+
+impl Indicator for Bollinger {
+    type Output = BollingerValue;
+
+    fn next(&mut self, candle: &Candle) -> Option<BollingerValue> {
+        self.window.push_back(candle.close);
+        if self.window.len() > self.period {
+            self.window.pop_front();
+        }
+        if self.window.len() < self.period {
+            return None;
+        }
+        // Summed afresh each bar, a running sum of squares loses precision at these prices
+        let period = self.period as f64;
+        let middle = self.window.iter().sum::<f64>() / period;
+        let variance = self
+            .window
+            .iter()
+            .map(|close| (close - middle).powi(2))
+            .sum::<f64>()
+            / period;
+        let band = self.width * variance.sqrt();
+        Some(BollingerValue {
+            upper: middle + band,
+            middle,
+            lower: middle - band,
+        })
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Trend {
+    Up,
+    Down,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SupertrendValue {
+    /// The trailing stop, under the bars in an uptrend and over them in a downtrend
+    pub value: f64,
+    pub trend: Trend,
+}
+
+/// Supertrend, bands `multiplier` ATRs from the bar's midpoint that only
+/// tighten until the close crosses them.
+///
+/// Starts in a downtrend, as TradingView's does.
+#[derive(Debug, Clone)]
+pub struct Supertrend {
+    multiplier: f64,
+    atr: Atr,
+    previous_close: Option<f64>,
+    // Final upper and lower bands and the trend as of the previous bar
+    previous: Option<(f64, f64, Trend)>,
+}
+
+impl Supertrend {
+    pub fn new(period: usize, multiplier: f64) -> Self {
+        Self {
+            multiplier,
+            atr: Atr::new(period),
+            previous_close: None,
+            previous: None,
+        }
+    }
+}
+
+impl Default for Supertrend {
+    /// The usual 10 bars at 3 ATRs.
+    fn default() -> Self {
+        Self::new(10, 3.0)
+    }
+}
+
+impl Indicator for Supertrend {
+    type Output = SupertrendValue;
+
+    fn next(&mut self, candle: &Candle) -> Option<SupertrendValue> {
+        let previous_close = self.previous_close.replace(candle.close);
+        let atr = self.atr.next(candle)?;
+        let middle = (candle.high + candle.low) / 2.0;
+        let mut upper = middle + self.multiplier * atr;
+        let mut lower = middle - self.multiplier * atr;
+
+        let trend = match (self.previous, previous_close) {
+            (Some((previous_upper, previous_lower, trend)), Some(previous_close)) => {
+                if upper > previous_upper && previous_close <= previous_upper {
+                    upper = previous_upper;
+                }
+                if lower < previous_lower && previous_close >= previous_lower {
+                    lower = previous_lower;
+                }
+                match trend {
+                    Trend::Down if candle.close > upper => Trend::Up,
+                    Trend::Up if candle.close < lower => Trend::Down,
+                    trend => trend,
+                }
+            }
+            _ => Trend::Down,
+        };
+        self.previous = Some((upper, lower, trend));
+
+        Some(SupertrendValue {
+            value: match trend {
+                Trend::Up => lower,
+                Trend::Down => upper,
+            },
+            trend,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::indicators::tests::{assert_close, candles};
+
+    #[test]
+    fn atr_matches_reference_values() {
+        let values = Atr::new(14).over(&candles());
+        assert_eq!(values[12], None);
+        assert_close(values[13], 0.94869);
+        assert_close(values[14], 0.9345);
+        assert_close(values[32], 1.09753);
+    }
+
+    #[test]
+    fn bollinger_bands_match_reference_values() {
+        let values = Bollinger::default().over(&candles());
+        assert_eq!(values[18], None);
+        let first = values[19].expect("warmed up");
+        assert_close(Some(first.upper), 47.11925);
+        assert_close(Some(first.middle), 45.41043);
+        assert_close(Some(first.lower), 43.7016);
+        let last = values[32].expect("warmed up");
+        assert_close(Some(last.upper), 47.62347);
+        assert_close(Some(last.middle), 45.24261);
+        assert_close(Some(last.lower), 42.86175);
+    }
+
+    #[test]
+    fn supertrend_follows_trend_flips() {
+        let values = Supertrend::new(5, 2.0).over(&candles());
+        assert_eq!(values[3], None);
+        // Starts down, flips up at bar 9 and back down at bar 26
+        let expected = [
+            (4, 45.84946, Trend::Down),
+            (8, 45.84946, Trend::Down),
+            (9, 44.10641, Trend::Up),
+            (17, 44.52435, Trend::Up),
+            (25, 44.55786, Trend::Up),
+            (26, 47.31891, Trend::Down),
+            (32, 45.44911, Trend::Down),
+        ];
+        for (index, level, trend) in expected {
+            let value = values[index].expect("warmed up");
+            assert_close(Some(value.value), level);
+            assert_eq!(value.trend, trend, "bar {}", index);
+        }
+    }
+}
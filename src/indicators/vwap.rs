@@ -0,0 +1,64 @@
+use super::Indicator;
+use crate::smartapi::Candle;
+use crate::time::ist_day;
+
+/// Volume weighted average of each bar's typical price, restarting every IST day.
+#[derive(Debug, Clone, Default)]
+pub struct Vwap {
+    day: Option<u64>,
+    turnover: f64,
+    volume: f64,
+}
+
+impl Vwap {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl Indicator for Vwap {
+    type Output = f64;
+
+    fn next(&mut self, candle: &Candle) -> Option<f64> {
+        let day = ist_day(candle.time);
+        if self.day != Some(day) {
+            *self = Self {
+                day: Some(day),
+                ..Self::default()
+            };
+        }
+        let typical = (candle.high + candle.low + candle.close) / 3.0;
+        self.turnover += typical * candle.volume as f64;
+        self.volume += candle.volume as f64;
+        (self.volume > 0.0).then(|| self.turnover / self.volume)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::indicators::tests::{assert_close, candles};
+
+    #[test]
+    fn vwap_restarts_each_day() {
+        let candles = candles();
+        let values = Vwap::new().over(&candles);
+        let first = &candles[0];
+        assert_close(values[0], (first.high + first.low + first.close) / 3.0);
+        assert_close(values[5], 44.26349);
+        // The second day starts afresh
+        let opening = &candles[6];
+        assert_close(
+            values[6],
+            (opening.high + opening.low + opening.close) / 3.0,
+        );
+        assert_close(values[32], 43.31232);
+    }
+
+    #[test]
+    fn vwap_is_undefined_without_volume() {
+        let mut candle = candles()[0];
+        candle.volume = 0;
+        assert_eq!(Vwap::new().next(&candle), None);
+    }
+}
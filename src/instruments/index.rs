@@ -0,0 +1,282 @@
+use super::{Instrument, InstrumentQuery};
+use std::collections::{HashMap, HashSet};
+
+// Upper bound on results whatever the caller asks for
+const MAX_RESULTS: usize = 100;
+// Fuzzy matches rank after every prefix and substring match
+const FUZZY_BASE_SCORE: u32 = 10;
+const MAX_FUZZY_GAPS: u32 = 20;
+
+/// In-memory symbol search over the instrument master.
+pub struct InstrumentIndex {
+    instruments: Vec<Instrument>,
+    // Uppercased search keys, parallel to `instruments`
+    symbols: Vec<String>,
+    names: Vec<String>,
+    // Positions into `instruments` sorted by symbol and by name, for prefix lookups
+    by_symbol: Vec<usize>,
+    by_name: Vec<usize>,
+    // Tokens repeat across exchanges
+    by_token: HashMap<String, Vec<usize>>,
+}
+
+impl InstrumentIndex {
+    pub fn new(instruments: Vec<Instrument>) -> Self {
+        let symbols: Vec<String> = instruments
+            .iter()
+            .map(|i| i.symbol.to_uppercase())
+            .collect();
+        let names: Vec<String> = instruments.iter().map(|i| i.name.to_uppercase()).collect();
+        let mut by_symbol: Vec<usize> = (0..instruments.len()).collect();
+        by_symbol.sort_by(|&a, &b| symbols[a].cmp(&symbols[b]));
+        let mut by_name: Vec<usize> = (0..instruments.len()).collect();
+        by_name.sort_by(|&a, &b| names[a].cmp(&names[b]));
+        let mut by_token: HashMap<String, Vec<usize>> = HashMap::new();
+        for (i, instrument) in instruments.iter().enumerate() {
+            by_token
+                .entry(instrument.token.clone())
+                .or_default()
+                .push(i);
+        }
+
+        Self {
+            instruments,
+            symbols,
+            names,
+            by_symbol,
+            by_name,
+            by_token,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.instruments.len()
+    }
+
+    /// Search by token, trading symbol and name.
+    ///
+    /// Ranks exact tokens and symbols first, then symbol and name prefixes,
+    /// substrings, and finally fuzzy symbol matches with the fewest skipped
+    /// characters. Tokens and prefixes come from sorted indexes; the master is
+    /// only scanned for substring and fuzzy matches when those fall short.
+    pub fn search(&self, query: &InstrumentQuery) -> Vec<Instrument> {
+        let text = query.text.trim().to_uppercase();
+        if text.is_empty() {
+            return Vec::new();
+        }
+        let limit = query.limit.min(MAX_RESULTS);
+        let compact: String = text.chars().filter(|c| !c.is_whitespace()).collect();
+        let matches_filters = |i: usize| {
+            let instrument = &self.instruments[i];
+            query
+                .exchange
+                .as_deref()
+                .is_none_or(|exchange| instrument.exchange.eq_ignore_ascii_case(exchange))
+                && query
+                    .instrument_type
+                    .as_deref()
+                    .is_none_or(|kind| instrument.instrument_type.eq_ignore_ascii_case(kind))
+        };
+
+        let mut scored: Vec<(u32, usize)> = Vec::new();
+        let mut seen = HashSet::new();
+        let mut add = |scored: &mut Vec<(u32, usize)>, (score, i): (u32, usize)| {
+            if seen.insert(i) && matches_filters(i) {
+                scored.push((score, i));
+            }
+        };
+
+        // Best scores first, so an instrument keeps the best of its matches
+        let tokens = self
+            .by_token
+            .get(&compact)
+            .into_iter()
+            .flatten()
+            .map(|&i| (0, i));
+        let symbol_prefixes = prefixed(&self.by_symbol, &self.symbols, &text)
+            .map(|i| (if self.symbols[i] == text { 0 } else { 1 }, i));
+        let name_prefixes = prefixed(&self.by_name, &self.names, &text).map(|i| (2, i));
+        for candidate in tokens.chain(symbol_prefixes).chain(name_prefixes) {
+            add(&mut scored, candidate);
+        }
+
+        // Everything below ranks after the indexed matches, so scan only while short
+        if scored.len() < limit {
+            for i in 0..self.instruments.len() {
+                if self.symbols[i].contains(&text) {
+                    add(&mut scored, (3, i));
+                } else if self.names[i].contains(&text) {
+                    add(&mut scored, (4, i));
+                }
+            }
+        }
+        if scored.len() < limit {
+            for i in 0..self.instruments.len() {
+                if let Some(gaps) = fuzzy_gaps(&self.symbols[i], &compact) {
+                    if gaps <= MAX_FUZZY_GAPS {
+                        add(&mut scored, (FUZZY_BASE_SCORE + gaps, i));
+                    }
+                }
+            }
+        }
+
+        scored.sort_by(|&(score_a, a), &(score_b, b)| {
+            score_a
+                .cmp(&score_b)
+                .then(self.symbols[a].len().cmp(&self.symbols[b].len()))
+                .then(self.symbols[a].cmp(&self.symbols[b]))
+        });
+        scored
+            .into_iter()
+            .take(limit)
+            .map(|(_, i)| self.instruments[i].clone())
+            .collect()
+    }
+}
+
+// Positions whose key starts with `prefix`, from positions sorted by key
+fn prefixed<'a>(
+    sorted: &'a [usize],
+    keys: &'a [String],
+    prefix: &'a str,
+) -> impl Iterator<Item = usize> + 'a {
+    let start = sorted.partition_point(|&i| keys[i].as_str() < prefix);
+    sorted[start..]
+        .iter()
+        .copied()
+        .take_while(move |&i| keys[i].starts_with(prefix))
+}
+
+// Characters skipped when `needle` is matched as a subsequence of `haystack`
+fn fuzzy_gaps(haystack: &str, needle: &str) -> Option<u32> {
+    let mut gaps = 0;
+    let mut haystack = haystack.chars();
+    for wanted in needle.chars() {
+        loop {
+            if haystack.next()? == wanted {
+                break;
+            }
+            gaps += 1;
+        }
+    }
+    Some(gaps)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn instrument(
+        token: &str,
+        symbol: &str,
+        name: &str,
+        exchange: &str,
+        instrument_type: &str,
+    ) -> Instrument {
+        Instrument {
+            token: token.to_string(),
+            symbol: symbol.to_string(),
+            name: name.to_string(),
+            exchange: exchange.to_string(),
+            instrument_type: instrument_type.to_string(),
+            expiry: String::new(),
+            strike: -1.0,
+            lot_size: 1,
+            tick_size: 5.0,
+        }
+    }
+
+    fn instruments() -> Vec<Instrument> {
+        vec![
+            instrument("17971", "SBICARD-EQ", "SBICARD", "NSE", ""),
+            instrument("3045", "SBIN-EQ", "SBIN", "NSE", ""),
+            instrument("500112", "SBIN", "SBIN", "BSE", ""),
+            instrument("58023", "SBIN26DECFUT", "SBIN", "NFO", "FUTSTK"),
+            instrument("2885", "RELIANCE-EQ", "RELIANCE", "NSE", ""),
+        ]
+    }
+
+    fn index() -> InstrumentIndex {
+        InstrumentIndex::new(instruments())
+    }
+
+    fn symbols(results: Vec<Instrument>) -> Vec<String> {
+        results.into_iter().map(|i| i.symbol).collect()
+    }
+
+    #[test]
+    fn ranks_exact_then_prefix_matches() {
+        let results = index().search(&InstrumentQuery::new("sbin"));
+        assert_eq!(symbols(results), ["SBIN", "SBIN-EQ", "SBIN26DECFUT"]);
+    }
+
+    #[test]
+    fn filters_by_exchange_and_instrument_type() {
+        let index = index();
+        let mut query = InstrumentQuery::new("SBIN");
+        query.exchange = Some("nfo".to_string());
+        assert_eq!(symbols(index.search(&query)), ["SBIN26DECFUT"]);
+
+        query.exchange = None;
+        query.instrument_type = Some("FUTSTK".to_string());
+        assert_eq!(symbols(index.search(&query)), ["SBIN26DECFUT"]);
+    }
+
+    #[test]
+    fn finds_tokens_and_name_prefixes() {
+        let index = InstrumentIndex::new(vec![
+            instrument("35001", "NIFTY26DECFUT", "NIFTY", "NFO", "FUTIDX"),
+            instrument("99926009", "Nifty Bank", "BANKNIFTY", "NSE", "AMXIDX"),
+        ]);
+
+        assert_eq!(
+            symbols(index.search(&InstrumentQuery::new("35001"))),
+            ["NIFTY26DECFUT"]
+        );
+        assert_eq!(
+            symbols(index.search(&InstrumentQuery::new("banknif"))),
+            ["Nifty Bank"]
+        );
+    }
+
+    #[test]
+    fn matches_whole_tokens_on_every_exchange() {
+        // Tokens are only unique within an exchange
+        let gold = instrument("3045", "GOLDM26DECFUT", "GOLDM", "MCX", "FUTCOM");
+        let mut instruments = instruments();
+        instruments.push(gold);
+        let index = InstrumentIndex::new(instruments);
+
+        let mut query = InstrumentQuery::new("3045");
+        assert_eq!(symbols(index.search(&query)), ["SBIN-EQ", "GOLDM26DECFUT"]);
+        query.exchange = Some("MCX".to_string());
+        assert_eq!(symbols(index.search(&query)), ["GOLDM26DECFUT"]);
+
+        assert_eq!(
+            symbols(index.search(&InstrumentQuery::new("500112"))),
+            ["SBIN"]
+        );
+        assert!(index.search(&InstrumentQuery::new("304")).is_empty());
+    }
+
+    #[test]
+    fn limits_keep_the_best_matches() {
+        let mut query = InstrumentQuery::new("SBI");
+        let all = symbols(index().search(&query));
+        assert_eq!(all, ["SBIN", "SBIN-EQ", "SBICARD-EQ", "SBIN26DECFUT"]);
+
+        query.limit = 2;
+        assert_eq!(symbols(index().search(&query)), all[..2]);
+
+        // Substrings are still found when prefixes fall short
+        let query = InstrumentQuery::new("BIN-");
+        assert_eq!(symbols(index().search(&query)), ["SBIN-EQ"]);
+    }
+
+    #[test]
+    fn falls_back_to_fuzzy_matches() {
+        let results = index().search(&InstrumentQuery::new("rlnc"));
+        assert_eq!(symbols(results), ["RELIANCE-EQ"]);
+        assert!(index().search(&InstrumentQuery::new("xyz")).is_empty());
+    }
+}
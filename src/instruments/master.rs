@@ -0,0 +1,154 @@
+use super::{Instrument, InstrumentIndex};
+use crate::config::ServerConfig;
+use crate::storage::get_app_data_dir;
+use crate::time::{ist_day, now_secs};
+use std::path::Path;
+use std::sync::Arc;
+use std::time::{Duration, UNIX_EPOCH};
+use tokio::sync::Mutex;
+
+const MASTER_URL: &str =
+    "https://margincalculator.angelbroking.com/OpenAPI_File/files/OpenAPIScripMaster.json";
+const CACHE_FILE: &str = "instruments.json";
+const DOWNLOAD_TIMEOUT: Duration = Duration::from_secs(120);
+
+struct LoadedIndex {
+    index: Arc<InstrumentIndex>,
+    // IST day the index was loaded, it is reloaded on the first use of a new day
+    day: u64,
+}
+
+static INDEX: Mutex<Option<LoadedIndex>> = Mutex::const_new(None);
+
+/// The instrument index, loading or refreshing the master when needed.
+///
+/// Keeps serving yesterday's index when today's refresh fails.
+pub async fn instrument_index() -> Result<Arc<InstrumentIndex>, String> {
+    let today = ist_day(now_secs());
+    let mut loaded = INDEX.lock().await;
+    if let Some(current) = loaded.as_ref().filter(|current| current.day == today) {
+        return Ok(current.index.clone());
+    }
+
+    match load_instruments(today).await {
+        Ok(instruments) => {
+            let index = blocking(move || Ok(InstrumentIndex::new(instruments))).await?;
+            tracing::info!("Instrument index ready with {} instruments", index.len());
+            let index = Arc::new(index);
+            *loaded = Some(LoadedIndex {
+                index: index.clone(),
+                day: today,
+            });
+            Ok(index)
+        }
+        Err(e) => match loaded.as_mut() {
+            Some(stale) => {
+                tracing::warn!(
+                    "Instrument refresh failed, keeping the previous index: {}",
+                    e
+                );
+                // Don't retry on every search, the next attempt is tomorrow or after a restart
+                stale.day = today;
+                Ok(stale.index.clone())
+            }
+            None => Err(e),
+        },
+    }
+}
+
+// Today's cached master, otherwise a fresh copy, otherwise any cached copy
+async fn load_instruments(today: u64) -> Result<Vec<Instrument>, String> {
+    let cache_path = get_app_data_dir()?.join(CACHE_FILE);
+    if cache_day(&cache_path) == Some(today) {
+        return blocking(move || parse(&read(&cache_path)?)).await;
+    }
+
+    let fetched = fetch_master().await;
+    blocking(move || {
+        match fetched.and_then(|bytes| parse(&bytes).map(|instruments| (instruments, bytes))) {
+            Ok((instruments, bytes)) => {
+                if let Err(e) = write_cache(&cache_path, &bytes) {
+                    tracing::warn!("Failed to cache the instrument master: {}", e);
+                }
+                Ok(instruments)
+            }
+            Err(e) if cache_path.exists() => {
+                tracing::warn!(
+                    "Failed to fetch the instrument master, using the cached copy: {}",
+                    e
+                );
+                parse(&read(&cache_path)?)
+            }
+            Err(e) => Err(e),
+        }
+    })
+    .await
+}
+
+// The configured source is either a URL or a local file
+async fn fetch_master() -> Result<Vec<u8>, String> {
+    let config = ServerConfig::init().map_err(|e| e.to_string())?;
+    let source = config
+        .instrument_master
+        .clone()
+        .unwrap_or_else(|| MASTER_URL.to_string());
+
+    if !source.starts_with("http://") && !source.starts_with("https://") {
+        tracing::info!("Loading instrument master from {}", source);
+        return blocking(move || read(Path::new(&source))).await;
+    }
+
+    tracing::info!("Downloading instrument master from {}", source);
+    let response = reqwest::Client::builder()
+        .timeout(DOWNLOAD_TIMEOUT)
+        .build()
+        .map_err(|e| format!("Failed to create HTTP client: {}", e))?
+        .get(&source)
+        .send()
+        .await
+        .and_then(|response| response.error_for_status())
+        .map_err(|e| format!("Failed to download instrument master: {}", e))?;
+    response
+        .bytes()
+        .await
+        .map(|bytes| bytes.to_vec())
+        .map_err(|e| format!("Failed to download instrument master: {}", e))
+}
+
+// Parsing and file IO on a multi megabyte master must stay off the async workers
+async fn blocking<T, F>(f: F) -> Result<T, String>
+where
+    F: FnOnce() -> Result<T, String> + Send + 'static,
+    T: Send + 'static,
+{
+    tokio::task::spawn_blocking(f)
+        .await
+        .map_err(|e| format!("Instrument master task failed: {}", e))?
+}
+
+fn parse(bytes: &[u8]) -> Result<Vec<Instrument>, String> {
+    serde_json::from_slice(bytes).map_err(|e| format!("Failed to parse instrument master: {}", e))
+}
+
+fn read(path: &Path) -> Result<Vec<u8>, String> {
+    std::fs::read(path).map_err(|e| format!("Failed to read {}: {}", path.display(), e))
+}
+
+// IST day the cache file was last written
+fn cache_day(path: &Path) -> Option<u64> {
+    let modified = std::fs::metadata(path).ok()?.modified().ok()?;
+    Some(ist_day(modified.duration_since(UNIX_EPOCH).ok()?.as_secs()))
+}
+
+// Write then rename so a crash never leaves a truncated master behind
+fn write_cache(path: &Path, bytes: &[u8]) -> Result<(), String> {
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir)
+            .map_err(|e| format!("Failed to create app directory: {}", e))?;
+    }
+    let tmp_path = path.with_extension("tmp");
+    std::fs::write(&tmp_path, bytes)
+        .map_err(|e| format!("Failed to write {}: {}", tmp_path.display(), e))?;
+    std::fs::rename(&tmp_path, path)
+        .map_err(|e| format!("Failed to write {}: {}", path.display(), e))
+}
@@ -0,0 +1,82 @@
+//! Instrument master: every token SmartAPI can trade or stream.
+//!
+//! The master file runs to tens of megabytes, so it only lives on the server.
+//! It is cached under the app data directory, refreshed once a day and kept
+//! in memory as an [`InstrumentIndex`] for symbol search.
+
+use crate::smartapi::{de, SmartApiError};
+use dioxus::prelude::*;
+use serde::{Deserialize, Serialize};
+
+#[cfg(feature = "server")]
+mod index;
+#[cfg(feature = "server")]
+pub use index::InstrumentIndex;
+
+#[cfg(feature = "server")]
+mod master;
+#[cfg(feature = "server")]
+pub use master::instrument_index;
+
+/// One entry of `OpenAPIScripMaster.json`.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct Instrument {
+    pub token: String,
+    /// Trading symbol, e.g. `SBIN-EQ` or `NIFTY26DEC2524000CE`
+    pub symbol: String,
+    pub name: String,
+    #[serde(rename = "exch_seg")]
+    pub exchange: String,
+    /// Empty for cash equities, otherwise e.g. `FUTIDX` or `OPTSTK`
+    #[serde(rename = "instrumenttype", default)]
+    pub instrument_type: String,
+    #[serde(default)]
+    pub expiry: String,
+    /// Strike as published, scaled by 100 for most derivatives and -1 otherwise
+    #[serde(deserialize_with = "de::number", default)]
+    pub strike: f64,
+    #[serde(rename = "lotsize", deserialize_with = "de::integer", default)]
+    pub lot_size: i64,
+    #[serde(deserialize_with = "de::number", default)]
+    pub tick_size: f64,
+}
+
+impl Instrument {
+    /// Segment badge, `EQ` for cash equities.
+    pub fn segment(&self) -> &str {
+        if self.instrument_type.is_empty() {
+            "EQ"
+        } else {
+            &self.instrument_type
+        }
+    }
+}
+
+/// Symbol search parameters, the filters match exactly and ignore case.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct InstrumentQuery {
+    pub text: String,
+    pub exchange: Option<String>,
+    pub instrument_type: Option<String>,
+    pub limit: usize,
+}
+
+impl InstrumentQuery {
+    pub fn new(text: &str) -> Self {
+        Self {
+            text: text.to_string(),
+            exchange: None,
+            instrument_type: None,
+            limit: 20,
+        }
+    }
+}
+
+/// Best matching instruments for a symbol search, best match first.
+#[server(SearchInstrumentsServer)]
+pub async fn search_instruments_server(
+    query: InstrumentQuery,
+) -> Result<Vec<Instrument>, ServerFnError<SmartApiError>> {
+    let index = instrument_index().await.map_err(SmartApiError::Network)?;
+    Ok(index.search(&query))
+}
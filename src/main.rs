@@ -1,12 +1,26 @@
 use dioxus::prelude::*;
 
 use auth::{use_auth, AuthProvider, AuthState};
-use views::{Dashboard, Login};
+use views::{Chart, Dashboard, Holdings, Login, OrderBook, OrderTicket, Positions, Trades};
 use components::Navbar;
+use smartstream::MarketFeedProvider;
 
 mod auth;
 mod components;
+#[cfg(feature = "server")]
+mod config;
+mod format;
+mod history;
+mod indicators;
+mod instruments;
+mod orders;
+mod secret;
+mod smartapi;
+mod smartstream;
+mod storage;
+mod time;
 mod views;
+mod watchlists;
 
 #[derive(Debug, Clone, Routable, PartialEq)]
 #[rustfmt::skip]
@@ -16,6 +30,18 @@ enum Route {
     #[layout(AppLayout)]
         #[route("/dashboard")]
         Dashboard {},
+        #[route("/holdings")]
+        Holdings {},
+        #[route("/positions")]
+        Positions {},
+        #[route("/orders")]
+        OrderBook {},
+        #[route("/trades")]
+        Trades {},
+        #[route("/order")]
+        OrderTicket {},
+        #[route("/chart/:exchange/:token/:symbol")]
+        Chart { exchange: String, token: String, symbol: String },
         #[route("/login")]
         Login {},
 }
@@ -24,6 +50,13 @@ const FAVICON: Asset = asset!("/assets/favicon.ico");
 const TAILWIND_CSS: Asset = asset!("/assets/tailwind.css");
 
 fn main() {
+    // Fail fast on a bad SmartAPI configuration instead of inside a server function
+    #[cfg(feature = "server")]
+    if let Err(e) = config::ServerConfig::init() {
+        eprintln!("Invalid server configuration: {}", e);
+        std::process::exit(1);
+    }
+
     dioxus::launch(App);
 }
 
@@ -35,7 +68,9 @@ fn App() -> Element {
         document::Link { rel: "stylesheet", href: TAILWIND_CSS }
 
         AuthProvider {
-            Router::<Route> {}
+            MarketFeedProvider {
+                Router::<Route> {}
+            }
         }
     }
 }
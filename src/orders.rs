@@ -0,0 +1,77 @@
+//! Order server functions shared by the trading screens.
+
+use crate::smartapi::{
+    CancelOrderRequest, MarginLeg, MarginRequirement, ModifyOrderRequest, OrderRequest,
+    OrderResponse, SmartApiError,
+};
+use dioxus::prelude::*;
+
+/// Place an order and return Angel's order id.
+#[server(PlaceOrderServer)]
+pub async fn place_order_server(
+    authorization: String,
+    order: OrderRequest,
+) -> Result<OrderResponse, ServerFnError<SmartApiError>> {
+    use crate::smartapi::{bearer_token, SmartApiClient};
+
+    order.validate().map_err(|message| SmartApiError::Api {
+        code: "INVALID_ORDER".to_string(),
+        message,
+    })?;
+
+    tracing::info!(
+        "Placing {:?} {:?} order for {} x {}",
+        order.order_type,
+        order.transaction_type,
+        order.quantity,
+        order.trading_symbol
+    );
+    Ok(SmartApiClient::shared()?
+        .place_order(bearer_token(&authorization), &order)
+        .await?)
+}
+
+/// Change the price, quantity or trigger of an open order.
+#[server(ModifyOrderServer)]
+pub async fn modify_order_server(
+    authorization: String,
+    order: ModifyOrderRequest,
+) -> Result<OrderResponse, ServerFnError<SmartApiError>> {
+    use crate::smartapi::{bearer_token, SmartApiClient};
+
+    order.validate().map_err(|message| SmartApiError::Api {
+        code: "INVALID_ORDER".to_string(),
+        message,
+    })?;
+
+    tracing::info!("Modifying order {}", order.order_id);
+    Ok(SmartApiClient::shared()?
+        .modify_order(bearer_token(&authorization), &order)
+        .await?)
+}
+
+#[server(CancelOrderServer)]
+pub async fn cancel_order_server(
+    authorization: String,
+    order: CancelOrderRequest,
+) -> Result<OrderResponse, ServerFnError<SmartApiError>> {
+    use crate::smartapi::{bearer_token, SmartApiClient};
+
+    tracing::info!("Cancelling order {}", order.order_id);
+    Ok(SmartApiClient::shared()?
+        .cancel_order(bearer_token(&authorization), &order)
+        .await?)
+}
+
+/// Margin required for a basket of legs, before any of them is placed.
+#[server(CalculateMarginServer)]
+pub async fn calculate_margin_server(
+    authorization: String,
+    legs: Vec<MarginLeg>,
+) -> Result<MarginRequirement, ServerFnError<SmartApiError>> {
+    use crate::smartapi::{bearer_token, SmartApiClient};
+
+    Ok(SmartApiClient::shared()?
+        .calculate_margin(bearer_token(&authorization), &legs)
+        .await?)
+}
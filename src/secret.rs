@@ -0,0 +1,62 @@
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// Placeholder printed instead of secret values.
+pub const REDACTED: &str = "[REDACTED]";
+
+/// Wrapper for credentials and tokens that never prints its contents.
+///
+/// Serializes transparently, so it can sit directly in API payloads.
+#[derive(Clone, PartialEq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct Secret<T>(T);
+
+impl<T> Secret<T> {
+    pub fn new(value: T) -> Self {
+        Self(value)
+    }
+
+    pub fn expose(&self) -> &T {
+        &self.0
+    }
+
+    pub fn into_inner(self) -> T {
+        self.0
+    }
+}
+
+impl<T> From<T> for Secret<T> {
+    fn from(value: T) -> Self {
+        Self(value)
+    }
+}
+
+impl<T> fmt::Debug for Secret<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(REDACTED)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn debug_is_redacted() {
+        let secret = Secret::new("hunter2".to_string());
+        assert_eq!(format!("{:?}", secret), REDACTED);
+        assert_eq!(
+            format!("{:#?}", Some(secret)),
+            format!("Some(\n    {},\n)", REDACTED)
+        );
+    }
+
+    #[test]
+    fn serializes_transparently() {
+        let secret = Secret::new("hunter2".to_string());
+        assert_eq!(serde_json::to_string(&secret).unwrap(), "\"hunter2\"");
+
+        let parsed: Secret<String> = serde_json::from_str("\"hunter2\"").unwrap();
+        assert_eq!(parsed.expose(), "hunter2");
+    }
+}
@@ -0,0 +1,241 @@
+use super::SmartApiError;
+use crate::config::ServerConfig;
+use reqwest::header::{HeaderMap, HeaderValue, AUTHORIZATION};
+use reqwest::{Method, StatusCode};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use std::sync::OnceLock;
+use std::time::Duration;
+
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(15);
+
+static SHARED_CLIENT: OnceLock<SmartApiClient> = OnceLock::new();
+
+/// Envelope wrapping every SmartAPI response.
+#[derive(Debug, Deserialize)]
+pub struct ApiResponse<T> {
+    pub status: bool,
+    #[serde(default)]
+    pub message: String,
+    #[serde(default)]
+    pub errorcode: String,
+    pub data: Option<T>,
+}
+
+/// Configured SmartAPI client that injects the headers Angel expects on every call.
+pub struct SmartApiClient {
+    http: reqwest::Client,
+    base_url: String,
+}
+
+impl SmartApiClient {
+    /// Client shared by all server functions, built on first use.
+    pub fn shared() -> Result<&'static SmartApiClient, SmartApiError> {
+        if let Some(client) = SHARED_CLIENT.get() {
+            return Ok(client);
+        }
+        let config = ServerConfig::init().map_err(|e| SmartApiError::Config(e.to_string()))?;
+        let client = Self::new(config)?;
+        Ok(SHARED_CLIENT.get_or_init(|| client))
+    }
+
+    pub fn new(config: &ServerConfig) -> Result<Self, SmartApiError> {
+        let mut headers = HeaderMap::new();
+        headers.insert("Content-Type", HeaderValue::from_static("application/json"));
+        headers.insert("Accept", HeaderValue::from_static("application/json"));
+        headers.insert("X-UserType", HeaderValue::from_static("USER"));
+        headers.insert("X-SourceID", HeaderValue::from_static("WEB"));
+        headers.insert(
+            "X-ClientLocalIP",
+            header_value(&config.local_ip.to_string())?,
+        );
+        headers.insert(
+            "X-ClientPublicIP",
+            header_value(&config.public_ip.to_string())?,
+        );
+        headers.insert("X-MACAddress", header_value(&config.mac_address)?);
+
+        let mut api_key = header_value(config.api_key.expose())?;
+        api_key.set_sensitive(true);
+        headers.insert("X-PrivateKey", api_key);
+
+        let http = reqwest::Client::builder()
+            .default_headers(headers)
+            .timeout(REQUEST_TIMEOUT)
+            .build()
+            .map_err(|e| SmartApiError::Config(e.to_string()))?;
+
+        Ok(Self {
+            http,
+            base_url: config.api_base_url.clone(),
+        })
+    }
+
+    /// Send a request and return the envelope once its status has been checked.
+    pub async fn send<B, T>(
+        &self,
+        method: Method,
+        path: &str,
+        jwt_token: Option<&str>,
+        body: Option<&B>,
+    ) -> Result<ApiResponse<T>, SmartApiError>
+    where
+        B: Serialize + ?Sized,
+        T: DeserializeOwned,
+    {
+        let mut request = self
+            .http
+            .request(method, format!("{}{}", self.base_url, path));
+        if let Some(jwt_token) = jwt_token {
+            request = request.header(AUTHORIZATION, format!("Bearer {}", jwt_token));
+        }
+        if let Some(body) = body {
+            request = request.json(body);
+        }
+
+        let response = request.send().await?;
+        let http_status = response.status();
+        let response_text = response.text().await?;
+
+        let response_json = match serde_json::from_str::<ApiResponse<T>>(&response_text) {
+            Ok(response_json) => response_json,
+            Err(_) if http_status == StatusCode::TOO_MANY_REQUESTS => {
+                return Err(SmartApiError::RateLimited);
+            }
+            // Throttled requests come back as a plain text 403
+            Err(_) if response_text.contains("exceeding access rate") => {
+                return Err(SmartApiError::RateLimited);
+            }
+            Err(e) => {
+                tracing::error!(
+                    "Failed to parse response from {} ({}): {:?}",
+                    path,
+                    http_status,
+                    e
+                );
+                return Err(SmartApiError::Network(
+                    "Failed to parse server response".to_string(),
+                ));
+            }
+        };
+
+        if !response_json.status {
+            return Err(SmartApiError::from_code(
+                &response_json.errorcode,
+                &response_json.message,
+            ));
+        }
+        Ok(response_json)
+    }
+
+    /// POST a JSON body and return the response `data`.
+    pub async fn post<B, T>(
+        &self,
+        path: &str,
+        jwt_token: Option<&str>,
+        body: &B,
+    ) -> Result<T, SmartApiError>
+    where
+        B: Serialize + ?Sized,
+        T: DeserializeOwned,
+    {
+        self.send(Method::POST, path, jwt_token, Some(body))
+            .await?
+            .data
+            .ok_or_else(|| SmartApiError::Network("No data received".to_string()))
+    }
+
+    /// GET an endpoint and return the response `data`.
+    pub async fn get<T>(&self, path: &str, jwt_token: Option<&str>) -> Result<T, SmartApiError>
+    where
+        T: DeserializeOwned,
+    {
+        self.send::<(), T>(Method::GET, path, jwt_token, None)
+            .await?
+            .data
+            .ok_or_else(|| SmartApiError::Network("No data received".to_string()))
+    }
+}
+
+fn header_value(value: &str) -> Result<HeaderValue, SmartApiError> {
+    HeaderValue::from_str(value)
+        .map_err(|_| SmartApiError::Config(format!("Invalid header value {:?}", value)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::secret::Secret;
+    use crate::smartapi::{
+        LoginApiRequest, OrderRequest, OrderStatus, ProductType, TransactionType,
+    };
+    use angel_mock::{MockServer, Scenario};
+
+    fn client(server: &MockServer) -> SmartApiClient {
+        let config = ServerConfig {
+            api_key: Secret::new("mock-key".to_string()),
+            local_ip: [127, 0, 0, 1].into(),
+            public_ip: [127, 0, 0, 1].into(),
+            mac_address: "02:00:00:00:00:01".to_string(),
+            instrument_master: None,
+            api_base_url: server.base_url(),
+            stream_url: server.stream_url(),
+        };
+        SmartApiClient::new(&config).unwrap()
+    }
+
+    fn login_request(totp: &str) -> LoginApiRequest {
+        let scenario = Scenario::default();
+        LoginApiRequest {
+            clientcode: scenario.client_code,
+            password: Secret::new(scenario.password),
+            totp: Secret::new(totp.to_string()),
+        }
+    }
+
+    #[tokio::test]
+    async fn trades_against_the_mock_server() {
+        let server = MockServer::start(Scenario::default()).await.unwrap();
+        let client = client(&server);
+
+        let tokens = client.login(&login_request("123456")).await.unwrap();
+        let jwt_token = tokens.jwt_token.expose();
+        let profile = client.get_profile(jwt_token).await.unwrap();
+        assert_eq!(profile.client_code, Scenario::default().client_code);
+
+        let order = OrderRequest::market(
+            "NSE",
+            "SBIN-EQ",
+            "3045",
+            TransactionType::Buy,
+            ProductType::Intraday,
+            2,
+        );
+        let placed = client.place_order(jwt_token, &order).await.unwrap();
+        let book = client.get_order_book(jwt_token).await.unwrap();
+        assert_eq!(book[0].order_id, placed.order_id);
+        assert_eq!(book[0].status_group(), OrderStatus::Complete);
+
+        let positions = client.get_positions(jwt_token).await.unwrap();
+        assert_eq!(positions[0].net_quantity, 2);
+
+        client
+            .logout(jwt_token, &profile.client_code)
+            .await
+            .unwrap();
+        assert_eq!(
+            client.get_profile(jwt_token).await,
+            Err(SmartApiError::InvalidToken)
+        );
+    }
+
+    #[tokio::test]
+    async fn maps_mock_errors() {
+        let server = MockServer::start(Scenario::default()).await.unwrap();
+        let client = client(&server);
+        assert_eq!(
+            client.login(&login_request("000000")).await.err(),
+            Some(SmartApiError::InvalidTotp)
+        );
+    }
+}
@@ -0,0 +1,31 @@
+use serde::{Deserialize, Deserializer};
+
+// SmartAPI sends most amounts as strings and uses null or "" for zero
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum Number {
+    Float(f64),
+    Text(String),
+    Null(()),
+}
+
+/// Deserialize an amount that may arrive as a number, a numeric string or null.
+pub fn number<'de, D>(deserializer: D) -> Result<f64, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    match Number::deserialize(deserializer)? {
+        Number::Float(value) => Ok(value),
+        Number::Text(text) if text.trim().is_empty() => Ok(0.0),
+        Number::Text(text) => text.trim().parse().map_err(serde::de::Error::custom),
+        Number::Null(()) => Ok(0.0),
+    }
+}
+
+/// Same as [`number`] for whole quantities.
+pub fn integer<'de, D>(deserializer: D) -> Result<i64, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    number(deserializer).map(|value| value as i64)
+}
@@ -0,0 +1,251 @@
+use dioxus::prelude::ServerFnError;
+use std::fmt;
+use std::str::FromStr;
+
+/// Errors reported by SmartAPI, keyed by Angel's documented `errorcode`s.
+///
+/// The error crosses the server function boundary as its `Display` string
+/// (`"<code>: <message>"`), which `FromStr` turns back into the same variant.
+#[derive(Debug, Clone, PartialEq)]
+pub enum SmartApiError {
+    /// AG8001 / AG8003
+    InvalidToken,
+    /// AG8002
+    TokenExpired,
+    /// AB8050 / AB8051
+    InvalidRefreshToken,
+    /// AB1000 / AB1001 / AB1002 / AB1032
+    InvalidCredentials,
+    /// AB1050
+    InvalidTotp,
+    /// AB1006
+    AccountBlocked,
+    /// AB1010 / AB1011
+    SessionExpired,
+    /// AB1007
+    Amx(String),
+    /// AG8004
+    InvalidApiKey,
+    /// Angel throttles clients that exceed the per-endpoint request rate
+    RateLimited,
+    /// Any other errorcode
+    Api { code: String, message: String },
+    /// The request never produced a usable response
+    Network(String),
+    /// Missing or invalid server configuration
+    Config(String),
+}
+
+impl SmartApiError {
+    pub fn from_code(code: &str, message: &str) -> Self {
+        match code {
+            "AG8001" | "AG8003" => Self::InvalidToken,
+            "AG8002" => Self::TokenExpired,
+            "AB8050" | "AB8051" => Self::InvalidRefreshToken,
+            "AB1000" | "AB1001" | "AB1002" | "AB1032" => Self::InvalidCredentials,
+            "AB1050" => Self::InvalidTotp,
+            "AB1006" => Self::AccountBlocked,
+            "AB1010" | "AB1011" => Self::SessionExpired,
+            "AB1007" => Self::Amx(message.to_string()),
+            "AG8004" => Self::InvalidApiKey,
+            "RATE_LIMITED" => Self::RateLimited,
+            "NETWORK" => Self::Network(message.to_string()),
+            "CONFIG" => Self::Config(message.to_string()),
+            _ if message.to_lowercase().contains("api key") => Self::InvalidApiKey,
+            _ if message.to_lowercase().contains("exceeding access rate") => Self::RateLimited,
+            _ => Self::Api {
+                code: code.to_string(),
+                message: message.to_string(),
+            },
+        }
+    }
+
+    pub fn code(&self) -> &str {
+        match self {
+            Self::InvalidToken => "AG8001",
+            Self::TokenExpired => "AG8002",
+            Self::InvalidRefreshToken => "AB8050",
+            Self::InvalidCredentials => "AB1000",
+            Self::InvalidTotp => "AB1050",
+            Self::AccountBlocked => "AB1006",
+            Self::SessionExpired => "AB1010",
+            Self::Amx(_) => "AB1007",
+            Self::InvalidApiKey => "AG8004",
+            Self::RateLimited => "RATE_LIMITED",
+            Self::Api { code, .. } => code,
+            Self::Network(_) => "NETWORK",
+            Self::Config(_) => "CONFIG",
+        }
+    }
+
+    /// Message suitable for showing to the trader.
+    pub fn user_message(&self) -> String {
+        match self {
+            Self::InvalidToken | Self::TokenExpired | Self::SessionExpired => {
+                "Your session has expired, please log in again".to_string()
+            }
+            Self::InvalidRefreshToken => {
+                "Could not renew your session, please log in again".to_string()
+            }
+            Self::InvalidCredentials => "Wrong client code or password".to_string(),
+            Self::InvalidTotp => {
+                "Wrong TOTP, check your authenticator app and try again".to_string()
+            }
+            Self::AccountBlocked => {
+                "Your account is blocked for trading, contact Angel One".to_string()
+            }
+            Self::Amx(message) => format!("Angel One order system error: {}", message),
+            Self::InvalidApiKey => "API key is invalid, check the server configuration".to_string(),
+            Self::RateLimited => "Too many requests, please wait a moment and retry".to_string(),
+            Self::Api { message, .. } => message.clone(),
+            Self::Network(_) => "Could not reach Angel One, check your connection".to_string(),
+            Self::Config(_) => "Server is misconfigured, contact support".to_string(),
+        }
+    }
+
+    /// Whether the error means the stored session can no longer be used.
+    pub fn is_session_error(&self) -> bool {
+        matches!(
+            self,
+            Self::InvalidToken
+                | Self::TokenExpired
+                | Self::InvalidRefreshToken
+                | Self::SessionExpired
+        )
+    }
+
+    fn detail(&self) -> String {
+        match self {
+            Self::Amx(message) | Self::Network(message) | Self::Config(message) => message.clone(),
+            Self::Api { message, .. } => message.clone(),
+            _ => self.user_message(),
+        }
+    }
+}
+
+impl fmt::Display for SmartApiError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.code(), self.detail())
+    }
+}
+
+impl std::error::Error for SmartApiError {}
+
+impl FromStr for SmartApiError {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (code, message) = s.split_once(": ").unwrap_or((s, ""));
+        Ok(Self::from_code(code, message))
+    }
+}
+
+#[cfg(feature = "server")]
+impl From<reqwest::Error> for SmartApiError {
+    fn from(error: reqwest::Error) -> Self {
+        Self::Network(error.to_string())
+    }
+}
+
+/// Message for any failed SmartAPI server function call.
+pub fn error_message(error: &ServerFnError<SmartApiError>) -> String {
+    match error {
+        ServerFnError::WrappedServerError(e) => e.user_message(),
+        ServerFnError::Request(_) => {
+            "Could not reach the server, check your connection".to_string()
+        }
+        _ => "Something went wrong, please try again".to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn maps_session_errorcodes() {
+        for (code, expected) in [
+            ("AG8001", SmartApiError::InvalidToken),
+            ("AG8002", SmartApiError::TokenExpired),
+            ("AG8003", SmartApiError::InvalidToken),
+            ("AB8050", SmartApiError::InvalidRefreshToken),
+            ("AB1010", SmartApiError::SessionExpired),
+            ("AB1011", SmartApiError::SessionExpired),
+        ] {
+            let error = SmartApiError::from_code(code, "Invalid Token");
+            assert_eq!(error, expected, "{}", code);
+            assert!(error.is_session_error(), "{}", code);
+            assert!(error.user_message().contains("log in again"), "{}", code);
+        }
+    }
+
+    #[test]
+    fn maps_other_errorcodes() {
+        for (code, message, expected) in [
+            (
+                "AB1000",
+                "Invalid Username or Password",
+                SmartApiError::InvalidCredentials,
+            ),
+            ("AB1050", "Invalid totp", SmartApiError::InvalidTotp),
+            (
+                "AB1006",
+                "Client is blocked for trading",
+                SmartApiError::AccountBlocked,
+            ),
+            (
+                "AB1007",
+                "AMX error",
+                SmartApiError::Amx("AMX error".to_string()),
+            ),
+            ("AG8004", "Invalid API Key", SmartApiError::InvalidApiKey),
+            ("", "Invalid API Key", SmartApiError::InvalidApiKey),
+            (
+                "",
+                "Access denied because of exceeding access rate",
+                SmartApiError::RateLimited,
+            ),
+            (
+                "AB2001",
+                "Internal Error",
+                SmartApiError::Api {
+                    code: "AB2001".to_string(),
+                    message: "Internal Error".to_string(),
+                },
+            ),
+        ] {
+            let error = SmartApiError::from_code(code, message);
+            assert_eq!(error, expected, "{} {}", code, message);
+            assert!(!error.is_session_error(), "{}", code);
+        }
+        assert_eq!(
+            SmartApiError::from_code("AB2001", "Internal Error").user_message(),
+            "Internal Error"
+        );
+    }
+
+    #[test]
+    fn round_trips_through_display() {
+        for error in [
+            SmartApiError::InvalidToken,
+            SmartApiError::TokenExpired,
+            SmartApiError::InvalidRefreshToken,
+            SmartApiError::InvalidCredentials,
+            SmartApiError::InvalidTotp,
+            SmartApiError::AccountBlocked,
+            SmartApiError::SessionExpired,
+            SmartApiError::Amx("Order rejected: RMS".to_string()),
+            SmartApiError::InvalidApiKey,
+            SmartApiError::RateLimited,
+            SmartApiError::Api {
+                code: "AB2001".to_string(),
+                message: "Internal Error".to_string(),
+            },
+            SmartApiError::Network("connection refused".to_string()),
+            SmartApiError::Config("SMARTAPI_API_KEY is not set".to_string()),
+        ] {
+            let text = error.to_string();
+            assert_eq!(text.parse::<SmartApiError>(), Ok(error), "{}", text);
+        }
+    }
+}
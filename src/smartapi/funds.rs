@@ -0,0 +1,53 @@
+use super::de;
+use serde::{Deserialize, Serialize};
+
+#[cfg(feature = "server")]
+use super::{SmartApiClient, SmartApiError};
+
+/// Funds and margin summary as returned by `getRMS`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct Funds {
+    #[serde(deserialize_with = "de::number", default)]
+    pub net: f64,
+    #[serde(rename = "availablecash", deserialize_with = "de::number", default)]
+    pub available_cash: f64,
+    #[serde(
+        rename = "availablelimitmargin",
+        deserialize_with = "de::number",
+        default
+    )]
+    pub available_limit_margin: f64,
+    #[serde(deserialize_with = "de::number", default)]
+    pub collateral: f64,
+    #[serde(rename = "m2mrealized", deserialize_with = "de::number", default)]
+    pub m2m_realized: f64,
+    #[serde(rename = "m2munrealized", deserialize_with = "de::number", default)]
+    pub m2m_unrealized: f64,
+    #[serde(rename = "utiliseddebits", deserialize_with = "de::number", default)]
+    pub utilised_debits: f64,
+    #[serde(rename = "utilisedspan", deserialize_with = "de::number", default)]
+    pub utilised_span: f64,
+    #[serde(rename = "utilisedexposure", deserialize_with = "de::number", default)]
+    pub utilised_exposure: f64,
+    #[serde(
+        rename = "utilisedoptionpremium",
+        deserialize_with = "de::number",
+        default
+    )]
+    pub utilised_option_premium: f64,
+}
+
+impl Funds {
+    /// Margin blocked by open positions and orders.
+    pub fn used_margin(&self) -> f64 {
+        self.utilised_span + self.utilised_exposure + self.utilised_option_premium
+    }
+}
+
+#[cfg(feature = "server")]
+impl SmartApiClient {
+    pub async fn get_funds(&self, jwt_token: &str) -> Result<Funds, SmartApiError> {
+        self.get("rest/secure/angelbroking/user/v1/getRMS", Some(jwt_token))
+            .await
+    }
+}
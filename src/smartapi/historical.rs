@@ -0,0 +1,197 @@
+use serde::{Deserialize, Serialize};
+
+#[cfg(feature = "server")]
+use super::{de, SmartApiClient, SmartApiError};
+
+/// Bar length accepted by `getCandleData`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum CandleInterval {
+    OneMinute,
+    ThreeMinute,
+    FiveMinute,
+    TenMinute,
+    FifteenMinute,
+    ThirtyMinute,
+    OneHour,
+    OneDay,
+}
+
+impl CandleInterval {
+    pub const ALL: [Self; 8] = [
+        Self::OneMinute,
+        Self::ThreeMinute,
+        Self::FiveMinute,
+        Self::TenMinute,
+        Self::FifteenMinute,
+        Self::ThirtyMinute,
+        Self::OneHour,
+        Self::OneDay,
+    ];
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::OneMinute => "ONE_MINUTE",
+            Self::ThreeMinute => "THREE_MINUTE",
+            Self::FiveMinute => "FIVE_MINUTE",
+            Self::TenMinute => "TEN_MINUTE",
+            Self::FifteenMinute => "FIFTEEN_MINUTE",
+            Self::ThirtyMinute => "THIRTY_MINUTE",
+            Self::OneHour => "ONE_HOUR",
+            Self::OneDay => "ONE_DAY",
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::OneMinute => "1m",
+            Self::ThreeMinute => "3m",
+            Self::FiveMinute => "5m",
+            Self::TenMinute => "10m",
+            Self::FifteenMinute => "15m",
+            Self::ThirtyMinute => "30m",
+            Self::OneHour => "1h",
+            Self::OneDay => "1D",
+        }
+    }
+
+    /// Length of one bar in seconds.
+    pub fn secs(self) -> u64 {
+        60 * match self {
+            Self::OneMinute => 1,
+            Self::ThreeMinute => 3,
+            Self::FiveMinute => 5,
+            Self::TenMinute => 10,
+            Self::FifteenMinute => 15,
+            Self::ThirtyMinute => 30,
+            Self::OneHour => 60,
+            Self::OneDay => 24 * 60,
+        }
+    }
+
+    /// Most days of data Angel returns in one request.
+    pub fn max_days(self) -> u64 {
+        match self {
+            Self::OneMinute => 30,
+            Self::ThreeMinute => 60,
+            Self::FiveMinute | Self::TenMinute => 100,
+            Self::FifteenMinute | Self::ThirtyMinute => 200,
+            Self::OneHour => 400,
+            Self::OneDay => 2000,
+        }
+    }
+}
+
+/// One OHLCV bar.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+pub struct Candle {
+    /// Unix time in seconds the bar opens at, midnight IST for daily bars
+    pub time: u64,
+    pub open: f64,
+    pub high: f64,
+    pub low: f64,
+    pub close: f64,
+    pub volume: i64,
+}
+
+/// Body of `historical/v1/getCandleData`, dates as `YYYY-MM-DD HH:MM` in IST.
+#[cfg(feature = "server")]
+#[derive(Debug, Serialize)]
+pub struct CandleApiRequest {
+    pub exchange: String,
+    #[serde(rename = "symboltoken")]
+    pub symbol_token: String,
+    pub interval: CandleInterval,
+    #[serde(rename = "fromdate")]
+    pub from_date: String,
+    #[serde(rename = "todate")]
+    pub to_date: String,
+}
+
+// Candles arrive as `[timestamp, open, high, low, close, volume]` rows
+#[cfg(feature = "server")]
+#[derive(Deserialize)]
+struct CandleRow(
+    String,
+    #[serde(deserialize_with = "de::number")] f64,
+    #[serde(deserialize_with = "de::number")] f64,
+    #[serde(deserialize_with = "de::number")] f64,
+    #[serde(deserialize_with = "de::number")] f64,
+    #[serde(deserialize_with = "de::integer")] i64,
+);
+
+#[cfg(feature = "server")]
+impl CandleRow {
+    fn into_candle(self) -> Option<Candle> {
+        Some(Candle {
+            time: crate::time::parse_rfc3339(&self.0)?,
+            open: self.1,
+            high: self.2,
+            low: self.3,
+            close: self.4,
+            volume: self.5,
+        })
+    }
+}
+
+#[cfg(feature = "server")]
+impl SmartApiClient {
+    /// Candles for a range within [`CandleInterval::max_days`], oldest first.
+    pub async fn get_candle_data(
+        &self,
+        jwt_token: &str,
+        request: &CandleApiRequest,
+    ) -> Result<Vec<Candle>, SmartApiError> {
+        // Ranges without trading come back as `data: null`
+        let rows = self
+            .send::<_, Vec<CandleRow>>(
+                reqwest::Method::POST,
+                "rest/secure/angelbroking/historical/v1/getCandleData",
+                Some(jwt_token),
+                Some(request),
+            )
+            .await?
+            .data
+            .unwrap_or_default();
+        Ok(rows
+            .into_iter()
+            .filter_map(|row| {
+                let timestamp = row.0.clone();
+                let candle = row.into_candle();
+                if candle.is_none() {
+                    tracing::warn!("Skipping candle with timestamp {:?}", timestamp);
+                }
+                candle
+            })
+            .collect())
+    }
+}
+
+#[cfg(all(test, feature = "server"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_candle_rows() {
+        let rows: Vec<CandleRow> = serde_json::from_str(
+            r#"[["2024-10-16T09:15:00+05:30", 812.35, 815, 811.5, "814.2", 120450],
+                ["not a time", 1, 1, 1, 1, 0]]"#,
+        )
+        .unwrap();
+        let candles: Vec<Candle> = rows
+            .into_iter()
+            .filter_map(CandleRow::into_candle)
+            .collect();
+        assert_eq!(
+            candles,
+            vec![Candle {
+                time: 1_729_050_300,
+                open: 812.35,
+                high: 815.0,
+                low: 811.5,
+                close: 814.2,
+                volume: 120_450,
+            }]
+        );
+    }
+}
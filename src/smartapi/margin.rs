@@ -0,0 +1,118 @@
+use super::{de, OrderRequest, OrderType, ProductType, TransactionType};
+use serde::{Deserialize, Serialize};
+
+#[cfg(feature = "server")]
+use super::{SmartApiClient, SmartApiError};
+
+/// One leg of a basket sent to the batch margin calculator.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct MarginLeg {
+    pub exchange: String,
+    pub qty: i64,
+    pub price: f64,
+    pub product_type: ProductType,
+    pub token: String,
+    pub trade_type: TransactionType,
+    pub order_type: OrderType,
+}
+
+impl From<&OrderRequest> for MarginLeg {
+    fn from(order: &OrderRequest) -> Self {
+        Self {
+            exchange: order.exchange.clone(),
+            qty: order.quantity,
+            price: order.price,
+            product_type: order.product_type,
+            token: order.symbol_token.clone(),
+            trade_type: order.transaction_type,
+            order_type: order.order_type,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct MarginComponents {
+    #[serde(deserialize_with = "de::number", default)]
+    pub span_margin: f64,
+    #[serde(deserialize_with = "de::number", default)]
+    pub net_premium: f64,
+    #[serde(deserialize_with = "de::number", default)]
+    pub delivery_margin: f64,
+    #[serde(deserialize_with = "de::number", default)]
+    pub margin_benefit: f64,
+}
+
+/// Margin needed for a basket, as returned by `margin/v1/batch`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct MarginRequirement {
+    #[serde(
+        rename = "totalMarginRequired",
+        deserialize_with = "de::number",
+        default
+    )]
+    pub total: f64,
+    #[serde(default)]
+    pub margin_components: MarginComponents,
+}
+
+impl MarginRequirement {
+    pub fn span(&self) -> f64 {
+        self.margin_components.span_margin
+    }
+
+    /// Exposure margin, the part of the total not covered by SPAN, premium or delivery margin.
+    pub fn exposure(&self) -> f64 {
+        let components = &self.margin_components;
+        (self.total - components.span_margin - components.net_premium - components.delivery_margin)
+            .max(0.0)
+    }
+}
+
+#[cfg(feature = "server")]
+#[derive(Serialize)]
+struct MarginApiRequest<'a> {
+    positions: &'a [MarginLeg],
+}
+
+#[cfg(feature = "server")]
+impl SmartApiClient {
+    pub async fn calculate_margin(
+        &self,
+        jwt_token: &str,
+        legs: &[MarginLeg],
+    ) -> Result<MarginRequirement, SmartApiError> {
+        self.post(
+            "rest/secure/angelbroking/margin/v1/batch",
+            Some(jwt_token),
+            &MarginApiRequest { positions: legs },
+        )
+        .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn splits_total_into_span_and_exposure() {
+        let requirement: MarginRequirement = serde_json::from_value(serde_json::json!({
+            "totalMarginRequired": 150000.0,
+            "marginComponents": {
+                "netPremium": 0.0,
+                "spanMargin": 110000.0,
+                "marginBenefit": 0.0,
+                "deliveryMargin": 0.0,
+                "nonNetPremium": 0.0,
+                "totOptionsPremium": 0.0
+            },
+            "marginBreakup": []
+        }))
+        .unwrap();
+        assert_eq!(requirement.span(), 110000.0);
+        assert_eq!(requirement.exposure(), 40000.0);
+    }
+}
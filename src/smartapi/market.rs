@@ -0,0 +1,98 @@
+use super::de;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+#[cfg(feature = "server")]
+use super::{SmartApiClient, SmartApiError};
+
+/// Most instruments Angel quotes in a single request.
+pub const MAX_QUOTE_TOKENS: usize = 50;
+
+/// How much of the quote to return, `Full` includes the day's change.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "UPPERCASE")]
+pub enum QuoteMode {
+    Ltp,
+    Ohlc,
+    Full,
+}
+
+/// Body of `market/v1/quote`, tokens grouped by exchange.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct QuoteRequest {
+    pub mode: QuoteMode,
+    pub exchange_tokens: HashMap<String, Vec<String>>,
+}
+
+impl QuoteRequest {
+    pub fn new<'a>(
+        mode: QuoteMode,
+        instruments: impl IntoIterator<Item = (&'a str, &'a str)>,
+    ) -> Self {
+        let mut exchange_tokens: HashMap<String, Vec<String>> = HashMap::new();
+        for (exchange, token) in instruments {
+            exchange_tokens
+                .entry(exchange.to_string())
+                .or_default()
+                .push(token.to_string());
+        }
+        Self {
+            mode,
+            exchange_tokens,
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.exchange_tokens.values().all(Vec::is_empty)
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct Quote {
+    pub exchange: String,
+    pub trading_symbol: String,
+    pub symbol_token: String,
+    #[serde(deserialize_with = "de::number", default)]
+    pub ltp: f64,
+    #[serde(deserialize_with = "de::number", default)]
+    pub open: f64,
+    #[serde(deserialize_with = "de::number", default)]
+    pub high: f64,
+    #[serde(deserialize_with = "de::number", default)]
+    pub low: f64,
+    #[serde(deserialize_with = "de::number", default)]
+    pub close: f64,
+    /// Change from the previous close, only in `Full` mode
+    #[serde(deserialize_with = "de::number", default)]
+    pub net_change: f64,
+    #[serde(deserialize_with = "de::number", default)]
+    pub percent_change: f64,
+}
+
+#[cfg(feature = "server")]
+#[derive(Deserialize)]
+struct QuoteApiResponse {
+    #[serde(default)]
+    fetched: Vec<Quote>,
+}
+
+#[cfg(feature = "server")]
+impl SmartApiClient {
+    /// Quotes for up to [`MAX_QUOTE_TOKENS`] instruments, unknown tokens are left out.
+    pub async fn get_quotes(
+        &self,
+        jwt_token: &str,
+        request: &QuoteRequest,
+    ) -> Result<Vec<Quote>, SmartApiError> {
+        let response: QuoteApiResponse = self
+            .post(
+                "rest/secure/angelbroking/market/v1/quote/",
+                Some(jwt_token),
+                request,
+            )
+            .await?;
+        Ok(response.fetched)
+    }
+}
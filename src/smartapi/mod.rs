@@ -0,0 +1,55 @@
+//! Typed client for the Angel One SmartAPI REST endpoints.
+//!
+//! The HTTP client is only compiled into the server, while the data types
+//! returned by server functions are shared with the UI.
+
+mod error;
+pub use error::{error_message, SmartApiError};
+
+pub(crate) mod de;
+mod ser;
+
+mod funds;
+pub use funds::Funds;
+
+mod historical;
+#[cfg(feature = "server")]
+pub use historical::CandleApiRequest;
+pub use historical::{Candle, CandleInterval};
+
+mod margin;
+pub use margin::{MarginLeg, MarginRequirement};
+
+mod market;
+pub use market::{Quote, QuoteMode, QuoteRequest, MAX_QUOTE_TOKENS};
+
+mod orders;
+pub use orders::{
+    CancelOrderRequest, Duration, ModifyOrderRequest, Order, OrderFills, OrderRequest,
+    OrderResponse, OrderStatus, OrderType, Position, ProductType, Trade, TransactionType, Variety,
+    EXCHANGES,
+};
+
+mod portfolio;
+pub use portfolio::{Holding, Portfolio};
+
+mod profile;
+pub use profile::UserProfile;
+
+#[cfg(feature = "server")]
+mod client;
+#[cfg(feature = "server")]
+pub use client::SmartApiClient;
+
+#[cfg(feature = "server")]
+mod session;
+#[cfg(feature = "server")]
+pub use session::LoginApiRequest;
+
+/// JWT carried by an `Authorization` header value from `AuthContext::get_auth_header`.
+#[cfg(feature = "server")]
+pub fn bearer_token(authorization: &str) -> &str {
+    authorization
+        .strip_prefix("Bearer ")
+        .unwrap_or(authorization)
+}
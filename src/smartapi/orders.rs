@@ -0,0 +1,871 @@
+use super::{de, ser};
+use serde::{Deserialize, Serialize};
+
+#[cfg(feature = "server")]
+use super::{SmartApiClient, SmartApiError};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "UPPERCASE")]
+pub enum Variety {
+    Normal,
+    Stoploss,
+    Amo,
+    Robo,
+    /// Anything Angel adds later, kept so the order book still loads
+    #[serde(other)]
+    Unknown,
+}
+
+impl Variety {
+    pub const ALL: [Self; 4] = [Self::Normal, Self::Stoploss, Self::Amo, Self::Robo];
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Normal => "NORMAL",
+            Self::Stoploss => "STOPLOSS",
+            Self::Amo => "AMO",
+            Self::Robo => "ROBO",
+            Self::Unknown => "OTHER",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "UPPERCASE")]
+pub enum TransactionType {
+    Buy,
+    Sell,
+}
+
+impl TransactionType {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Buy => "BUY",
+            Self::Sell => "SELL",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum OrderType {
+    Market,
+    Limit,
+    StoplossLimit,
+    StoplossMarket,
+    #[serde(other)]
+    Unknown,
+}
+
+impl OrderType {
+    pub const ALL: [Self; 4] = [
+        Self::Market,
+        Self::Limit,
+        Self::StoplossLimit,
+        Self::StoplossMarket,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Market => "MARKET",
+            Self::Limit => "LIMIT",
+            Self::StoplossLimit => "SL",
+            Self::StoplossMarket => "SL-M",
+            Self::Unknown => "OTHER",
+        }
+    }
+
+    pub fn needs_price(self) -> bool {
+        matches!(self, Self::Limit | Self::StoplossLimit)
+    }
+
+    pub fn needs_trigger(self) -> bool {
+        matches!(self, Self::StoplossLimit | Self::StoplossMarket)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "UPPERCASE")]
+pub enum ProductType {
+    Delivery,
+    Carryforward,
+    Margin,
+    Intraday,
+    Bo,
+    Co,
+    #[serde(other)]
+    Unknown,
+}
+
+impl ProductType {
+    pub const ALL: [Self; 6] = [
+        Self::Delivery,
+        Self::Carryforward,
+        Self::Margin,
+        Self::Intraday,
+        Self::Bo,
+        Self::Co,
+    ];
+
+    /// Short name traders know the product by.
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Delivery => "CNC",
+            Self::Carryforward => "NRML",
+            Self::Margin => "MARGIN",
+            Self::Intraday => "MIS",
+            Self::Bo => "BO",
+            Self::Co => "CO",
+            Self::Unknown => "OTHER",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "UPPERCASE")]
+pub enum Duration {
+    Day,
+    Ioc,
+    #[serde(other)]
+    Unknown,
+}
+
+impl Duration {
+    pub const ALL: [Self; 2] = [Self::Day, Self::Ioc];
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Day => "DAY",
+            Self::Ioc => "IOC",
+            Self::Unknown => "OTHER",
+        }
+    }
+}
+
+/// Exchanges orders can be routed to.
+pub const EXCHANGES: [&str; 6] = ["NSE", "BSE", "NFO", "BFO", "MCX", "CDS"];
+
+/// Order parameters for `placeOrder`.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct OrderRequest {
+    pub variety: Variety,
+    #[serde(rename = "tradingsymbol")]
+    pub trading_symbol: String,
+    #[serde(rename = "symboltoken")]
+    pub symbol_token: String,
+    #[serde(rename = "transactiontype")]
+    pub transaction_type: TransactionType,
+    pub exchange: String,
+    #[serde(rename = "ordertype")]
+    pub order_type: OrderType,
+    #[serde(rename = "producttype")]
+    pub product_type: ProductType,
+    pub duration: Duration,
+    #[serde(serialize_with = "ser::as_string", deserialize_with = "de::number")]
+    pub price: f64,
+    #[serde(
+        rename = "triggerprice",
+        serialize_with = "ser::as_string",
+        deserialize_with = "de::number",
+        default
+    )]
+    pub trigger_price: f64,
+    /// ROBO target, as a price difference from the entry
+    #[serde(
+        rename = "squareoff",
+        serialize_with = "ser::as_string",
+        deserialize_with = "de::number",
+        default
+    )]
+    pub square_off: f64,
+    /// ROBO stop loss, as a price difference from the entry
+    #[serde(
+        rename = "stoploss",
+        serialize_with = "ser::as_string",
+        deserialize_with = "de::number",
+        default
+    )]
+    pub stop_loss: f64,
+    #[serde(serialize_with = "ser::as_string", deserialize_with = "de::integer")]
+    pub quantity: i64,
+}
+
+impl OrderRequest {
+    /// Market order for the DAY, the simplest way to enter or exit a position.
+    pub fn market(
+        exchange: &str,
+        trading_symbol: &str,
+        symbol_token: &str,
+        transaction_type: TransactionType,
+        product_type: ProductType,
+        quantity: i64,
+    ) -> Self {
+        Self {
+            variety: Variety::Normal,
+            trading_symbol: trading_symbol.to_string(),
+            symbol_token: symbol_token.to_string(),
+            transaction_type,
+            exchange: exchange.to_string(),
+            order_type: OrderType::Market,
+            product_type,
+            duration: Duration::Day,
+            price: 0.0,
+            trigger_price: 0.0,
+            square_off: 0.0,
+            stop_loss: 0.0,
+            quantity,
+        }
+    }
+
+    /// Check the parameter combinations Angel would reject.
+    pub fn validate(&self) -> Result<(), String> {
+        check_known_types(
+            self.variety,
+            self.order_type,
+            self.product_type,
+            self.duration,
+        )?;
+        if self.trading_symbol.trim().is_empty() || self.symbol_token.trim().is_empty() {
+            return Err("Select an instrument".to_string());
+        }
+        if self.quantity <= 0 {
+            return Err("Quantity must be greater than zero".to_string());
+        }
+        if self.order_type.needs_price() && self.price <= 0.0 {
+            return Err("Enter a price for a limit order".to_string());
+        }
+        if self.order_type.needs_trigger() && self.trigger_price <= 0.0 {
+            return Err("Enter a trigger price for a stop loss order".to_string());
+        }
+        if self.order_type.needs_trigger() != (self.variety == Variety::Stoploss) {
+            return Err("SL and SL-M orders must use the STOPLOSS variety".to_string());
+        }
+        check_trigger_side(
+            self.order_type,
+            self.transaction_type,
+            self.price,
+            self.trigger_price,
+        )?;
+        if self.variety == Variety::Robo {
+            if self.order_type != OrderType::Limit {
+                return Err("ROBO orders must be LIMIT orders".to_string());
+            }
+            if self.square_off <= 0.0 || self.stop_loss <= 0.0 {
+                return Err("ROBO orders need a target and a stop loss".to_string());
+            }
+        }
+        Ok(())
+    }
+}
+
+// Types read from Angel that the app has no name for can't be sent back
+fn check_known_types(
+    variety: Variety,
+    order_type: OrderType,
+    product_type: ProductType,
+    duration: Duration,
+) -> Result<(), String> {
+    if variety == Variety::Unknown
+        || order_type == OrderType::Unknown
+        || product_type == ProductType::Unknown
+        || duration == Duration::Unknown
+    {
+        return Err("This order type isn't supported here, use Angel One".to_string());
+    }
+    Ok(())
+}
+
+// A stop loss limit triggering past its limit price would fire at once
+fn check_trigger_side(
+    order_type: OrderType,
+    transaction_type: TransactionType,
+    price: f64,
+    trigger_price: f64,
+) -> Result<(), String> {
+    if order_type == OrderType::StoplossLimit {
+        let trigger_ok = match transaction_type {
+            TransactionType::Buy => trigger_price <= price,
+            TransactionType::Sell => trigger_price >= price,
+        };
+        if !trigger_ok {
+            return Err("Trigger price is on the wrong side of the limit price".to_string());
+        }
+    }
+    Ok(())
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct OrderResponse {
+    #[serde(rename = "orderid")]
+    pub order_id: String,
+    #[serde(rename = "uniqueorderid", default)]
+    pub unique_order_id: String,
+}
+
+/// Coarse order state used to filter the order book.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OrderStatus {
+    Open,
+    Complete,
+    Cancelled,
+    Rejected,
+}
+
+impl OrderStatus {
+    pub const ALL: [Self; 4] = [Self::Open, Self::Complete, Self::Cancelled, Self::Rejected];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Open => "Open",
+            Self::Complete => "Complete",
+            Self::Cancelled => "Cancelled",
+            Self::Rejected => "Rejected",
+        }
+    }
+}
+
+/// An entry of the day's order book from `getOrderBook`.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct Order {
+    #[serde(rename = "orderid")]
+    pub order_id: String,
+    pub variety: Variety,
+    #[serde(rename = "tradingsymbol")]
+    pub trading_symbol: String,
+    #[serde(rename = "symboltoken")]
+    pub symbol_token: String,
+    #[serde(rename = "transactiontype")]
+    pub transaction_type: TransactionType,
+    pub exchange: String,
+    #[serde(rename = "ordertype")]
+    pub order_type: OrderType,
+    #[serde(rename = "producttype")]
+    pub product_type: ProductType,
+    pub duration: Duration,
+    #[serde(deserialize_with = "de::number", default)]
+    pub price: f64,
+    #[serde(rename = "triggerprice", deserialize_with = "de::number", default)]
+    pub trigger_price: f64,
+    #[serde(rename = "averageprice", deserialize_with = "de::number", default)]
+    pub average_price: f64,
+    #[serde(deserialize_with = "de::integer", default)]
+    pub quantity: i64,
+    #[serde(rename = "filledshares", deserialize_with = "de::integer", default)]
+    pub filled_quantity: i64,
+    #[serde(rename = "unfilledshares", deserialize_with = "de::integer", default)]
+    pub pending_quantity: i64,
+    /// Angel's status text, e.g. "open", "trigger pending" or "rejected"
+    #[serde(default)]
+    pub status: String,
+    /// Rejection or cancellation reason
+    #[serde(default)]
+    pub text: String,
+    #[serde(rename = "updatetime", default)]
+    pub update_time: String,
+    #[serde(rename = "uniqueorderid", default)]
+    pub unique_order_id: String,
+    #[serde(rename = "exchangeorderid", default)]
+    pub exchange_order_id: String,
+    #[serde(rename = "exchorderupdatetime", default)]
+    pub exchange_update_time: String,
+}
+
+impl Order {
+    pub fn status_group(&self) -> OrderStatus {
+        match self.status.to_lowercase().as_str() {
+            "complete" => OrderStatus::Complete,
+            "cancelled" => OrderStatus::Cancelled,
+            "rejected" => OrderStatus::Rejected,
+            // open, trigger pending, modified, AMO received and the various pending states
+            _ => OrderStatus::Open,
+        }
+    }
+
+    /// Whether the order can still be modified or cancelled.
+    pub fn is_open(&self) -> bool {
+        self.status_group() == OrderStatus::Open
+    }
+
+    /// Request changing the price, quantity or trigger of this order.
+    pub fn modify(&self, price: f64, quantity: i64, trigger_price: f64) -> ModifyOrderRequest {
+        ModifyOrderRequest {
+            variety: self.variety,
+            order_id: self.order_id.clone(),
+            transaction_type: self.transaction_type,
+            order_type: self.order_type,
+            product_type: self.product_type,
+            duration: self.duration,
+            price,
+            trigger_price,
+            quantity,
+            trading_symbol: self.trading_symbol.clone(),
+            symbol_token: self.symbol_token.clone(),
+            exchange: self.exchange.clone(),
+        }
+    }
+
+    pub fn cancel(&self) -> CancelOrderRequest {
+        CancelOrderRequest {
+            variety: self.variety,
+            order_id: self.order_id.clone(),
+        }
+    }
+}
+
+/// Order parameters for `modifyOrder`.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ModifyOrderRequest {
+    pub variety: Variety,
+    #[serde(rename = "orderid")]
+    pub order_id: String,
+    /// Side of the order being modified, the trigger check depends on it
+    #[serde(rename = "transactiontype")]
+    pub transaction_type: TransactionType,
+    #[serde(rename = "ordertype")]
+    pub order_type: OrderType,
+    #[serde(rename = "producttype")]
+    pub product_type: ProductType,
+    pub duration: Duration,
+    #[serde(serialize_with = "ser::as_string", deserialize_with = "de::number")]
+    pub price: f64,
+    #[serde(
+        rename = "triggerprice",
+        serialize_with = "ser::as_string",
+        deserialize_with = "de::number"
+    )]
+    pub trigger_price: f64,
+    #[serde(serialize_with = "ser::as_string", deserialize_with = "de::integer")]
+    pub quantity: i64,
+    #[serde(rename = "tradingsymbol")]
+    pub trading_symbol: String,
+    #[serde(rename = "symboltoken")]
+    pub symbol_token: String,
+    pub exchange: String,
+}
+
+impl ModifyOrderRequest {
+    pub fn validate(&self) -> Result<(), String> {
+        check_known_types(
+            self.variety,
+            self.order_type,
+            self.product_type,
+            self.duration,
+        )?;
+        if self.quantity <= 0 {
+            return Err("Quantity must be greater than zero".to_string());
+        }
+        if self.order_type.needs_price() && self.price <= 0.0 {
+            return Err("Enter a price for a limit order".to_string());
+        }
+        if self.order_type.needs_trigger() && self.trigger_price <= 0.0 {
+            return Err("Enter a trigger price for a stop loss order".to_string());
+        }
+        check_trigger_side(
+            self.order_type,
+            self.transaction_type,
+            self.price,
+            self.trigger_price,
+        )
+    }
+}
+
+/// Order parameters for `cancelOrder`.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct CancelOrderRequest {
+    pub variety: Variety,
+    #[serde(rename = "orderid")]
+    pub order_id: String,
+}
+
+/// A single fill from `getTradeBook`.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct Trade {
+    #[serde(rename = "orderid")]
+    pub order_id: String,
+    #[serde(rename = "fillid")]
+    pub fill_id: String,
+    #[serde(rename = "filltime", default)]
+    pub fill_time: String,
+    #[serde(rename = "fillprice", deserialize_with = "de::number", default)]
+    pub fill_price: f64,
+    #[serde(rename = "fillsize", deserialize_with = "de::integer", default)]
+    pub fill_size: i64,
+    #[serde(rename = "tradevalue", deserialize_with = "de::number", default)]
+    pub trade_value: f64,
+    #[serde(rename = "tradingsymbol")]
+    pub trading_symbol: String,
+    pub exchange: String,
+    #[serde(rename = "producttype")]
+    pub product_type: ProductType,
+    #[serde(rename = "transactiontype")]
+    pub transaction_type: TransactionType,
+}
+
+/// All fills of one order.
+#[derive(Debug, Clone, PartialEq)]
+pub struct OrderFills {
+    pub order_id: String,
+    pub trades: Vec<Trade>,
+}
+
+impl OrderFills {
+    /// Group fills by order, keeping the trade book's order of first appearance.
+    pub fn group(trades: Vec<Trade>) -> Vec<Self> {
+        let mut groups: Vec<Self> = Vec::new();
+        for trade in trades {
+            match groups
+                .iter_mut()
+                .find(|group| group.order_id == trade.order_id)
+            {
+                Some(group) => group.trades.push(trade),
+                None => groups.push(Self {
+                    order_id: trade.order_id.clone(),
+                    trades: vec![trade],
+                }),
+            }
+        }
+        groups
+    }
+
+    pub fn quantity(&self) -> i64 {
+        self.trades.iter().map(|trade| trade.fill_size).sum()
+    }
+
+    /// Quantity weighted fill price.
+    pub fn average_price(&self) -> f64 {
+        let quantity = self.quantity();
+        if quantity == 0 {
+            return 0.0;
+        }
+        self.trades
+            .iter()
+            .map(|trade| trade.fill_price * trade.fill_size as f64)
+            .sum::<f64>()
+            / quantity as f64
+    }
+
+    pub fn value(&self) -> f64 {
+        self.trades.iter().map(|trade| trade.trade_value).sum()
+    }
+
+    /// The first fill, which carries the instrument and side of the order.
+    pub fn first(&self) -> &Trade {
+        &self.trades[0]
+    }
+}
+
+/// An open intraday or carried forward position from `getPosition`.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct Position {
+    pub exchange: String,
+    #[serde(rename = "tradingsymbol")]
+    pub trading_symbol: String,
+    #[serde(rename = "symboltoken")]
+    pub symbol_token: String,
+    #[serde(rename = "producttype")]
+    pub product_type: ProductType,
+    #[serde(rename = "buyqty", deserialize_with = "de::integer", default)]
+    pub buy_quantity: i64,
+    #[serde(rename = "sellqty", deserialize_with = "de::integer", default)]
+    pub sell_quantity: i64,
+    #[serde(rename = "netqty", deserialize_with = "de::integer", default)]
+    pub net_quantity: i64,
+    #[serde(rename = "buyavgprice", deserialize_with = "de::number", default)]
+    pub buy_average_price: f64,
+    #[serde(rename = "sellavgprice", deserialize_with = "de::number", default)]
+    pub sell_average_price: f64,
+    #[serde(rename = "avgnetprice", deserialize_with = "de::number", default)]
+    pub net_average_price: f64,
+    #[serde(deserialize_with = "de::number", default)]
+    pub ltp: f64,
+    #[serde(deserialize_with = "de::number", default)]
+    pub pnl: f64,
+    #[serde(deserialize_with = "de::number", default)]
+    pub realised: f64,
+    #[serde(deserialize_with = "de::number", default)]
+    pub unrealised: f64,
+}
+
+impl Position {
+    /// Quantity traded today, excluding anything carried forward.
+    pub fn day_quantity(&self) -> i64 {
+        self.buy_quantity - self.sell_quantity
+    }
+
+    pub fn is_traded_today(&self) -> bool {
+        self.buy_quantity != 0 || self.sell_quantity != 0
+    }
+
+    /// Opposite market order closing the net quantity, `None` when already flat.
+    pub fn square_off_order(&self) -> Option<OrderRequest> {
+        let transaction_type = match self.net_quantity {
+            0 => return None,
+            qty if qty > 0 => TransactionType::Sell,
+            _ => TransactionType::Buy,
+        };
+        Some(OrderRequest::market(
+            &self.exchange,
+            &self.trading_symbol,
+            &self.symbol_token,
+            transaction_type,
+            self.product_type,
+            self.net_quantity.abs(),
+        ))
+    }
+}
+
+#[cfg(feature = "server")]
+impl SmartApiClient {
+    pub async fn place_order(
+        &self,
+        jwt_token: &str,
+        order: &OrderRequest,
+    ) -> Result<OrderResponse, SmartApiError> {
+        self.post(
+            "rest/secure/angelbroking/order/v1/placeOrder",
+            Some(jwt_token),
+            order,
+        )
+        .await
+    }
+
+    pub async fn modify_order(
+        &self,
+        jwt_token: &str,
+        order: &ModifyOrderRequest,
+    ) -> Result<OrderResponse, SmartApiError> {
+        self.post(
+            "rest/secure/angelbroking/order/v1/modifyOrder",
+            Some(jwt_token),
+            order,
+        )
+        .await
+    }
+
+    pub async fn cancel_order(
+        &self,
+        jwt_token: &str,
+        order: &CancelOrderRequest,
+    ) -> Result<OrderResponse, SmartApiError> {
+        self.post(
+            "rest/secure/angelbroking/order/v1/cancelOrder",
+            Some(jwt_token),
+            order,
+        )
+        .await
+    }
+
+    pub async fn get_order_book(&self, jwt_token: &str) -> Result<Vec<Order>, SmartApiError> {
+        // Like positions, an empty book comes back as `data: null`
+        Ok(self
+            .send::<(), Vec<Order>>(
+                reqwest::Method::GET,
+                "rest/secure/angelbroking/order/v1/getOrderBook",
+                Some(jwt_token),
+                None,
+            )
+            .await?
+            .data
+            .unwrap_or_default())
+    }
+
+    pub async fn get_trade_book(&self, jwt_token: &str) -> Result<Vec<Trade>, SmartApiError> {
+        Ok(self
+            .send::<(), Vec<Trade>>(
+                reqwest::Method::GET,
+                "rest/secure/angelbroking/order/v1/getTradeBook",
+                Some(jwt_token),
+                None,
+            )
+            .await?
+            .data
+            .unwrap_or_default())
+    }
+
+    /// Latest state of a single order, looked up by its unique order id.
+    pub async fn get_order_details(
+        &self,
+        jwt_token: &str,
+        unique_order_id: &str,
+    ) -> Result<Order, SmartApiError> {
+        self.get(
+            &format!(
+                "rest/secure/angelbroking/order/v1/details/{}",
+                unique_order_id
+            ),
+            Some(jwt_token),
+        )
+        .await
+    }
+
+    pub async fn get_positions(&self, jwt_token: &str) -> Result<Vec<Position>, SmartApiError> {
+        // Angel returns `data: null` rather than an empty list when flat
+        Ok(self
+            .send::<(), Vec<Position>>(
+                reqwest::Method::GET,
+                "rest/secure/angelbroking/order/v1/getPosition",
+                Some(jwt_token),
+                None,
+            )
+            .await?
+            .data
+            .unwrap_or_default())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn limit_buy() -> OrderRequest {
+        OrderRequest {
+            order_type: OrderType::Limit,
+            price: 100.0,
+            ..OrderRequest::market(
+                "NSE",
+                "SBIN-EQ",
+                "3045",
+                TransactionType::Buy,
+                ProductType::Delivery,
+                1,
+            )
+        }
+    }
+
+    #[test]
+    fn serializes_numbers_as_strings() {
+        let json = serde_json::to_value(limit_buy()).unwrap();
+        assert_eq!(json["price"], "100");
+        assert_eq!(json["quantity"], "1");
+        assert_eq!(json["ordertype"], "LIMIT");
+        assert_eq!(json["producttype"], "DELIVERY");
+    }
+
+    #[test]
+    fn validates_stop_loss_orders() {
+        assert!(limit_buy().validate().is_ok());
+
+        let mut order = OrderRequest {
+            order_type: OrderType::StoplossLimit,
+            trigger_price: 99.0,
+            ..limit_buy()
+        };
+        assert!(order.validate().is_err(), "SL needs the STOPLOSS variety");
+
+        order.variety = Variety::Stoploss;
+        assert!(order.validate().is_ok());
+
+        order.trigger_price = 101.0;
+        assert!(
+            order.validate().is_err(),
+            "buy trigger above the limit price"
+        );
+    }
+
+    #[test]
+    fn parses_order_book_entries() {
+        let order: Order = serde_json::from_value(serde_json::json!({
+            "variety": "STOPLOSS",
+            "ordertype": "STOPLOSS_LIMIT",
+            "producttype": "INTRADAY",
+            "duration": "DAY",
+            "price": 194.0,
+            "triggerprice": "193.5",
+            "quantity": "10",
+            "tradingsymbol": "SBIN-EQ",
+            "transactiontype": "BUY",
+            "exchange": "NSE",
+            "symboltoken": "3045",
+            "averageprice": 0,
+            "filledshares": "0",
+            "unfilledshares": "10",
+            "orderid": "201020000000080",
+            "text": "",
+            "status": "trigger pending",
+            "updatetime": "20-Oct-2020 13:10:59"
+        }))
+        .unwrap();
+        assert_eq!(order.trigger_price, 193.5);
+        assert_eq!(order.pending_quantity, 10);
+        assert!(order.is_open());
+
+        let modify = order.modify(195.0, 5, 194.5);
+        assert!(modify.validate().is_ok());
+        let json = serde_json::to_value(&modify).unwrap();
+        assert_eq!(json["orderid"], "201020000000080");
+        assert_eq!(json["triggerprice"], "194.5");
+        assert_eq!(json["quantity"], "5");
+
+        // A buy stop loss triggering above its limit would fill at once
+        assert!(order.modify(195.0, 5, 196.0).validate().is_err());
+    }
+
+    #[test]
+    fn keeps_orders_with_unknown_types() {
+        let order: Order = serde_json::from_value(serde_json::json!({
+            "variety": "ICEBERG",
+            "ordertype": "LIMIT",
+            "producttype": "MTF",
+            "duration": "GTT",
+            "price": "194",
+            "quantity": "10",
+            "tradingsymbol": "SBIN-EQ",
+            "transactiontype": "BUY",
+            "exchange": "NSE",
+            "symboltoken": "3045",
+            "orderid": "201020000000081",
+            "status": "open"
+        }))
+        .unwrap();
+        assert_eq!(order.variety, Variety::Unknown);
+        assert_eq!(order.product_type, ProductType::Unknown);
+        assert_eq!(order.duration, Duration::Unknown);
+        assert_eq!(order.product_type.label(), "OTHER");
+        assert!(order.modify(195.0, 10, 0.0).validate().is_err());
+
+        // Survives the trip through a server function
+        let json = serde_json::to_string(&order).unwrap();
+        assert_eq!(serde_json::from_str::<Order>(&json).unwrap(), order);
+    }
+
+    #[test]
+    fn groups_fills_per_order() {
+        let fill = |order_id: &str, fill_id: &str, price: f64, size: i64| Trade {
+            order_id: order_id.to_string(),
+            fill_id: fill_id.to_string(),
+            fill_time: String::new(),
+            fill_price: price,
+            fill_size: size,
+            trade_value: price * size as f64,
+            trading_symbol: "SBIN-EQ".to_string(),
+            exchange: "NSE".to_string(),
+            product_type: ProductType::Intraday,
+            transaction_type: TransactionType::Buy,
+        };
+        let groups = OrderFills::group(vec![
+            fill("1", "a", 100.0, 10),
+            fill("2", "b", 50.0, 1),
+            fill("1", "c", 103.0, 20),
+        ]);
+
+        assert_eq!(groups.len(), 2);
+        assert_eq!(groups[0].order_id, "1");
+        assert_eq!(groups[0].quantity(), 30);
+        assert_eq!(groups[0].average_price(), 102.0);
+        assert_eq!(groups[1].trades.len(), 1);
+    }
+
+    #[test]
+    fn validates_robo_orders() {
+        let mut order = OrderRequest {
+            variety: Variety::Robo,
+            product_type: ProductType::Bo,
+            ..limit_buy()
+        };
+        assert!(order.validate().is_err());
+
+        order.square_off = 5.0;
+        order.stop_loss = 2.0;
+        assert!(order.validate().is_ok());
+    }
+}
@@ -0,0 +1,99 @@
+use super::de;
+use serde::{Deserialize, Serialize};
+
+#[cfg(feature = "server")]
+use super::{SmartApiClient, SmartApiError};
+
+/// A long term holding in the demat account.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct Holding {
+    #[serde(rename = "tradingsymbol")]
+    pub trading_symbol: String,
+    pub exchange: String,
+    #[serde(rename = "symboltoken", default)]
+    pub symbol_token: String,
+    #[serde(default)]
+    pub isin: String,
+    #[serde(default)]
+    pub product: String,
+    #[serde(deserialize_with = "de::integer", default)]
+    pub quantity: i64,
+    #[serde(rename = "t1quantity", deserialize_with = "de::integer", default)]
+    pub t1_quantity: i64,
+    #[serde(rename = "averageprice", deserialize_with = "de::number", default)]
+    pub average_price: f64,
+    #[serde(deserialize_with = "de::number", default)]
+    pub ltp: f64,
+    #[serde(deserialize_with = "de::number", default)]
+    pub close: f64,
+    #[serde(rename = "profitandloss", deserialize_with = "de::number", default)]
+    pub profit_and_loss: f64,
+    #[serde(rename = "pnlpercentage", deserialize_with = "de::number", default)]
+    pub pnl_percentage: f64,
+}
+
+impl Holding {
+    /// Quantity including shares bought in the last session that are yet to settle.
+    pub fn total_quantity(&self) -> i64 {
+        self.quantity + self.t1_quantity
+    }
+
+    pub fn day_change(&self) -> f64 {
+        (self.ltp - self.close) * self.total_quantity() as f64
+    }
+
+    pub fn day_change_percentage(&self) -> f64 {
+        if self.close == 0.0 {
+            0.0
+        } else {
+            (self.ltp - self.close) / self.close * 100.0
+        }
+    }
+}
+
+/// Portfolio wide totals reported alongside the holdings.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct HoldingTotals {
+    #[serde(rename = "totalholdingvalue", deserialize_with = "de::number", default)]
+    pub holding_value: f64,
+    #[serde(rename = "totalinvvalue", deserialize_with = "de::number", default)]
+    pub investment_value: f64,
+    #[serde(
+        rename = "totalprofitandloss",
+        deserialize_with = "de::number",
+        default
+    )]
+    pub profit_and_loss: f64,
+    #[serde(
+        rename = "totalpnlpercentage",
+        deserialize_with = "de::number",
+        default
+    )]
+    pub pnl_percentage: f64,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct Portfolio {
+    #[serde(default)]
+    pub holdings: Vec<Holding>,
+    #[serde(rename = "totalholding", default)]
+    pub totals: HoldingTotals,
+}
+
+impl Portfolio {
+    pub fn day_change(&self) -> f64 {
+        self.holdings.iter().map(Holding::day_change).sum()
+    }
+}
+
+#[cfg(feature = "server")]
+impl SmartApiClient {
+    /// Holdings with portfolio totals (`getAllHolding`).
+    pub async fn get_all_holdings(&self, jwt_token: &str) -> Result<Portfolio, SmartApiError> {
+        self.get(
+            "rest/secure/angelbroking/portfolio/v1/getAllHolding",
+            Some(jwt_token),
+        )
+        .await
+    }
+}
@@ -0,0 +1,31 @@
+use serde::{Deserialize, Serialize};
+
+#[cfg(feature = "server")]
+use super::{SmartApiClient, SmartApiError};
+
+/// Trader profile as returned by `getProfile`.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct UserProfile {
+    #[serde(rename = "clientcode")]
+    pub client_code: String,
+    pub name: String,
+    #[serde(default)]
+    pub email: String,
+    #[serde(rename = "brokerid", default)]
+    pub broker_id: String,
+    #[serde(default)]
+    pub exchanges: Vec<String>,
+    #[serde(default)]
+    pub products: Vec<String>,
+}
+
+#[cfg(feature = "server")]
+impl SmartApiClient {
+    pub async fn get_profile(&self, jwt_token: &str) -> Result<UserProfile, SmartApiError> {
+        self.get(
+            "rest/secure/angelbroking/user/v1/getProfile",
+            Some(jwt_token),
+        )
+        .await
+    }
+}
@@ -0,0 +1,11 @@
+use serde::Serializer;
+use std::fmt::Display;
+
+/// SmartAPI expects numeric order fields as JSON strings.
+pub fn as_string<S, T>(value: &T, serializer: S) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+    T: Display,
+{
+    serializer.collect_str(value)
+}
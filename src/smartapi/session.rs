@@ -0,0 +1,122 @@
+use super::{SmartApiClient, SmartApiError};
+use crate::secret::Secret;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Serialize)]
+pub struct LoginApiRequest {
+    pub clientcode: String,
+    pub password: Secret<String>,
+    pub totp: Secret<String>,
+}
+
+#[derive(Debug, Serialize)]
+struct RefreshApiRequest<'a> {
+    #[serde(rename = "refreshToken")]
+    refresh_token: Secret<&'a str>,
+}
+
+#[derive(Debug, Serialize)]
+struct LogoutApiRequest<'a> {
+    clientcode: &'a str,
+}
+
+/// Token triple returned by both `loginByPassword` and `generateTokens`.
+#[derive(Debug, Deserialize)]
+pub struct LoginApiResponseData {
+    #[serde(rename = "jwtToken")]
+    pub jwt_token: Secret<String>,
+    #[serde(rename = "refreshToken")]
+    pub refresh_token: Secret<String>,
+    #[serde(rename = "feedToken")]
+    pub feed_token: Secret<String>,
+}
+
+impl SmartApiClient {
+    pub async fn login(
+        &self,
+        request: &LoginApiRequest,
+    ) -> Result<LoginApiResponseData, SmartApiError> {
+        self.post(
+            "rest/auth/angelbroking/user/v1/loginByPassword",
+            None,
+            request,
+        )
+        .await
+    }
+
+    pub async fn generate_tokens(
+        &self,
+        jwt_token: &str,
+        refresh_token: &str,
+    ) -> Result<LoginApiResponseData, SmartApiError> {
+        let request = RefreshApiRequest {
+            refresh_token: Secret::new(refresh_token),
+        };
+        self.post(
+            "rest/auth/angelbroking/jwt/v1/generateTokens",
+            Some(jwt_token),
+            &request,
+        )
+        .await
+    }
+
+    pub async fn logout(&self, jwt_token: &str, clientcode: &str) -> Result<(), SmartApiError> {
+        let request = LogoutApiRequest { clientcode };
+        self.send::<_, serde_json::Value>(
+            reqwest::Method::POST,
+            "rest/secure/angelbroking/user/v1/logout",
+            Some(jwt_token),
+            Some(&request),
+        )
+        .await?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn login_request_debug_is_redacted() {
+        let request = LoginApiRequest {
+            clientcode: "A123456".to_string(),
+            password: Secret::new("pass-secret".to_string()),
+            totp: Secret::new("987654".to_string()),
+        };
+
+        let output = format!("{:?}", request);
+        assert!(!output.contains("pass-secret"));
+        assert!(!output.contains("987654"));
+        assert!(output.contains("A123456"));
+
+        // The wire format still carries the real values
+        let json = serde_json::to_string(&request).unwrap();
+        assert!(json.contains("\"password\":\"pass-secret\""));
+    }
+
+    #[test]
+    fn refresh_request_debug_is_redacted() {
+        let request = RefreshApiRequest {
+            refresh_token: Secret::new("refresh-secret"),
+        };
+
+        let output = format!("{:?}", request);
+        assert!(!output.contains("refresh-secret"), "leaked: {}", output);
+
+        let json = serde_json::to_string(&request).unwrap();
+        assert_eq!(json, r#"{"refreshToken":"refresh-secret"}"#);
+    }
+
+    #[test]
+    fn login_response_debug_is_redacted() {
+        let data: LoginApiResponseData = serde_json::from_str(
+            r#"{"jwtToken":"jwt-secret","refreshToken":"refresh-secret","feedToken":"feed-secret"}"#,
+        )
+        .unwrap();
+
+        let output = format!("{:?}", data);
+        assert!(!output.contains("secret"), "leaked: {}", output);
+        assert_eq!(data.jwt_token.expose(), "jwt-secret");
+    }
+}
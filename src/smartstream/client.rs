@@ -0,0 +1,335 @@
+use super::{ExchangeType, FeedSession, Subscription, SubscriptionMode, Tick};
+use crate::secret::Secret;
+use futures::{SinkExt, StreamExt};
+use std::collections::{BTreeSet, HashMap};
+use std::time::Duration;
+use tokio::sync::{broadcast, mpsc};
+use tokio_tungstenite::tungstenite::client::IntoClientRequest;
+use tokio_tungstenite::tungstenite::http::HeaderValue;
+use tokio_tungstenite::tungstenite::Message;
+
+// Angel drops connections that stay silent for longer than this
+const HEARTBEAT_INTERVAL: Duration = Duration::from_secs(30);
+const INITIAL_BACKOFF: Duration = Duration::from_secs(1);
+const MAX_BACKOFF: Duration = Duration::from_secs(60);
+// Ticks buffered per listener before the slowest one starts skipping
+const TICK_BUFFER: usize = 1024;
+
+const ACTION_UNSUBSCRIBE: u8 = 0;
+const ACTION_SUBSCRIBE: u8 = 1;
+
+enum Command {
+    Subscribe(Subscription),
+    Unsubscribe(Subscription),
+}
+
+/// Handle to a SmartStream connection running in the background.
+///
+/// The connection heartbeats, reconnects with backoff and resubscribes on
+/// its own. It closes once the handle is dropped.
+pub struct SmartStream {
+    feed_token: Secret<String>,
+    commands: mpsc::UnboundedSender<Command>,
+    ticks: broadcast::Sender<Tick>,
+}
+
+impl SmartStream {
+    pub fn connect(url: String, session: FeedSession, api_key: Secret<String>) -> Self {
+        let (commands, receiver) = mpsc::unbounded_channel();
+        let (ticks, _) = broadcast::channel(TICK_BUFFER);
+        let feed_token = session.feed_token.clone();
+        tokio::spawn(run(url, session, api_key, receiver, ticks.clone()));
+        Self {
+            feed_token,
+            commands,
+            ticks,
+        }
+    }
+
+    /// Whether the connection was opened with this feed token.
+    pub fn uses_feed_token(&self, feed_token: &str) -> bool {
+        self.feed_token.expose() == feed_token
+    }
+
+    pub fn subscribe(&self, subscription: Subscription) {
+        let _ = self.commands.send(Command::Subscribe(subscription));
+    }
+
+    pub fn unsubscribe(&self, subscription: Subscription) {
+        let _ = self.commands.send(Command::Unsubscribe(subscription));
+    }
+
+    pub fn ticks(&self) -> broadcast::Receiver<Tick> {
+        self.ticks.subscribe()
+    }
+
+    pub fn listener_count(&self) -> usize {
+        self.ticks.receiver_count()
+    }
+}
+
+// Tokens currently subscribed, so they can be replayed after a reconnect
+#[derive(Default)]
+struct Subscriptions(HashMap<SubscriptionMode, HashMap<ExchangeType, BTreeSet<String>>>);
+
+impl Subscriptions {
+    /// Record the command and return the request for tokens that actually changed.
+    fn apply(&mut self, command: Command) -> Option<String> {
+        let (action, subscription) = match command {
+            Command::Subscribe(subscription) => (ACTION_SUBSCRIBE, subscription),
+            Command::Unsubscribe(subscription) => (ACTION_UNSUBSCRIBE, subscription),
+        };
+        let tokens = self
+            .0
+            .entry(subscription.mode)
+            .or_default()
+            .entry(subscription.exchange_type)
+            .or_default();
+        let changed: Vec<String> = subscription
+            .tokens
+            .into_iter()
+            .filter(|token| match action {
+                ACTION_SUBSCRIBE => tokens.insert(token.clone()),
+                _ => tokens.remove(token),
+            })
+            .collect();
+
+        (!changed.is_empty()).then(|| {
+            request(
+                action,
+                subscription.mode,
+                &[(subscription.exchange_type, changed)],
+            )
+        })
+    }
+
+    /// Requests subscribing everything again on a new connection.
+    fn replay(&self) -> Vec<String> {
+        self.0
+            .iter()
+            .map(|(&mode, exchanges)| {
+                let token_list: Vec<(ExchangeType, Vec<String>)> = exchanges
+                    .iter()
+                    .filter(|(_, tokens)| !tokens.is_empty())
+                    .map(|(&exchange_type, tokens)| {
+                        (exchange_type, tokens.iter().cloned().collect())
+                    })
+                    .collect();
+                (mode, token_list)
+            })
+            .filter(|(_, token_list)| !token_list.is_empty())
+            .map(|(mode, token_list)| request(ACTION_SUBSCRIBE, mode, &token_list))
+            .collect()
+    }
+}
+
+fn request(
+    action: u8,
+    mode: SubscriptionMode,
+    token_list: &[(ExchangeType, Vec<String>)],
+) -> String {
+    let token_list: Vec<serde_json::Value> = token_list
+        .iter()
+        .map(|(exchange_type, tokens)| {
+            serde_json::json!({ "exchangeType": exchange_type.code(), "tokens": tokens })
+        })
+        .collect();
+    serde_json::json!({
+        "action": action,
+        "params": { "mode": mode.code(), "tokenList": token_list },
+    })
+    .to_string()
+}
+
+async fn run(
+    url: String,
+    session: FeedSession,
+    api_key: Secret<String>,
+    mut commands: mpsc::UnboundedReceiver<Command>,
+    ticks: broadcast::Sender<Tick>,
+) {
+    let mut subscriptions = Subscriptions::default();
+    let mut backoff = INITIAL_BACKOFF;
+
+    loop {
+        match connection(
+            &url,
+            &session,
+            &api_key,
+            &mut subscriptions,
+            &mut commands,
+            &ticks,
+        )
+        .await
+        {
+            Ok(()) => {
+                tracing::info!("SmartStream closed for {}", session.client_code);
+                return;
+            }
+            Err(SessionError::Connect(e)) => {
+                tracing::warn!("SmartStream connection failed: {}", e);
+            }
+            Err(SessionError::Disconnected(e)) => {
+                tracing::warn!("SmartStream disconnected: {}", e);
+                backoff = INITIAL_BACKOFF;
+            }
+        }
+
+        // Keep track of subscription changes while waiting to reconnect
+        let wait = tokio::time::sleep(backoff);
+        tokio::pin!(wait);
+        loop {
+            tokio::select! {
+                _ = &mut wait => break,
+                command = commands.recv() => match command {
+                    Some(command) => {
+                        subscriptions.apply(command);
+                    }
+                    None => return,
+                },
+            }
+        }
+        backoff = (backoff * 2).min(MAX_BACKOFF);
+    }
+}
+
+enum SessionError {
+    Connect(String),
+    Disconnected(String),
+}
+
+// One connection, returns `Ok` once every handle is gone
+async fn connection(
+    url: &str,
+    session: &FeedSession,
+    api_key: &Secret<String>,
+    subscriptions: &mut Subscriptions,
+    commands: &mut mpsc::UnboundedReceiver<Command>,
+    ticks: &broadcast::Sender<Tick>,
+) -> Result<(), SessionError> {
+    let mut request = url
+        .into_client_request()
+        .map_err(|e| SessionError::Connect(e.to_string()))?;
+    let headers = request.headers_mut();
+    for (name, value) in [
+        ("Authorization", session.jwt_token.expose()),
+        ("x-api-key", api_key.expose()),
+        ("x-client-code", &session.client_code),
+        ("x-feed-token", session.feed_token.expose()),
+    ] {
+        let mut value = HeaderValue::from_str(value)
+            .map_err(|_| SessionError::Connect(format!("{} is not a valid header value", name)))?;
+        value.set_sensitive(true);
+        headers.insert(name, value);
+    }
+
+    let (socket, _) = tokio_tungstenite::connect_async(request)
+        .await
+        .map_err(|e| SessionError::Connect(e.to_string()))?;
+    tracing::info!("SmartStream connected for {}", session.client_code);
+    let (mut write, mut read) = socket.split();
+    let disconnected =
+        |e: tokio_tungstenite::tungstenite::Error| SessionError::Disconnected(e.to_string());
+
+    for request in subscriptions.replay() {
+        write
+            .send(Message::Text(request))
+            .await
+            .map_err(disconnected)?;
+    }
+
+    let mut heartbeat = tokio::time::interval(HEARTBEAT_INTERVAL);
+    heartbeat.tick().await;
+    loop {
+        tokio::select! {
+            command = commands.recv() => {
+                let Some(command) = command else {
+                    let _ = write.close().await;
+                    return Ok(());
+                };
+                if let Some(request) = subscriptions.apply(command) {
+                    write.send(Message::Text(request)).await.map_err(disconnected)?;
+                }
+            }
+            message = read.next() => match message {
+                Some(Ok(Message::Binary(packet))) => match Tick::decode(&packet) {
+                    // No listeners is fine, the UI may be reconnecting
+                    Ok(tick) => {
+                        let _ = ticks.send(tick);
+                    }
+                    Err(e) => tracing::debug!("Skipping SmartStream packet: {}", e),
+                },
+                Some(Ok(Message::Text(text))) if text == "pong" => {}
+                Some(Ok(Message::Text(text))) => tracing::warn!("SmartStream error: {}", text),
+                Some(Ok(Message::Close(frame))) => {
+                    return Err(SessionError::Disconnected(format!("closed by server: {:?}", frame)));
+                }
+                Some(Ok(_)) => {}
+                Some(Err(e)) => return Err(disconnected(e)),
+                None => return Err(SessionError::Disconnected("connection closed".to_string())),
+            },
+            _ = heartbeat.tick() => {
+                write.send(Message::Text("ping".to_string())).await.map_err(disconnected)?;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn subscription(tokens: &[&str]) -> Subscription {
+        Subscription {
+            mode: SubscriptionMode::Ltp,
+            exchange_type: ExchangeType::NseCm,
+            tokens: tokens.iter().map(|token| token.to_string()).collect(),
+        }
+    }
+
+    #[test]
+    fn only_sends_tokens_that_changed() {
+        let mut subscriptions = Subscriptions::default();
+        let request = subscriptions.apply(Command::Subscribe(subscription(&["3045", "1333"])));
+        let request: serde_json::Value = serde_json::from_str(&request.unwrap()).unwrap();
+        assert_eq!(request["action"], 1);
+        assert_eq!(request["params"]["mode"], 1);
+        assert_eq!(request["params"]["tokenList"][0]["exchangeType"], 1);
+
+        assert_eq!(
+            subscriptions.apply(Command::Subscribe(subscription(&["3045"]))),
+            None
+        );
+        assert_eq!(
+            subscriptions.apply(Command::Unsubscribe(subscription(&["99926000"]))),
+            None
+        );
+
+        let request =
+            subscriptions.apply(Command::Unsubscribe(subscription(&["3045", "99926000"])));
+        let request: serde_json::Value = serde_json::from_str(&request.unwrap()).unwrap();
+        assert_eq!(request["action"], 0);
+        assert_eq!(
+            request["params"]["tokenList"][0]["tokens"],
+            serde_json::json!(["3045"])
+        );
+    }
+
+    #[test]
+    fn replays_remaining_subscriptions() {
+        let mut subscriptions = Subscriptions::default();
+        subscriptions.apply(Command::Subscribe(subscription(&["3045", "1333"])));
+        subscriptions.apply(Command::Unsubscribe(subscription(&["3045"])));
+
+        let replay = subscriptions.replay();
+        assert_eq!(replay.len(), 1);
+        let request: serde_json::Value = serde_json::from_str(&replay[0]).unwrap();
+        assert_eq!(
+            request["params"]["tokenList"][0]["tokens"],
+            serde_json::json!(["1333"])
+        );
+
+        subscriptions.apply(Command::Unsubscribe(subscription(&["1333"])));
+        assert!(subscriptions.replay().is_empty());
+    }
+}
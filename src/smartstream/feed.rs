@@ -0,0 +1,273 @@
+use super::{
+    stream_ticks_server, subscribe_feed_server, unsubscribe_feed_server, ExchangeType, FeedSession,
+    Subscription, SubscriptionMode, Tick,
+};
+use crate::auth::{use_auth, AuthContext, AuthState};
+use crate::time::sleep;
+use dioxus::prelude::server_fn::codec::TextStream;
+use dioxus::prelude::*;
+use futures::future::{self, Either};
+use futures::StreamExt;
+use std::collections::HashMap;
+use std::time::Duration;
+
+const IDLE_CHECK_INTERVAL: Duration = Duration::from_secs(1);
+// How quickly an open stream notices a logout or new login, ticks or not
+const SESSION_CHECK_INTERVAL: Duration = Duration::from_secs(1);
+const INITIAL_BACKOFF: Duration = Duration::from_secs(1);
+const MAX_BACKOFF: Duration = Duration::from_secs(30);
+
+/// Live ticks shared by every screen, see [`MarketFeedProvider`].
+#[derive(Clone, Copy)]
+pub struct MarketFeed {
+    auth: AuthContext,
+    ticks: Signal<HashMap<(ExchangeType, String), Tick>>,
+    // Number of components wanting each instrument in each mode
+    subscriptions: Signal<HashMap<(SubscriptionMode, ExchangeType, String), usize>>,
+    // Set while a tick stream is open or opening, subscriptions go straight to the server then
+    streaming: Signal<bool>,
+    connected: Signal<bool>,
+}
+
+impl MarketFeed {
+    /// Latest tick for an instrument, if one arrived this session.
+    pub fn tick(&self, exchange_type: ExchangeType, token: &str) -> Option<Tick> {
+        self.ticks
+            .read()
+            .get(&(exchange_type, token.to_string()))
+            .cloned()
+    }
+
+    /// Whether the tick stream is open right now, false while it reconnects.
+    pub fn is_connected(&self) -> bool {
+        *self.connected.read()
+    }
+
+    pub fn subscribe(&mut self, mode: SubscriptionMode, instruments: &[(ExchangeType, String)]) {
+        let mut added = Vec::new();
+        {
+            let mut subscriptions = self.subscriptions.write();
+            for (exchange_type, token) in instruments {
+                let count = subscriptions
+                    .entry((mode, *exchange_type, token.clone()))
+                    .or_default();
+                *count += 1;
+                if *count == 1 {
+                    added.push((*exchange_type, token.clone()));
+                }
+            }
+        }
+        self.update_server(true, mode, added);
+    }
+
+    pub fn unsubscribe(&mut self, mode: SubscriptionMode, instruments: &[(ExchangeType, String)]) {
+        let mut removed = Vec::new();
+        {
+            let mut subscriptions = self.subscriptions.write();
+            for (exchange_type, token) in instruments {
+                let key = (mode, *exchange_type, token.clone());
+                let Some(count) = subscriptions.get_mut(&key) else {
+                    continue;
+                };
+                *count -= 1;
+                if *count == 0 {
+                    subscriptions.remove(&key);
+                    removed.push((*exchange_type, token.clone()));
+                }
+            }
+        }
+        self.update_server(false, mode, removed);
+    }
+
+    // Without an open stream the next one sends everything wanted anyway
+    fn update_server(
+        &self,
+        subscribe: bool,
+        mode: SubscriptionMode,
+        instruments: Vec<(ExchangeType, String)>,
+    ) {
+        if instruments.is_empty() || !*self.streaming.peek() {
+            return;
+        }
+        let Some(session) = self.session() else {
+            return;
+        };
+        // Unsubscribing happens as components unmount, so the task can't belong to them
+        spawn_forever(async move {
+            for subscription in group(mode, instruments) {
+                let result = if subscribe {
+                    subscribe_feed_server(session.clone(), subscription).await
+                } else {
+                    unsubscribe_feed_server(session.clone(), subscription).await
+                };
+                if let Err(e) = result {
+                    tracing::warn!("Failed to update market feed subscriptions: {}", e);
+                }
+            }
+        });
+    }
+
+    // The signed in session, without subscribing the caller to auth changes
+    fn session(&self) -> Option<FeedSession> {
+        match &*self.auth.state.peek() {
+            AuthState::Authenticated(tokens) => Some(FeedSession::from(tokens)),
+            _ => None,
+        }
+    }
+
+    // Everything wanted, grouped into one subscription per mode and exchange
+    fn wanted(&self) -> Vec<Subscription> {
+        let mut grouped: HashMap<(SubscriptionMode, ExchangeType), Vec<String>> = HashMap::new();
+        for (mode, exchange_type, token) in self.subscriptions.peek().keys() {
+            grouped
+                .entry((*mode, *exchange_type))
+                .or_default()
+                .push(token.clone());
+        }
+        grouped
+            .into_iter()
+            .map(|((mode, exchange_type), tokens)| Subscription {
+                mode,
+                exchange_type,
+                tokens,
+            })
+            .collect()
+    }
+
+    fn is_current(&self, session: &FeedSession) -> bool {
+        self.session()
+            .is_some_and(|current| current.feed_token == session.feed_token)
+    }
+
+    // Read ticks until the stream ends or the session it was opened with is
+    // over. Dropping the stream closes it, which lets the server disconnect.
+    async fn receive(&mut self, stream: TextStream, session: &FeedSession) {
+        let feed = *self;
+        // A logout or new login ends the stream, even while no ticks arrive
+        let mut session_over = std::pin::pin!(async move {
+            while feed.is_current(session) {
+                sleep(SESSION_CHECK_INTERVAL).await;
+            }
+        });
+        let mut chunks = stream.into_inner();
+        let mut buffer = String::new();
+        loop {
+            let chunk = match future::select(chunks.next(), session_over.as_mut()).await {
+                Either::Left((Some(chunk), _)) => chunk,
+                Either::Left((None, _)) | Either::Right(_) => return,
+            };
+            match chunk {
+                Ok(chunk) => buffer.push_str(&chunk),
+                Err(e) => {
+                    tracing::warn!("Market feed interrupted: {}", e);
+                    return;
+                }
+            }
+            // Chunks don't line up with ticks, keep any partial line for the next one
+            while let Some(end) = buffer.find('\n') {
+                let line: String = buffer.drain(..=end).collect();
+                match serde_json::from_str::<Tick>(line.trim_end()) {
+                    Ok(tick) => {
+                        self.ticks
+                            .write()
+                            .insert((tick.exchange_type, tick.token.clone()), tick);
+                    }
+                    Err(e) => tracing::warn!("Skipping malformed tick: {}", e),
+                }
+            }
+        }
+    }
+}
+
+fn group(mode: SubscriptionMode, instruments: Vec<(ExchangeType, String)>) -> Vec<Subscription> {
+    let mut grouped: HashMap<ExchangeType, Vec<String>> = HashMap::new();
+    for (exchange_type, token) in instruments {
+        grouped.entry(exchange_type).or_default().push(token);
+    }
+    grouped
+        .into_iter()
+        .map(|(exchange_type, tokens)| Subscription {
+            mode,
+            exchange_type,
+            tokens,
+        })
+        .collect()
+}
+
+pub fn use_market_feed() -> MarketFeed {
+    use_context::<MarketFeed>()
+}
+
+/// Keep `instruments` subscribed in `mode` while the calling component is mounted.
+pub fn use_feed_subscription(
+    mode: SubscriptionMode,
+    instruments: Memo<Vec<(ExchangeType, String)>>,
+) {
+    let mut feed = use_market_feed();
+    let mut current = use_signal(Vec::<(ExchangeType, String)>::new);
+
+    use_effect(move || {
+        let wanted = instruments();
+        let previous = current.peek().clone();
+        let removed: Vec<_> = previous
+            .iter()
+            .filter(|i| !wanted.contains(i))
+            .cloned()
+            .collect();
+        let added: Vec<_> = wanted
+            .iter()
+            .filter(|i| !previous.contains(i))
+            .cloned()
+            .collect();
+        feed.unsubscribe(mode, &removed);
+        feed.subscribe(mode, &added);
+        current.set(wanted);
+    });
+
+    use_drop(move || feed.unsubscribe(mode, &current.peek()));
+}
+
+/// Streams ticks for whatever the mounted components subscribed, while signed in.
+///
+/// Reconnects with backoff when the stream drops, and after a new login.
+#[component]
+pub fn MarketFeedProvider(children: Element) -> Element {
+    let auth = use_auth();
+    let mut feed = use_context_provider(|| MarketFeed {
+        auth,
+        ticks: Signal::new(HashMap::new()),
+        subscriptions: Signal::new(HashMap::new()),
+        streaming: Signal::new(false),
+        connected: Signal::new(false),
+    });
+
+    use_future(move || async move {
+        let mut backoff = INITIAL_BACKOFF;
+        loop {
+            let wanted = feed.wanted();
+            let Some(session) = feed.session().filter(|_| !wanted.is_empty()) else {
+                sleep(IDLE_CHECK_INTERVAL).await;
+                continue;
+            };
+
+            feed.streaming.set(true);
+            match stream_ticks_server(session.clone(), wanted).await {
+                Ok(stream) => {
+                    feed.connected.set(true);
+                    backoff = INITIAL_BACKOFF;
+                    feed.receive(stream, &session).await;
+                    feed.connected.set(false);
+                }
+                Err(e) => tracing::warn!("Failed to open the market feed: {}", e),
+            }
+            feed.streaming.set(false);
+
+            sleep(backoff).await;
+            backoff = (backoff * 2).min(MAX_BACKOFF);
+        }
+    });
+
+    rsx! {
+        {children}
+    }
+}
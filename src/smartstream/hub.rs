@@ -0,0 +1,117 @@
+use super::client::SmartStream;
+use super::{FeedSession, Tick};
+use crate::config::ServerConfig;
+use crate::smartapi::SmartApiError;
+use dioxus::prelude::ServerFnError;
+use futures::Stream;
+use std::collections::HashMap;
+use std::sync::{Arc, LazyLock, Mutex, PoisonError, Weak};
+use std::time::Duration;
+use tokio::sync::broadcast;
+use tokio::sync::broadcast::error::RecvError;
+
+// A feed that nobody streams from this long after connecting is disconnected
+const UNCLAIMED_FEED_TIMEOUT: Duration = Duration::from_secs(30);
+
+// Angel allows a few feed connections per client, so screens share one
+static FEEDS: LazyLock<Mutex<HashMap<String, Arc<SmartStream>>>> = LazyLock::new(Default::default);
+
+/// The client's feed, connecting it or replacing it after a new login.
+pub fn feed(session: FeedSession) -> Result<Arc<SmartStream>, SmartApiError> {
+    let mut feeds = FEEDS.lock().unwrap_or_else(PoisonError::into_inner);
+    if let Some(feed) = feeds
+        .get(&session.client_code)
+        .filter(|feed| feed.uses_feed_token(session.feed_token.expose()))
+    {
+        return Ok(feed.clone());
+    }
+
+    let config = ServerConfig::init().map_err(|e| SmartApiError::Config(e.to_string()))?;
+    let client_code = session.client_code.clone();
+    let feed = Arc::new(SmartStream::connect(
+        config.stream_url.clone(),
+        session,
+        config.api_key.clone(),
+    ));
+    feeds.insert(client_code.clone(), feed.clone());
+
+    // Subscribing alone opens a feed, let it go if no tick stream follows
+    let unclaimed = Arc::downgrade(&feed);
+    tokio::spawn(async move {
+        tokio::time::sleep(UNCLAIMED_FEED_TIMEOUT).await;
+        release(&client_code, &unclaimed);
+    });
+    Ok(feed)
+}
+
+/// The client's feed if one is connected with this session.
+pub fn existing(session: &FeedSession) -> Option<Arc<SmartStream>> {
+    FEEDS
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .get(&session.client_code)
+        .filter(|feed| feed.uses_feed_token(session.feed_token.expose()))
+        .cloned()
+}
+
+// Disconnect the feed while it has no listeners, unless a new login replaced it already
+fn release(client_code: &str, feed: &Weak<SmartStream>) {
+    let mut feeds = FEEDS.lock().unwrap_or_else(PoisonError::into_inner);
+    if feeds.get(client_code).is_some_and(|current| {
+        Arc::as_ptr(current) == feed.as_ptr() && current.listener_count() == 0
+    }) {
+        feeds.remove(client_code);
+    }
+}
+
+// A tick receiver that releases the feed when dropped
+struct Listener {
+    ticks: Option<broadcast::Receiver<Tick>>,
+    client_code: String,
+    feed: Weak<SmartStream>,
+}
+
+impl Listener {
+    async fn next(&mut self) -> Option<Tick> {
+        let ticks = self.ticks.as_mut()?;
+        loop {
+            match ticks.recv().await {
+                Ok(tick) => return Some(tick),
+                Err(RecvError::Lagged(skipped)) => {
+                    tracing::warn!("Tick stream fell behind, skipped {} ticks", skipped);
+                }
+                Err(RecvError::Closed) => return None,
+            }
+        }
+    }
+}
+
+impl Drop for Listener {
+    fn drop(&mut self) {
+        // The receiver must be gone before the listener count is checked
+        self.ticks = None;
+        release(&self.client_code, &self.feed);
+    }
+}
+
+/// Ticks from `feed` as JSON lines, ending when the feed closes.
+pub fn tick_lines(
+    client_code: String,
+    feed: &Arc<SmartStream>,
+) -> impl Stream<Item = Result<String, ServerFnError>> + Send + 'static {
+    let listener = Listener {
+        ticks: Some(feed.ticks()),
+        client_code,
+        feed: Arc::downgrade(feed),
+    };
+    futures::stream::unfold(listener, |mut listener| async move {
+        let tick = listener.next().await?;
+        Some((to_line(&tick), listener))
+    })
+}
+
+fn to_line(tick: &Tick) -> Result<String, ServerFnError> {
+    serde_json::to_string(tick)
+        .map(|json| json + "\n")
+        .map_err(|e| ServerFnError::new(format!("Failed to encode tick: {}", e)))
+}
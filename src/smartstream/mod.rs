@@ -0,0 +1,84 @@
+//! SmartStream 2.0, Angel's WebSocket market data feed.
+//!
+//! The WebSocket needs the API key, so it lives on the server: one
+//! connection per client code, shared by every open screen. The UI receives
+//! decoded ticks as JSON lines from [`stream_ticks_server`] and keeps them in
+//! signals through [`MarketFeedProvider`].
+
+use crate::auth::AuthTokens;
+use crate::secret::Secret;
+use crate::smartapi::SmartApiError;
+use dioxus::prelude::server_fn::codec::{StreamingText, TextStream};
+use dioxus::prelude::*;
+use serde::{Deserialize, Serialize};
+
+mod tick;
+pub use tick::{ExchangeType, SubscriptionMode, Tick};
+
+mod feed;
+pub use feed::{use_feed_subscription, use_market_feed, MarketFeedProvider};
+
+#[cfg(feature = "server")]
+mod client;
+#[cfg(feature = "server")]
+mod hub;
+
+/// Tokens of one exchange segment to (un)subscribe in a mode.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Subscription {
+    pub mode: SubscriptionMode,
+    pub exchange_type: ExchangeType,
+    pub tokens: Vec<String>,
+}
+
+/// Credentials the feed connects with, the refresh token stays on the device.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FeedSession {
+    pub client_code: String,
+    pub jwt_token: Secret<String>,
+    pub feed_token: Secret<String>,
+}
+
+impl From<&AuthTokens> for FeedSession {
+    fn from(tokens: &AuthTokens) -> Self {
+        Self {
+            client_code: tokens.user_id.clone(),
+            jwt_token: Secret::new(tokens.jwt_token.clone()),
+            feed_token: Secret::new(tokens.feed_token.clone()),
+        }
+    }
+}
+
+/// Ticks for the signed in client as newline separated JSON, subscribing
+/// `subscriptions` first so a reconnecting UI gets back what it had.
+#[server(StreamTicksServer, output = StreamingText)]
+pub async fn stream_ticks_server(
+    session: FeedSession,
+    subscriptions: Vec<Subscription>,
+) -> Result<TextStream, ServerFnError> {
+    let feed = hub::feed(session.clone()).map_err(|e| ServerFnError::new(e.user_message()))?;
+    for subscription in subscriptions {
+        feed.subscribe(subscription);
+    }
+    Ok(TextStream::new(hub::tick_lines(session.client_code, &feed)))
+}
+
+#[server(SubscribeFeedServer)]
+pub async fn subscribe_feed_server(
+    session: FeedSession,
+    subscription: Subscription,
+) -> Result<(), ServerFnError<SmartApiError>> {
+    hub::feed(session)?.subscribe(subscription);
+    Ok(())
+}
+
+#[server(UnsubscribeFeedServer)]
+pub async fn unsubscribe_feed_server(
+    session: FeedSession,
+    subscription: Subscription,
+) -> Result<(), ServerFnError<SmartApiError>> {
+    if let Some(feed) = hub::existing(&session) {
+        feed.unsubscribe(subscription);
+    }
+    Ok(())
+}
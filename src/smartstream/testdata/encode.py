@@ -0,0 +1,67 @@
+#!/usr/bin/env python3
+"""Rebuild the golden SmartStream packets from their decoded JSON.
+
+The layout follows Angel's SmartStream 2.0 binary response documentation
+and the struct formats SmartWebSocketV2._parse_binary_data unpacks with in
+the smartapi-python SDK, not the offsets in tick.rs, so the decoder is
+checked against an independent encoding. Packets recorded from a live feed
+can be dropped in next to these as extra golden pairs.
+
+    python3 encode.py
+"""
+
+import json
+import struct
+from pathlib import Path
+
+MODES = {"Ltp": 1, "Quote": 2, "SnapQuote": 3}
+EXCHANGES = {"NseCm": 1, "NseFo": 2, "BseCm": 3, "BseFo": 4, "McxFo": 5, "NcxFo": 7, "CdeFo": 13}
+
+
+def encode(tick):
+    exchange = tick["exchange_type"]
+    scale = 10_000_000 if exchange == "CdeFo" else 100
+
+    def price(rupees):
+        return struct.pack("<q", round(rupees * scale))
+
+    packet = struct.pack("<BB", MODES[tick["mode"]], EXCHANGES[exchange])
+    packet += tick["token"].encode().ljust(25, b"\0")
+    packet += struct.pack("<qq", tick["sequence_number"], tick["exchange_timestamp"])
+    packet += price(tick["ltp"])
+
+    quote = tick["quote"]
+    if quote:
+        packet += struct.pack("<q", quote["last_traded_quantity"])
+        packet += price(quote["average_price"])
+        packet += struct.pack("<qdd", quote["volume"], quote["total_buy_quantity"], quote["total_sell_quantity"])
+        for field in ("open", "high", "low", "close"):
+            packet += price(quote[field])
+
+    snap = tick["snap_quote"]
+    if snap:
+        packet += struct.pack(
+            "<qqd",
+            snap["last_traded_timestamp"],
+            snap["open_interest"],
+            snap["open_interest_change_percent"],
+        )
+        # Ten 20 byte entries: buy flag (H), quantity (q), price (q), orders (H)
+        levels = [(0, level) for level in snap["asks"] if level["quantity"]]
+        levels += [(1, level) for level in snap["bids"] if level["quantity"]]
+        for flag, level in levels:
+            packet += struct.pack("<Hq", flag, level["quantity"]) + price(level["price"])
+            packet += struct.pack("<H", level["orders"])
+        packet += bytes(20 * (10 - len(levels)))
+        for field in ("upper_circuit", "lower_circuit", "week_52_high", "week_52_low"):
+            packet += price(snap[field])
+
+    return packet
+
+
+if __name__ == "__main__":
+    here = Path(__file__).parent
+    for source in sorted(here.glob("*.json")):
+        packet = encode(json.loads(source.read_text()))
+        source.with_suffix(".bin").write_bytes(packet)
+        print(f"{source.with_suffix('.bin').name}: {len(packet)} bytes")
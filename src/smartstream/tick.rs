@@ -0,0 +1,395 @@
+//! SmartStream 2.0 binary packets.
+//!
+//! Only depends on `std` and `serde`, so the fuzz target under `fuzz/` can
+//! build it on its own.
+
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+// Packet sizes per mode, each mode extends the previous one
+const LTP_PACKET_LEN: usize = 51;
+const QUOTE_PACKET_LEN: usize = 123;
+const SNAP_QUOTE_PACKET_LEN: usize = 379;
+const TOKEN_LEN: usize = 25;
+// Levels of market depth on each side of a snap quote
+const DEPTH_LEVELS: usize = 5;
+const DEPTH_ENTRY_LEN: usize = 20;
+
+/// How much data a subscription streams, each mode includes the previous one.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum SubscriptionMode {
+    Ltp,
+    Quote,
+    SnapQuote,
+    /// 20 level market depth
+    Depth,
+}
+
+impl SubscriptionMode {
+    pub fn code(self) -> u8 {
+        match self {
+            Self::Ltp => 1,
+            Self::Quote => 2,
+            Self::SnapQuote => 3,
+            Self::Depth => 4,
+        }
+    }
+
+    pub fn from_code(code: u8) -> Option<Self> {
+        match code {
+            1 => Some(Self::Ltp),
+            2 => Some(Self::Quote),
+            3 => Some(Self::SnapQuote),
+            4 => Some(Self::Depth),
+            _ => None,
+        }
+    }
+}
+
+/// Exchange segment as numbered by SmartStream.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum ExchangeType {
+    NseCm,
+    NseFo,
+    BseCm,
+    BseFo,
+    McxFo,
+    NcxFo,
+    CdeFo,
+}
+
+impl ExchangeType {
+    pub fn code(self) -> u8 {
+        match self {
+            Self::NseCm => 1,
+            Self::NseFo => 2,
+            Self::BseCm => 3,
+            Self::BseFo => 4,
+            Self::McxFo => 5,
+            Self::NcxFo => 7,
+            Self::CdeFo => 13,
+        }
+    }
+
+    pub fn from_code(code: u8) -> Option<Self> {
+        match code {
+            1 => Some(Self::NseCm),
+            2 => Some(Self::NseFo),
+            3 => Some(Self::BseCm),
+            4 => Some(Self::BseFo),
+            5 => Some(Self::McxFo),
+            7 => Some(Self::NcxFo),
+            13 => Some(Self::CdeFo),
+            _ => None,
+        }
+    }
+
+    /// Segment for an exchange name as used by the REST API and instrument master.
+    pub fn from_exchange(exchange: &str) -> Option<Self> {
+        match exchange {
+            "NSE" => Some(Self::NseCm),
+            "NFO" => Some(Self::NseFo),
+            "BSE" => Some(Self::BseCm),
+            "BFO" => Some(Self::BseFo),
+            "MCX" => Some(Self::McxFo),
+            "NCDEX" => Some(Self::NcxFo),
+            "CDS" => Some(Self::CdeFo),
+            _ => None,
+        }
+    }
+
+    /// Convert a price from the feed, in paise or 1e-7 rupees for currencies, to rupees.
+    pub fn price(self, raw: i64) -> f64 {
+        match self {
+            Self::CdeFo => raw as f64 / 10_000_000.0,
+            _ => raw as f64 / 100.0,
+        }
+    }
+}
+
+/// A market data update from SmartStream.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct Tick {
+    pub mode: SubscriptionMode,
+    pub exchange_type: ExchangeType,
+    pub token: String,
+    pub sequence_number: i64,
+    /// Unix time in milliseconds
+    pub exchange_timestamp: i64,
+    /// Last traded price in rupees
+    pub ltp: f64,
+    /// Present in Quote and SnapQuote mode
+    pub quote: Option<QuoteData>,
+    /// Present in SnapQuote mode
+    pub snap_quote: Option<SnapQuoteData>,
+}
+
+/// The day's trading summary, prices in rupees.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct QuoteData {
+    pub last_traded_quantity: i64,
+    pub average_price: f64,
+    pub volume: i64,
+    pub total_buy_quantity: f64,
+    pub total_sell_quantity: f64,
+    pub open: f64,
+    pub high: f64,
+    pub low: f64,
+    pub close: f64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct SnapQuoteData {
+    /// Unix time in seconds
+    pub last_traded_timestamp: i64,
+    pub open_interest: i64,
+    pub open_interest_change_percent: f64,
+    /// Best bids first, missing levels are zeroed
+    pub bids: [DepthLevel; DEPTH_LEVELS],
+    /// Best asks first, missing levels are zeroed
+    pub asks: [DepthLevel; DEPTH_LEVELS],
+    pub upper_circuit: f64,
+    pub lower_circuit: f64,
+    pub week_52_high: f64,
+    pub week_52_low: f64,
+}
+
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq)]
+pub struct DepthLevel {
+    pub price: f64,
+    pub quantity: i64,
+    pub orders: u16,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum DecodeError {
+    TooShort {
+        expected: usize,
+        actual: usize,
+    },
+    UnknownMode(u8),
+    UnknownExchange(u8),
+    /// 20 level depth packets have a layout of their own
+    Unsupported(SubscriptionMode),
+    InvalidToken,
+}
+
+impl fmt::Display for DecodeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::TooShort { expected, actual } => {
+                write!(f, "packet has {} bytes, expected {}", actual, expected)
+            }
+            Self::UnknownMode(mode) => write!(f, "unknown subscription mode {}", mode),
+            Self::UnknownExchange(exchange) => write!(f, "unknown exchange type {}", exchange),
+            Self::Unsupported(mode) => write!(f, "{:?} packets are not supported", mode),
+            Self::InvalidToken => write!(f, "token is not valid UTF-8"),
+        }
+    }
+}
+
+impl std::error::Error for DecodeError {}
+
+impl Tick {
+    /// Decode a binary SmartStream packet, all fields are little endian.
+    ///
+    /// Never panics, whatever the input. Bytes past the mode's length are ignored.
+    pub fn decode(packet: &[u8]) -> Result<Self, DecodeError> {
+        let (&mode, &exchange) = match packet {
+            [mode, exchange, ..] => (mode, exchange),
+            _ => return Err(too_short(LTP_PACKET_LEN, packet)),
+        };
+        let mode = SubscriptionMode::from_code(mode).ok_or(DecodeError::UnknownMode(mode))?;
+        let expected = match mode {
+            SubscriptionMode::Ltp => LTP_PACKET_LEN,
+            SubscriptionMode::Quote => QUOTE_PACKET_LEN,
+            SubscriptionMode::SnapQuote => SNAP_QUOTE_PACKET_LEN,
+            SubscriptionMode::Depth => return Err(DecodeError::Unsupported(mode)),
+        };
+        if packet.len() < expected {
+            return Err(too_short(expected, packet));
+        }
+        let exchange_type =
+            ExchangeType::from_code(exchange).ok_or(DecodeError::UnknownExchange(exchange))?;
+        let reader = Reader {
+            packet,
+            exchange_type,
+        };
+
+        // The token is NUL padded to a fixed width
+        let token = &packet[2..2 + TOKEN_LEN];
+        let token_end = token.iter().position(|&b| b == 0).unwrap_or(TOKEN_LEN);
+        let token =
+            std::str::from_utf8(&token[..token_end]).map_err(|_| DecodeError::InvalidToken)?;
+
+        Ok(Self {
+            mode,
+            exchange_type,
+            token: token.to_string(),
+            sequence_number: reader.i64(27),
+            exchange_timestamp: reader.i64(35),
+            ltp: reader.price(43),
+            quote: (expected >= QUOTE_PACKET_LEN).then(|| reader.quote()),
+            snap_quote: (expected >= SNAP_QUOTE_PACKET_LEN).then(|| reader.snap_quote()),
+        })
+    }
+}
+
+fn too_short(expected: usize, packet: &[u8]) -> DecodeError {
+    DecodeError::TooShort {
+        expected,
+        actual: packet.len(),
+    }
+}
+
+// Reads fields at fixed offsets, only used once the length has been checked
+struct Reader<'a> {
+    packet: &'a [u8],
+    exchange_type: ExchangeType,
+}
+
+impl Reader<'_> {
+    fn bytes<const N: usize>(&self, offset: usize) -> [u8; N] {
+        let mut bytes = [0u8; N];
+        bytes.copy_from_slice(&self.packet[offset..offset + N]);
+        bytes
+    }
+
+    fn u16(&self, offset: usize) -> u16 {
+        u16::from_le_bytes(self.bytes(offset))
+    }
+
+    fn i64(&self, offset: usize) -> i64 {
+        i64::from_le_bytes(self.bytes(offset))
+    }
+
+    fn f64(&self, offset: usize) -> f64 {
+        f64::from_le_bytes(self.bytes(offset))
+    }
+
+    fn price(&self, offset: usize) -> f64 {
+        self.exchange_type.price(self.i64(offset))
+    }
+
+    fn quote(&self) -> QuoteData {
+        QuoteData {
+            last_traded_quantity: self.i64(51),
+            average_price: self.price(59),
+            volume: self.i64(67),
+            total_buy_quantity: self.f64(75),
+            total_sell_quantity: self.f64(83),
+            open: self.price(91),
+            high: self.price(99),
+            low: self.price(107),
+            close: self.price(115),
+        }
+    }
+
+    fn snap_quote(&self) -> SnapQuoteData {
+        let mut bids = [DepthLevel::default(); DEPTH_LEVELS];
+        let mut asks = [DepthLevel::default(); DEPTH_LEVELS];
+        let (mut bid_count, mut ask_count) = (0, 0);
+
+        // Ten entries flagged buy (1) or sell (0), best price first on each side
+        for entry in 0..2 * DEPTH_LEVELS {
+            let offset = 147 + entry * DEPTH_ENTRY_LEN;
+            let level = DepthLevel {
+                quantity: self.i64(offset + 2),
+                price: self.price(offset + 10),
+                orders: self.u16(offset + 18),
+            };
+            let (side, count) = match self.u16(offset) {
+                1 => (&mut bids, &mut bid_count),
+                _ => (&mut asks, &mut ask_count),
+            };
+            if let Some(slot) = side.get_mut(*count) {
+                *slot = level;
+                *count += 1;
+            }
+        }
+
+        SnapQuoteData {
+            last_traded_timestamp: self.i64(123),
+            open_interest: self.i64(131),
+            open_interest_change_percent: self.f64(139),
+            bids,
+            asks,
+            upper_circuit: self.price(347),
+            lower_circuit: self.price(355),
+            week_52_high: self.price(363),
+            week_52_low: self.price(371),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Packets encoded from the decoded ticks next to them by testdata/encode.py,
+    // which follows the SDK's struct layout rather than the offsets above
+    const GOLDEN: [(&[u8], &str); 3] = [
+        (
+            include_bytes!("testdata/ltp.bin"),
+            include_str!("testdata/ltp.json"),
+        ),
+        (
+            include_bytes!("testdata/quote.bin"),
+            include_str!("testdata/quote.json"),
+        ),
+        (
+            include_bytes!("testdata/snap_quote.bin"),
+            include_str!("testdata/snap_quote.json"),
+        ),
+    ];
+
+    #[test]
+    fn decodes_golden_packets() {
+        for (packet, expected) in GOLDEN {
+            let expected: Tick = serde_json::from_str(expected).unwrap();
+            assert_eq!(Tick::decode(packet), Ok(expected));
+        }
+    }
+
+    #[test]
+    fn rejects_truncated_packets() {
+        for (packet, _) in GOLDEN {
+            for len in 0..packet.len() {
+                assert!(matches!(
+                    Tick::decode(&packet[..len]),
+                    Err(DecodeError::TooShort { .. } | DecodeError::UnknownMode(_))
+                ));
+            }
+        }
+    }
+
+    #[test]
+    fn rejects_unknown_fields() {
+        let mut packet = GOLDEN[0].0.to_vec();
+        packet[1] = 6;
+        assert_eq!(Tick::decode(&packet), Err(DecodeError::UnknownExchange(6)));
+        packet[0] = 4;
+        assert_eq!(
+            Tick::decode(&packet),
+            Err(DecodeError::Unsupported(SubscriptionMode::Depth))
+        );
+        packet[0] = 9;
+        assert_eq!(Tick::decode(&packet), Err(DecodeError::UnknownMode(9)));
+
+        let mut packet = GOLDEN[0].0.to_vec();
+        packet[2] = 0xff;
+        assert_eq!(Tick::decode(&packet), Err(DecodeError::InvalidToken));
+    }
+
+    #[test]
+    fn never_panics_on_corrupted_packets() {
+        // Cheap stand-in for the fuzz target: flip every byte of every packet
+        for (packet, _) in GOLDEN {
+            for i in 0..packet.len() {
+                let mut corrupted = packet.to_vec();
+                corrupted[i] ^= 0xff;
+                let _ = Tick::decode(&corrupted);
+            }
+        }
+    }
+}
@@ -0,0 +1,296 @@
+use super::{StoredSession, TokenStore};
+use crate::auth::AuthTokens;
+use crate::secret::Secret;
+use aes_gcm::aead::rand_core::RngCore;
+use aes_gcm::aead::{Aead, AeadCore, KeyInit, OsRng};
+use aes_gcm::{Aes256Gcm, Key, Nonce};
+use base64::{engine::general_purpose::STANDARD, Engine};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+const SESSION_FILE: &str = "session.enc";
+const DEVICE_SECRET_FILE: &str = "device.key";
+// Plaintext files written by earlier versions, migrated on first load
+const LEGACY_TOKENS_FILE: &str = "auth_tokens.json";
+const LEGACY_EXPIRY_FILE: &str = "auth_expiry.txt";
+
+const PAYLOAD_VERSION: u8 = 1;
+const DEVICE_SECRET_LEN: usize = 32;
+
+#[derive(Serialize, Deserialize)]
+struct EncryptedPayload {
+    version: u8,
+    nonce: String,
+    ciphertext: String,
+}
+
+/// Native store keeping the session AES-256-GCM encrypted on disk.
+///
+/// The key is a random per-device secret kept next to the session, both
+/// readable only by the current user (0600 in a 0700 directory). This guards
+/// the tokens against other local users and stray copies of `session.enc`,
+/// not against anyone who can read the whole app directory.
+pub struct EncryptedFileTokenStore {
+    dir: PathBuf,
+    key: OnceLock<Secret<Vec<u8>>>,
+}
+
+impl EncryptedFileTokenStore {
+    pub fn new(dir: PathBuf) -> Self {
+        Self {
+            dir,
+            key: OnceLock::new(),
+        }
+    }
+
+    fn cipher(&self) -> Result<Aes256Gcm, String> {
+        let key = match self.key.get() {
+            Some(key) => key,
+            None => {
+                let device_secret = self.device_secret()?;
+                self.key.get_or_init(|| Secret::new(device_secret))
+            }
+        };
+        Ok(Aes256Gcm::new(Key::<Aes256Gcm>::from_slice(key.expose())))
+    }
+
+    fn device_secret(&self) -> Result<Vec<u8>, String> {
+        let path = self.dir.join(DEVICE_SECRET_FILE);
+        if path.exists() {
+            let secret =
+                fs::read(&path).map_err(|e| format!("Failed to read device secret: {}", e))?;
+            if secret.len() == DEVICE_SECRET_LEN {
+                return Ok(secret);
+            }
+            tracing::warn!("Device secret is corrupt, generating a new one");
+        }
+
+        let mut secret = vec![0u8; DEVICE_SECRET_LEN];
+        OsRng.fill_bytes(&mut secret);
+        create_private_dir(&self.dir)?;
+        write_private(&path, &secret)?;
+        Ok(secret)
+    }
+
+    fn load_legacy(&self) -> Result<Option<StoredSession>, String> {
+        let tokens_path = self.dir.join(LEGACY_TOKENS_FILE);
+        let expiry_path = self.dir.join(LEGACY_EXPIRY_FILE);
+        if !tokens_path.exists() {
+            return Ok(None);
+        }
+
+        let session = fs::read_to_string(&tokens_path)
+            .ok()
+            .and_then(|json| serde_json::from_str::<AuthTokens>(&json).ok())
+            .zip(
+                fs::read_to_string(&expiry_path)
+                    .ok()
+                    .and_then(|expiry| expiry.trim().parse::<u64>().ok()),
+            )
+            .map(|(tokens, expires_at)| StoredSession { tokens, expires_at });
+
+        if let Some(session) = &session {
+            self.save(session)?;
+            tracing::info!("Migrated plaintext tokens to encrypted storage");
+        }
+        remove_if_exists(&tokens_path)?;
+        remove_if_exists(&expiry_path)?;
+        Ok(session)
+    }
+}
+
+impl TokenStore for EncryptedFileTokenStore {
+    fn save(&self, session: &StoredSession) -> Result<(), String> {
+        create_private_dir(&self.dir)?;
+
+        let plaintext = serde_json::to_vec(session)
+            .map_err(|e| format!("Failed to serialize tokens: {}", e))?;
+        let nonce = Aes256Gcm::generate_nonce(&mut OsRng);
+        let ciphertext = self
+            .cipher()?
+            .encrypt(&nonce, plaintext.as_slice())
+            .map_err(|_| "Failed to encrypt tokens".to_string())?;
+
+        let payload = EncryptedPayload {
+            version: PAYLOAD_VERSION,
+            nonce: STANDARD.encode(nonce),
+            ciphertext: STANDARD.encode(ciphertext),
+        };
+        let payload_json = serde_json::to_vec(&payload)
+            .map_err(|e| format!("Failed to serialize encrypted tokens: {}", e))?;
+
+        // Write then rename so a crash never leaves a half written session
+        let path = self.dir.join(SESSION_FILE);
+        let tmp_path = path.with_extension("tmp");
+        write_private(&tmp_path, &payload_json)?;
+        fs::rename(&tmp_path, &path).map_err(|e| format!("Failed to write tokens file: {}", e))?;
+
+        tracing::info!("Tokens stored to encrypted file successfully");
+        Ok(())
+    }
+
+    fn load(&self) -> Result<Option<StoredSession>, String> {
+        let path = self.dir.join(SESSION_FILE);
+        if !path.exists() {
+            return self.load_legacy();
+        }
+
+        let payload_json =
+            fs::read(&path).map_err(|e| format!("Failed to read tokens file: {}", e))?;
+        let payload: EncryptedPayload = serde_json::from_slice(&payload_json)
+            .map_err(|e| format!("Failed to parse tokens file: {}", e))?;
+        if payload.version != PAYLOAD_VERSION {
+            return Err(format!(
+                "Unsupported tokens file version {}",
+                payload.version
+            ));
+        }
+
+        let nonce = STANDARD
+            .decode(&payload.nonce)
+            .map_err(|e| format!("Failed to decode nonce: {}", e))?;
+        if nonce.len() != 12 {
+            return Err("Invalid nonce in tokens file".to_string());
+        }
+        let ciphertext = STANDARD
+            .decode(&payload.ciphertext)
+            .map_err(|e| format!("Failed to decode tokens: {}", e))?;
+
+        let plaintext = self
+            .cipher()?
+            .decrypt(Nonce::from_slice(&nonce), ciphertext.as_slice())
+            .map_err(|_| "Failed to decrypt tokens, the device key changed".to_string())?;
+
+        serde_json::from_slice(&plaintext)
+            .map(Some)
+            .map_err(|e| format!("Failed to parse tokens: {}", e))
+    }
+
+    fn clear(&self) -> Result<(), String> {
+        remove_if_exists(&self.dir.join(SESSION_FILE))?;
+        remove_if_exists(&self.dir.join(LEGACY_TOKENS_FILE))?;
+        remove_if_exists(&self.dir.join(LEGACY_EXPIRY_FILE))?;
+
+        tracing::info!("Token files cleared");
+        Ok(())
+    }
+}
+
+fn create_private_dir(dir: &Path) -> Result<(), String> {
+    fs::create_dir_all(dir).map_err(|e| format!("Failed to create app directory: {}", e))?;
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        fs::set_permissions(dir, fs::Permissions::from_mode(0o700))
+            .map_err(|e| format!("Failed to restrict app directory: {}", e))?;
+    }
+
+    Ok(())
+}
+
+// Write a file readable only by the current user
+fn write_private(path: &Path, contents: &[u8]) -> Result<(), String> {
+    use std::io::Write;
+
+    let mut options = fs::OpenOptions::new();
+    options.write(true).create(true).truncate(true);
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+
+    let mut file = options
+        .open(path)
+        .map_err(|e| format!("Failed to open {}: {}", path.display(), e))?;
+    file.write_all(contents)
+        .map_err(|e| format!("Failed to write {}: {}", path.display(), e))?;
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        fs::set_permissions(path, fs::Permissions::from_mode(0o600))
+            .map_err(|e| format!("Failed to restrict {}: {}", path.display(), e))?;
+    }
+
+    Ok(())
+}
+
+fn remove_if_exists(path: &Path) -> Result<(), String> {
+    if path.exists() {
+        fs::remove_file(path).map_err(|e| format!("Failed to remove {}: {}", path.display(), e))?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir =
+            std::env::temp_dir().join(format!("angel_trading_{}_{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        dir
+    }
+
+    fn session() -> StoredSession {
+        StoredSession {
+            tokens: AuthTokens {
+                jwt_token: "jwt-secret".to_string(),
+                refresh_token: "refresh-secret".to_string(),
+                feed_token: "feed-secret".to_string(),
+                user_id: "A123456".to_string(),
+            },
+            expires_at: 1_700_000_000,
+        }
+    }
+
+    #[test]
+    fn round_trips_without_plaintext_on_disk() {
+        let dir = temp_dir("round_trip");
+        let store = EncryptedFileTokenStore::new(dir.clone());
+
+        store.save(&session()).unwrap();
+        let on_disk = fs::read_to_string(dir.join(SESSION_FILE)).unwrap();
+        assert!(!on_disk.contains("secret"));
+
+        // A fresh store re-derives the same key from the device secret
+        let reopened = EncryptedFileTokenStore::new(dir.clone());
+        assert_eq!(reopened.load().unwrap(), Some(session()));
+
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let mode = fs::metadata(dir.join(SESSION_FILE))
+                .unwrap()
+                .permissions()
+                .mode();
+            assert_eq!(mode & 0o777, 0o600);
+        }
+
+        reopened.clear().unwrap();
+        assert_eq!(reopened.load().unwrap(), None);
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn session_file_needs_the_device_key() {
+        let dir = temp_dir("device_key");
+        let copy = temp_dir("device_key_copy");
+        EncryptedFileTokenStore::new(dir.clone())
+            .save(&session())
+            .unwrap();
+
+        fs::create_dir_all(&copy).unwrap();
+        fs::copy(dir.join(SESSION_FILE), copy.join(SESSION_FILE)).unwrap();
+        assert!(EncryptedFileTokenStore::new(copy.clone()).load().is_err());
+
+        let _ = fs::remove_dir_all(&dir);
+        let _ = fs::remove_dir_all(&copy);
+    }
+}
@@ -0,0 +1,82 @@
+use super::{StoredSession, TokenStore};
+use crate::auth::AuthTokens;
+use web_sys::{window, Storage};
+
+const TOKEN_STORAGE_KEY: &str = "angel_trading_auth_tokens";
+const TOKEN_EXPIRY_KEY: &str = "angel_trading_auth_expiry";
+
+/// Browser store keeping the session in localStorage.
+pub struct LocalStorageTokenStore;
+
+impl TokenStore for LocalStorageTokenStore {
+    fn save(&self, session: &StoredSession) -> Result<(), String> {
+        let storage = get_local_storage()?;
+
+        let tokens_json = serde_json::to_string(&session.tokens)
+            .map_err(|e| format!("Failed to serialize tokens: {}", e))?;
+
+        storage
+            .set_item(TOKEN_STORAGE_KEY, &tokens_json)
+            .map_err(|e| format!("Failed to store tokens: {:?}", e))?;
+
+        // Expiry is kept in milliseconds to match `Date.now()`
+        let expiry = session.expires_at as f64 * 1000.0;
+        storage
+            .set_item(TOKEN_EXPIRY_KEY, &expiry.to_string())
+            .map_err(|e| format!("Failed to store token expiry: {:?}", e))?;
+
+        tracing::info!("Tokens stored successfully with expiry");
+        Ok(())
+    }
+
+    fn load(&self) -> Result<Option<StoredSession>, String> {
+        let storage = get_local_storage()?;
+
+        let Some(tokens_json) = storage
+            .get_item(TOKEN_STORAGE_KEY)
+            .map_err(|e| format!("Failed to read tokens: {:?}", e))?
+        else {
+            return Ok(None);
+        };
+        let Some(expiry_str) = storage
+            .get_item(TOKEN_EXPIRY_KEY)
+            .map_err(|e| format!("Failed to read token expiry: {:?}", e))?
+        else {
+            return Ok(None);
+        };
+
+        let tokens = serde_json::from_str::<AuthTokens>(&tokens_json)
+            .map_err(|e| format!("Failed to parse stored tokens: {}", e))?;
+        let expiry_ms = expiry_str
+            .parse::<f64>()
+            .map_err(|e| format!("Failed to parse expiry: {}", e))?;
+
+        Ok(Some(StoredSession {
+            tokens,
+            expires_at: (expiry_ms / 1000.0) as u64,
+        }))
+    }
+
+    fn clear(&self) -> Result<(), String> {
+        let storage = get_local_storage()?;
+
+        storage
+            .remove_item(TOKEN_STORAGE_KEY)
+            .map_err(|e| format!("Failed to clear tokens: {:?}", e))?;
+
+        storage
+            .remove_item(TOKEN_EXPIRY_KEY)
+            .map_err(|e| format!("Failed to clear token expiry: {:?}", e))?;
+
+        tracing::info!("Tokens cleared from storage");
+        Ok(())
+    }
+}
+
+pub(super) fn get_local_storage() -> Result<Storage, String> {
+    let window = window().ok_or("No window available")?;
+    window
+        .local_storage()
+        .map_err(|e| format!("Failed to access localStorage: {:?}", e))?
+        .ok_or("localStorage not available".to_string())
+}
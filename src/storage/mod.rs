@@ -0,0 +1,104 @@
+//! Persistence for the signed-in session and the trader's watchlists.
+//!
+//! Tokens go through a [`TokenStore`] so each platform can pick where they
+//! live: localStorage in the browser and an encrypted file natively.
+//! Watchlists aren't secret and are kept as plain JSON in the same places.
+//!
+//! The file key comes from a random per-device secret alone. Deriving it from
+//! a user PIN was left out on purpose, as every launch would then have to ask
+//! for the PIN before the saved session could be restored.
+
+use crate::auth::AuthTokens;
+use serde::{Deserialize, Serialize};
+use std::sync::OnceLock;
+
+#[cfg(not(target_arch = "wasm32"))]
+mod encrypted_file;
+#[cfg(not(target_arch = "wasm32"))]
+pub use encrypted_file::EncryptedFileTokenStore;
+
+#[cfg(target_arch = "wasm32")]
+mod local_storage;
+#[cfg(target_arch = "wasm32")]
+pub use local_storage::LocalStorageTokenStore;
+
+mod watchlists;
+pub use watchlists::{load_watchlists, save_watchlists};
+
+/// Tokens together with the unix time (seconds) they stop being valid.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct StoredSession {
+    pub tokens: AuthTokens,
+    pub expires_at: u64,
+}
+
+/// Backend that persists the session between app launches.
+pub trait TokenStore: Send + Sync {
+    fn save(&self, session: &StoredSession) -> Result<(), String>;
+    fn load(&self) -> Result<Option<StoredSession>, String>;
+    fn clear(&self) -> Result<(), String>;
+}
+
+static TOKEN_STORE: OnceLock<Box<dyn TokenStore>> = OnceLock::new();
+
+/// This platform's token store, created on first use.
+pub fn token_store() -> Result<&'static dyn TokenStore, String> {
+    if let Some(store) = TOKEN_STORE.get() {
+        return Ok(store.as_ref());
+    }
+    let store = default_token_store()?;
+    Ok(TOKEN_STORE.get_or_init(|| store).as_ref())
+}
+
+// Other backends, such as a platform keychain, get picked here
+fn default_token_store() -> Result<Box<dyn TokenStore>, String> {
+    #[cfg(target_arch = "wasm32")]
+    {
+        Ok(Box::new(LocalStorageTokenStore))
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    {
+        Ok(Box::new(EncryptedFileTokenStore::new(get_app_data_dir()?)))
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+pub fn get_app_data_dir() -> Result<std::path::PathBuf, String> {
+    use std::env;
+
+    #[cfg(target_os = "android")]
+    {
+        // For Android, use app-specific storage
+        env::var("ANDROID_DATA")
+            .map(|data| std::path::PathBuf::from(data).join("angel_trading"))
+            .or_else(|_| {
+                env::var("HOME").map(|home| std::path::PathBuf::from(home).join(".angel_trading"))
+            })
+            .map_err(|_| "Failed to determine app data directory".to_string())
+    }
+
+    #[cfg(target_os = "ios")]
+    {
+        // For iOS, use Documents directory
+        env::var("HOME")
+            .map(|home| {
+                std::path::PathBuf::from(home)
+                    .join("Documents")
+                    .join("angel_trading")
+            })
+            .map_err(|_| "Failed to determine app data directory".to_string())
+    }
+
+    #[cfg(not(any(target_os = "android", target_os = "ios")))]
+    {
+        // For desktop platforms
+        env::var("HOME")
+            .map(|home| std::path::PathBuf::from(home).join(".angel_trading"))
+            .or_else(|_| {
+                env::var("USERPROFILE")
+                    .map(|profile| std::path::PathBuf::from(profile).join(".angel_trading"))
+            })
+            .map_err(|_| "Failed to determine app data directory".to_string())
+    }
+}
@@ -0,0 +1,60 @@
+use crate::watchlists::Watchlist;
+
+#[cfg(target_arch = "wasm32")]
+const WATCHLISTS_STORAGE_KEY: &str = "angel_trading_watchlists";
+#[cfg(not(target_arch = "wasm32"))]
+const WATCHLISTS_FILE: &str = "watchlists.json";
+
+/// Saved watchlists, empty until the first save.
+pub fn load_watchlists() -> Result<Vec<Watchlist>, String> {
+    #[cfg(target_arch = "wasm32")]
+    let json = super::local_storage::get_local_storage()?
+        .get_item(WATCHLISTS_STORAGE_KEY)
+        .map_err(|e| format!("Failed to read watchlists: {:?}", e))?;
+
+    #[cfg(not(target_arch = "wasm32"))]
+    let json = {
+        let path = super::get_app_data_dir()?.join(WATCHLISTS_FILE);
+        if path.exists() {
+            Some(
+                std::fs::read_to_string(&path)
+                    .map_err(|e| format!("Failed to read watchlists: {}", e))?,
+            )
+        } else {
+            None
+        }
+    };
+
+    match json {
+        Some(json) => {
+            serde_json::from_str(&json).map_err(|e| format!("Failed to parse watchlists: {}", e))
+        }
+        None => Ok(Vec::new()),
+    }
+}
+
+pub fn save_watchlists(watchlists: &[Watchlist]) -> Result<(), String> {
+    let json = serde_json::to_string(watchlists)
+        .map_err(|e| format!("Failed to serialize watchlists: {}", e))?;
+
+    #[cfg(target_arch = "wasm32")]
+    {
+        super::local_storage::get_local_storage()?
+            .set_item(WATCHLISTS_STORAGE_KEY, &json)
+            .map_err(|e| format!("Failed to store watchlists: {:?}", e))
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    {
+        let dir = super::get_app_data_dir()?;
+        std::fs::create_dir_all(&dir)
+            .map_err(|e| format!("Failed to create app directory: {}", e))?;
+
+        // Write then rename so a crash never loses every list
+        let path = dir.join(WATCHLISTS_FILE);
+        let tmp_path = path.with_extension("tmp");
+        std::fs::write(&tmp_path, json)
+            .map_err(|e| format!("Failed to write watchlists: {}", e))?;
+        std::fs::rename(&tmp_path, &path).map_err(|e| format!("Failed to write watchlists: {}", e))
+    }
+}
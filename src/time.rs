@@ -0,0 +1,161 @@
+use std::time::Duration;
+
+// Indian markets and Angel's sessions run on IST
+const IST_OFFSET_SECS: u64 = 5 * 60 * 60 + 30 * 60;
+const SECS_PER_DAY: u64 = 24 * 60 * 60;
+
+// Current unix time in seconds, on every platform
+pub fn now_secs() -> u64 {
+    #[cfg(target_arch = "wasm32")]
+    {
+        (js_sys::Date::now() / 1000.0) as u64
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    {
+        std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or_default()
+    }
+}
+
+// Async sleep that works on both the browser and native runtimes
+pub async fn sleep(duration: Duration) {
+    #[cfg(target_arch = "wasm32")]
+    {
+        gloo_timers::future::sleep(duration).await;
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    {
+        tokio::time::sleep(duration).await;
+    }
+}
+
+// Days since the epoch in IST, changes at local midnight
+pub fn ist_day(secs: u64) -> u64 {
+    (secs + IST_OFFSET_SECS) / SECS_PER_DAY
+}
+
+// Unix time (seconds) of the midnight starting the next IST day
+pub fn next_ist_midnight(secs: u64) -> u64 {
+    (ist_day(secs) + 1) * SECS_PER_DAY - IST_OFFSET_SECS
+}
+
+// Unix time (seconds) of the midnight starting an IST day
+#[cfg(feature = "server")]
+pub fn ist_day_start(day: u64) -> u64 {
+    day * SECS_PER_DAY - IST_OFFSET_SECS
+}
+
+// `YYYY-MM-DD HH:MM` in IST, the format SmartAPI takes dates in
+pub fn format_ist_minute(secs: u64) -> String {
+    let local = secs + IST_OFFSET_SECS;
+    let (year, month, day) = civil_from_days((local / SECS_PER_DAY) as i64);
+    let minutes = local % SECS_PER_DAY / 60;
+    format!(
+        "{:04}-{:02}-{:02} {:02}:{:02}",
+        year,
+        month,
+        day,
+        minutes / 60,
+        minutes % 60
+    )
+}
+
+// Unix time (seconds) of an RFC 3339 timestamp such as `2024-10-16T09:15:00+05:30`
+#[cfg(feature = "server")]
+pub fn parse_rfc3339(text: &str) -> Option<u64> {
+    let (date, time) = text.trim().split_once('T')?;
+    let mut date = date.splitn(3, '-').map(|part| part.parse::<i64>().ok());
+    let (year, month, day) = (date.next()??, date.next()??, date.next()??);
+    if !(1..=12).contains(&month) || !(1..=31).contains(&day) || time.len() < 8 {
+        return None;
+    }
+
+    let (clock, zone) = time.split_at(8);
+    let mut clock = clock.splitn(3, ':').map(|part| part.parse::<i64>().ok());
+    let (hour, minute, second) = (clock.next()??, clock.next()??, clock.next()??);
+    // Fractional seconds don't matter at candle resolution
+    let zone = zone.trim_start_matches(|c: char| c == '.' || c.is_ascii_digit());
+    let offset = match zone {
+        "Z" | "z" => 0,
+        _ => {
+            let sign = match zone.chars().next()? {
+                '+' => 1,
+                '-' => -1,
+                _ => return None,
+            };
+            let (hours, minutes) = zone[1..].split_once(':')?;
+            sign * (hours.parse::<i64>().ok()? * 3600 + minutes.parse::<i64>().ok()? * 60)
+        }
+    };
+
+    let secs = days_from_civil(year, month, day) * SECS_PER_DAY as i64
+        + hour * 3600
+        + minute * 60
+        + second
+        - offset;
+    u64::try_from(secs).ok()
+}
+
+// Days since the epoch of a proleptic Gregorian date (Howard Hinnant's algorithm)
+#[cfg(feature = "server")]
+fn days_from_civil(year: i64, month: i64, day: i64) -> i64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let year_of_era = year - era * 400;
+    let day_of_year = (153 * (month + if month > 2 { -3 } else { 9 }) + 2) / 5 + day - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    era * 146_097 + day_of_era - 719_468
+}
+
+fn civil_from_days(days: i64) -> (i64, i64, i64) {
+    let days = days + 719_468;
+    let era = days.div_euclid(146_097);
+    let day_of_era = days - era * 146_097;
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let shifted_month = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * shifted_month + 2) / 5 + 1;
+    let month = if shifted_month < 10 {
+        shifted_month + 3
+    } else {
+        shifted_month - 9
+    };
+    let year = year_of_era + era * 400 + if month <= 2 { 1 } else { 0 };
+    (year, month, day)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn formats_ist_minutes() {
+        assert_eq!(format_ist_minute(1_729_050_300), "2024-10-16 09:15");
+        assert_eq!(format_ist_minute(0), "1970-01-01 05:30");
+    }
+
+    #[test]
+    fn next_ist_midnight_rolls_over_at_1830_utc() {
+        // 2024-10-16 18:30 UTC is 2024-10-17 00:00 IST
+        let midnight = 1_729_103_400;
+        assert_eq!(next_ist_midnight(midnight - 1), midnight);
+        assert_eq!(next_ist_midnight(midnight), midnight + SECS_PER_DAY);
+        assert_eq!(next_ist_midnight(midnight + 1), midnight + SECS_PER_DAY);
+        assert_eq!(ist_day(midnight - 1) + 1, ist_day(midnight));
+    }
+
+    #[cfg(feature = "server")]
+    #[test]
+    fn parses_smartapi_timestamps() {
+        let secs = parse_rfc3339("2024-10-16T09:15:00+05:30").unwrap();
+        assert_eq!(secs, 1_729_050_300);
+        assert_eq!(parse_rfc3339("2024-10-16T03:45:00.000Z"), Some(secs));
+        assert_eq!(ist_day_start(ist_day(secs)), 1_729_017_000);
+        assert_eq!(parse_rfc3339("2024-10-16 09:15"), None);
+    }
+}
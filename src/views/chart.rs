@@ -0,0 +1,29 @@
+use crate::auth::use_require_auth;
+use crate::components::CandleChart;
+use dioxus::prelude::*;
+
+#[component]
+pub fn Chart(exchange: String, token: String, symbol: String) -> Element {
+    // Require authentication to access charts
+    let is_authenticated = use_require_auth();
+
+    if !is_authenticated {
+        return rsx! {
+            div { class: "flex items-center justify-center h-full",
+                div { class: "text-lg font-medium text-gray-600",
+                    "Redirecting to login..."
+                }
+            }
+        };
+    }
+
+    rsx! {
+        div { class: "p-4",
+            div { class: "mb-4 flex items-center gap-2",
+                h1 { class: "text-2xl font-bold text-gray-900", "{symbol}" }
+                span { class: "badge badge-outline", "{exchange}" }
+            }
+            CandleChart { exchange, token }
+        }
+    }
+}
@@ -1,11 +1,13 @@
-use crate::auth::use_require_auth;
-use crate::components::{Echo, Hero};
+use crate::auth::{use_auth, use_require_auth};
+use crate::components::{FundsPanel, WatchlistPanel};
+use crate::smartapi::UserProfile;
 use dioxus::prelude::*;
 
 #[component]
 pub fn Dashboard() -> Element {
     // Require authentication to access dashboard
     let is_authenticated = use_require_auth();
+    let auth = use_auth();
     
     if !is_authenticated {
         return rsx! {
@@ -17,14 +19,49 @@ pub fn Dashboard() -> Element {
         };
     }
 
+    let profile = auth.get_profile();
+
     rsx! {
         div { class: "p-4",
             div { class: "mb-4",
                 h1 { class: "text-2xl font-bold text-gray-900", "Dashboard" }
-                p { class: "text-gray-600", "Welcome to your trading dashboard" }
+                if let Some(profile) = &profile {
+                    p { class: "text-gray-600", "Welcome, {profile.name}" }
+                } else {
+                    p { class: "text-gray-600", "Welcome to your trading dashboard" }
+                }
+            }
+            if let Some(profile) = profile {
+                ProfileCard { profile }
+            }
+            FundsPanel {}
+            WatchlistPanel {}
+        }
+    }
+}
+
+#[component]
+fn ProfileCard(profile: UserProfile) -> Element {
+    rsx! {
+        div { class: "mb-4 rounded-lg border border-zinc-100 p-4 text-sm dark:border-zinc-800",
+            div { class: "grid grid-cols-2 gap-2",
+                span { class: "text-gray-500", "Client code" }
+                span { "{profile.client_code}" }
+                span { class: "text-gray-500", "Email" }
+                span { "{profile.email}" }
+                span { class: "text-gray-500", "Broker" }
+                span { "{profile.broker_id}" }
+            }
+            div { class: "mt-3 flex flex-wrap gap-1",
+                for exchange in profile.exchanges.iter() {
+                    span { class: "badge badge-outline", "{exchange}" }
+                }
+            }
+            div { class: "mt-2 flex flex-wrap gap-1",
+                for product in profile.products.iter() {
+                    span { class: "badge badge-ghost", "{product}" }
+                }
             }
-            Hero {}
-            Echo {}
         }
     }
 }
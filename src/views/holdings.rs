@@ -0,0 +1,153 @@
+use crate::auth::{use_auth, use_require_auth};
+use crate::components::ErrorMessage;
+use crate::format::{inr, percent, pnl_class, signed_inr};
+use crate::smartapi::{error_message, Holding, Portfolio, SmartApiError};
+use dioxus::prelude::*;
+
+#[component]
+pub fn Holdings() -> Element {
+    // Require authentication to access holdings
+    let is_authenticated = use_require_auth();
+    let auth = use_auth();
+    let mut portfolio = use_signal(|| None::<Portfolio>);
+    let mut error = use_signal(|| None::<String>);
+    let mut is_loading = use_signal(|| false);
+
+    let refresh = move || {
+        spawn(async move {
+            let Some((_, authorization)) = auth.get_auth_header() else {
+                return;
+            };
+            is_loading.set(true);
+            match get_holdings_server(authorization).await {
+                Ok(latest) => {
+                    portfolio.set(Some(latest));
+                    error.set(None);
+                }
+                Err(e) => {
+                    tracing::error!("Failed to load holdings: {}", e);
+                    error.set(Some(error_message(&e)));
+                }
+            }
+            is_loading.set(false);
+        });
+    };
+
+    use_effect(move || {
+        if auth.is_authenticated() {
+            refresh();
+        }
+    });
+
+    if !is_authenticated {
+        return rsx! {
+            div { class: "flex items-center justify-center h-full",
+                div { class: "text-lg font-medium text-gray-600",
+                    "Redirecting to login..."
+                }
+            }
+        };
+    }
+
+    rsx! {
+        div { class: "p-4",
+            div { class: "mb-4 flex items-center justify-between",
+                h1 { class: "text-2xl font-bold text-gray-900", "Holdings" }
+                button {
+                    class: "btn btn-sm btn-ghost",
+                    disabled: is_loading(),
+                    onclick: move |_| refresh(),
+                    if is_loading() { "Refreshing..." } else { "Refresh" }
+                }
+            }
+
+            ErrorMessage { message: error() }
+
+            if let Some(portfolio) = portfolio() {
+                PortfolioSummary { portfolio: portfolio.clone() }
+
+                if portfolio.holdings.is_empty() {
+                    p { class: "text-sm text-gray-500", "You have no holdings" }
+                }
+                div { class: "overflow-x-auto",
+                    table { class: "table table-sm",
+                        thead {
+                            tr {
+                                th { "Instrument" }
+                                th { class: "text-right", "Qty" }
+                                th { class: "text-right", "Avg" }
+                                th { class: "text-right", "LTP" }
+                                th { class: "text-right", "Day" }
+                                th { class: "text-right", "P&L" }
+                            }
+                        }
+                        tbody {
+                            for holding in portfolio.holdings.iter() {
+                                HoldingRow { key: "{holding.exchange}:{holding.trading_symbol}", holding: holding.clone() }
+                            }
+                        }
+                    }
+                }
+            } else if is_loading() {
+                p { class: "text-sm text-gray-500", "Loading holdings..." }
+            }
+        }
+    }
+}
+
+#[component]
+fn PortfolioSummary(portfolio: Portfolio) -> Element {
+    let totals = &portfolio.totals;
+    let day_change = portfolio.day_change();
+
+    rsx! {
+        div { class: "mb-4 grid grid-cols-2 gap-2 rounded-lg border border-zinc-100 p-4 text-sm dark:border-zinc-800",
+            span { class: "text-gray-500", "Invested" }
+            span { class: "text-right", "{inr(totals.investment_value)}" }
+            span { class: "text-gray-500", "Current value" }
+            span { class: "text-right font-medium", "{inr(totals.holding_value)}" }
+            span { class: "text-gray-500", "Day change" }
+            span { class: "text-right {pnl_class(day_change)}", "{signed_inr(day_change)}" }
+            span { class: "text-gray-500", "Total P&L" }
+            span { class: "text-right {pnl_class(totals.profit_and_loss)}",
+                "{signed_inr(totals.profit_and_loss)} ({percent(totals.pnl_percentage)})"
+            }
+        }
+    }
+}
+
+#[component]
+fn HoldingRow(holding: Holding) -> Element {
+    let day_change = holding.day_change();
+
+    rsx! {
+        tr {
+            td {
+                div { class: "font-medium", "{holding.trading_symbol}" }
+                div { class: "text-xs text-gray-500", "{holding.exchange}" }
+            }
+            td { class: "text-right", "{holding.total_quantity()}" }
+            td { class: "text-right", "{inr(holding.average_price)}" }
+            td { class: "text-right", "{inr(holding.ltp)}" }
+            td { class: "text-right {pnl_class(day_change)}",
+                div { "{signed_inr(day_change)}" }
+                div { class: "text-xs", "{percent(holding.day_change_percentage())}" }
+            }
+            td { class: "text-right {pnl_class(holding.profit_and_loss)}",
+                div { "{signed_inr(holding.profit_and_loss)}" }
+                div { class: "text-xs", "{percent(holding.pnl_percentage)}" }
+            }
+        }
+    }
+}
+
+#[server(GetHoldingsServer)]
+async fn get_holdings_server(
+    authorization: String,
+) -> Result<Portfolio, ServerFnError<SmartApiError>> {
+    use crate::smartapi::{bearer_token, SmartApiClient};
+
+    Ok(SmartApiClient::shared()?
+        .get_all_holdings(bearer_token(&authorization))
+        .await?)
+}
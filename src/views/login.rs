@@ -1,7 +1,7 @@
 use crate::auth::{use_auth, use_redirect_if_authenticated, AuthTokens};
 use crate::components::{Button, FormActions, Input, SimpleForm, ErrorMessage};
+use crate::smartapi::{error_message as api_error_message, SmartApiError};
 use dioxus::prelude::*;
-use std::env;
 
 #[component]
 pub fn Login() -> Element {
@@ -38,8 +38,7 @@ pub fn Login() -> Element {
                     error_message.set(None);
 
                     // All fields are valid, proceed with login
-                    let mut auth = auth.clone();
-                    let nav = nav.clone();
+                    let mut auth = auth;
                     spawn(async move {
                         match login_server(user_val.clone(), password_val.clone(), totp_val.clone()).await {
                             Ok(tokens) => {
@@ -59,7 +58,7 @@ pub fn Login() -> Element {
                             }
                             Err(error) => {
                                 tracing::error!("Login failed: {error}");
-                                error_message.set(Some("Login failed".to_string()));
+                                error_message.set(Some(api_error_message(&error)));
                             }
                         }
                         is_loading.set(false);
@@ -135,106 +134,27 @@ pub fn Login() -> Element {
     }
 }
 
-#[derive(Debug, serde::Serialize)]
-struct LoginApiRequest {
-    clientcode: String,
-    password: String,
-    totp: String,
-}
-
-#[derive(Debug, serde::Deserialize)]
-struct LoginApiResponse {
-    status: bool,
-    message: String,
-    errorcode: String,
-    data: Option<LoginApiResponseData>,
-}
-
-#[derive(Debug, serde::Deserialize)]
-struct LoginApiResponseData {
-    #[serde(rename = "jwtToken")]
-    jwt_token: String,
-    #[serde(rename = "refreshToken")]
-    refresh_token: String,
-    #[serde(rename = "feedToken")]
-    feed_token: String,
-    state: Option<String>,
-}
-
 #[server(LoginServer)]
 async fn login_server(
     clientcode: String,
     password: String,
     totp: String,
-) -> Result<AuthTokens, ServerFnError> {
-    let base_url = "https://apiconnect.angelbroking.com/";
-    let url = "rest/auth/angelbroking/user/v1/loginByPassword";
-    let client = reqwest::Client::new();
-
-    let mut headers = reqwest::header::HeaderMap::new();
-    headers.insert("Content-Type", "application/json".parse().unwrap());
-    headers.insert("Accept", "application/json".parse().unwrap());
-    headers.insert("X-UserType", "USER".parse().unwrap());
-    headers.insert("X-SourceID", "WEB".parse().unwrap());
-    headers.insert(
-        "X-ClientLocalIP",
-        env::var("LOCAL_IP").unwrap_or_default().parse().unwrap(),
-    );
-    headers.insert(
-        "X-ClientPublicIP",
-        env::var("PUBLIC_IP").unwrap_or_default().parse().unwrap(),
-    );
-    headers.insert(
-        "X-MACAddress",
-        env::var("MAC_ADDRESS").unwrap_or_default().parse().unwrap(),
-    );
-    headers.insert(
-        "X-PrivateKey",
-        env::var("API_KEY").unwrap_or_default().parse().unwrap(),
-    );
+) -> Result<AuthTokens, ServerFnError<SmartApiError>> {
+    use crate::smartapi::{LoginApiRequest, SmartApiClient};
 
     let request = LoginApiRequest {
         clientcode: clientcode.clone(),
-        password,
-        totp,
+        password: password.into(),
+        totp: totp.into(),
     };
 
-    let response = client
-        .post(&format!("{}{}", base_url, url))
-        .headers(headers)
-        .json(&request)
-        .send()
-        .await?;
-
-    tracing::info!("Request: {:?}", request);
-
-    let response_text = response.text().await?;
-    tracing::info!("Response Text: {:?}", response_text);
-
-    match serde_json::from_str::<LoginApiResponse>(&response_text) {
-        Ok(response_json) => {
-            tracing::info!("Parsed Response JSON: {:?}", response_json);
-            if response_json.status {
-                if let Some(data) = response_json.data {
-                    let tokens = AuthTokens {
-                        jwt_token: data.jwt_token,
-                        refresh_token: data.refresh_token,
-                        feed_token: data.feed_token,
-                        user_id: clientcode,
-                    };
-                    Ok(tokens)
-                } else {
-                    Err(ServerFnError::ServerError("No token data received".to_string()))
-                }
-            } else {
-                Err(ServerFnError::ServerError(response_json.message))
-            }
-        }
-        Err(e) => {
-            tracing::error!("Failed to parse JSON: {:?}", e);
-            Err(ServerFnError::ServerError(
-                "Failed to parse server response".to_string(),
-            ))
-        }
-    }
+    tracing::info!("Logging in client {}", clientcode);
+
+    let data = SmartApiClient::shared()?.login(&request).await?;
+    Ok(AuthTokens {
+        jwt_token: data.jwt_token.into_inner(),
+        refresh_token: data.refresh_token.into_inner(),
+        feed_token: data.feed_token.into_inner(),
+        user_id: clientcode,
+    })
 }
@@ -1,6 +1,24 @@
+mod chart;
+pub use chart::Chart;
+
 mod dashboard;
 pub use dashboard::Dashboard;
 
+mod holdings;
+pub use holdings::Holdings;
+
 mod login;
 pub use login::Login;
 
+mod order_book;
+pub use order_book::OrderBook;
+
+mod order_ticket;
+pub use order_ticket::OrderTicket;
+
+mod positions;
+pub use positions::Positions;
+
+mod trades;
+pub use trades::Trades;
+
@@ -0,0 +1,362 @@
+use crate::auth::{use_auth, use_require_auth};
+use crate::components::{ErrorMessage, SuccessMessage};
+use crate::format::inr;
+use crate::orders::{cancel_order_server, modify_order_server};
+use crate::smartapi::{
+    error_message, ModifyOrderRequest, Order, OrderStatus, SmartApiError, TransactionType,
+};
+use crate::time::sleep;
+use dioxus::prelude::*;
+use std::time::Duration;
+
+const ORDER_BOOK_POLL_INTERVAL: Duration = Duration::from_secs(10);
+
+#[component]
+pub fn OrderBook() -> Element {
+    // Require authentication to access the order book
+    let is_authenticated = use_require_auth();
+    let auth = use_auth();
+    let mut orders = use_signal(Vec::<Order>::new);
+    let mut status_filter = use_signal(|| None::<OrderStatus>);
+    let mut auto_refresh = use_signal(|| true);
+    let mut error = use_signal(|| None::<String>);
+    let mut success = use_signal(|| None::<String>);
+    let mut is_loading = use_signal(|| false);
+
+    let refresh = move || {
+        spawn(async move {
+            let Some((_, authorization)) = auth.get_auth_header() else {
+                return;
+            };
+            is_loading.set(true);
+            match get_order_book_server(authorization).await {
+                Ok(latest) => {
+                    orders.set(latest);
+                    error.set(None);
+                }
+                Err(e) => {
+                    tracing::error!("Failed to load order book: {}", e);
+                    error.set(Some(error_message(&e)));
+                }
+            }
+            is_loading.set(false);
+        });
+    };
+
+    // Initial load and polling while auto refresh is on
+    use_future(move || async move {
+        loop {
+            if auth.is_authenticated() && (auto_refresh() || orders.peek().is_empty()) {
+                refresh();
+            }
+            sleep(ORDER_BOOK_POLL_INTERVAL).await;
+        }
+    });
+
+    let modify = move |request: ModifyOrderRequest| {
+        spawn(async move {
+            let Some((_, authorization)) = auth.get_auth_header() else {
+                return;
+            };
+            match modify_order_server(authorization, request).await {
+                Ok(response) => {
+                    success.set(Some(format!("Order {} modified", response.order_id)));
+                    error.set(None);
+                    refresh();
+                }
+                Err(e) => {
+                    tracing::error!("Modify order failed: {}", e);
+                    error.set(Some(error_message(&e)));
+                }
+            }
+        });
+    };
+
+    let cancel = move |order: Order| {
+        spawn(async move {
+            let Some((_, authorization)) = auth.get_auth_header() else {
+                return;
+            };
+            match cancel_order_server(authorization, order.cancel()).await {
+                Ok(response) => {
+                    success.set(Some(format!("Order {} cancelled", response.order_id)));
+                    error.set(None);
+                    refresh();
+                }
+                Err(e) => {
+                    tracing::error!("Cancel order failed: {}", e);
+                    error.set(Some(error_message(&e)));
+                }
+            }
+        });
+    };
+
+    if !is_authenticated {
+        return rsx! {
+            div { class: "flex items-center justify-center h-full",
+                div { class: "text-lg font-medium text-gray-600",
+                    "Redirecting to login..."
+                }
+            }
+        };
+    }
+
+    let visible: Vec<Order> = orders()
+        .into_iter()
+        .filter(|order| status_filter().is_none_or(|status| order.status_group() == status))
+        .collect();
+
+    rsx! {
+        div { class: "p-4",
+            div { class: "mb-4 flex items-center justify-between",
+                h1 { class: "text-2xl font-bold text-gray-900", "Orders" }
+                div { class: "flex items-center gap-2",
+                    label { class: "label cursor-pointer gap-2 text-sm",
+                        input {
+                            r#type: "checkbox",
+                            class: "toggle toggle-sm",
+                            checked: auto_refresh(),
+                            onchange: move |event: FormEvent| auto_refresh.set(event.data.checked()),
+                        }
+                        "Auto"
+                    }
+                    button {
+                        class: "btn btn-sm btn-ghost",
+                        disabled: is_loading(),
+                        onclick: move |_| refresh(),
+                        if is_loading() { "Refreshing..." } else { "Refresh" }
+                    }
+                }
+            }
+
+            div { class: "mb-3 join",
+                for option in std::iter::once(None).chain(OrderStatus::ALL.map(Some)) {
+                    button {
+                        class: if status_filter() == option { "btn btn-sm join-item btn-active" } else { "btn btn-sm join-item" },
+                        onclick: move |_| status_filter.set(option),
+                        {option.map_or("All", OrderStatus::label)}
+                    }
+                }
+            }
+
+            ErrorMessage { message: error() }
+            SuccessMessage { message: success() }
+
+            if visible.is_empty() && !is_loading() {
+                p { class: "text-sm text-gray-500", "No orders" }
+            }
+
+            div { class: "overflow-x-auto",
+                table { class: "table table-sm",
+                    thead {
+                        tr {
+                            th { "Instrument" }
+                            th { "Type" }
+                            th { class: "text-right", "Filled / Qty" }
+                            th { class: "text-right", "Price" }
+                            th { "Status" }
+                            th {}
+                        }
+                    }
+                    tbody {
+                        for order in visible {
+                            OrderRow {
+                                key: "{order.order_id}",
+                                order: order.clone(),
+                                on_modify: modify,
+                                on_cancel: cancel,
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum RowAction {
+    None,
+    Modify,
+    ConfirmCancel,
+}
+
+#[component]
+fn OrderRow(
+    order: Order,
+    on_modify: EventHandler<ModifyOrderRequest>,
+    on_cancel: EventHandler<Order>,
+) -> Element {
+    let mut action = use_signal(|| RowAction::None);
+    let mut price = use_signal(|| order.price.to_string());
+    let mut quantity = use_signal(|| order.quantity.to_string());
+    let mut trigger_price = use_signal(|| order.trigger_price.to_string());
+    let mut form_error = use_signal(|| None::<String>);
+
+    let side_class = match order.transaction_type {
+        TransactionType::Buy => "text-green-600",
+        TransactionType::Sell => "text-red-600",
+    };
+    let status_class = match order.status_group() {
+        OrderStatus::Open => "badge badge-info",
+        OrderStatus::Complete => "badge badge-success",
+        OrderStatus::Cancelled => "badge badge-ghost",
+        OrderStatus::Rejected => "badge badge-error",
+    };
+    let shown_price = if order.average_price > 0.0 {
+        order.average_price
+    } else {
+        order.price
+    };
+    let order_type = order.order_type;
+    let modify_order = order.clone();
+    let cancel_order = order.clone();
+
+    // Typos are reported here rather than sent to Angel as zero
+    let build_modify = move || -> Result<ModifyOrderRequest, String> {
+        let number = |value: String, name: &str| {
+            value
+                .trim()
+                .parse::<f64>()
+                .map_err(|_| format!("{} is not a number", name))
+        };
+        let quantity = quantity()
+            .trim()
+            .parse::<i64>()
+            .map_err(|_| "Quantity must be a whole number".to_string())?;
+        let request = modify_order.modify(
+            number(price(), "Price")?,
+            quantity,
+            number(trigger_price(), "Trigger price")?,
+        );
+        request.validate()?;
+        Ok(request)
+    };
+
+    rsx! {
+        tr {
+            td {
+                div { class: "font-medium", "{order.trading_symbol}" }
+                div { class: "text-xs text-gray-500", "{order.exchange} · {order.product_type.label()} · {order.update_time}" }
+            }
+            td {
+                span { class: side_class, "{order.transaction_type.as_str()}" }
+                div { class: "text-xs text-gray-500", "{order.order_type.label()} · {order.variety.as_str()}" }
+            }
+            td { class: "text-right",
+                "{order.filled_quantity} / {order.quantity}"
+                if order.pending_quantity > 0 && order.is_open() {
+                    div { class: "text-xs text-gray-500", "{order.pending_quantity} pending" }
+                }
+            }
+            td { class: "text-right",
+                "{inr(shown_price)}"
+                if order.trigger_price > 0.0 {
+                    div { class: "text-xs text-gray-500", "Trigger {inr(order.trigger_price)}" }
+                }
+            }
+            td {
+                span { class: status_class, "{order.status}" }
+                if !order.text.is_empty() {
+                    div { class: "text-xs text-red-600", "{order.text}" }
+                }
+            }
+            td { class: "text-right",
+                if order.is_open() {
+                    match action() {
+                        RowAction::None => rsx! {
+                            button {
+                                class: "btn btn-xs btn-outline",
+                                onclick: move |_| action.set(RowAction::Modify),
+                                "Modify"
+                            }
+                            button {
+                                class: "btn btn-xs btn-ghost",
+                                onclick: move |_| action.set(RowAction::ConfirmCancel),
+                                "Cancel"
+                            }
+                        },
+                        RowAction::ConfirmCancel => rsx! {
+                            button {
+                                class: "btn btn-xs btn-error",
+                                onclick: move |_| {
+                                    action.set(RowAction::None);
+                                    on_cancel.call(cancel_order.clone());
+                                },
+                                "Confirm cancel"
+                            }
+                            button {
+                                class: "btn btn-xs btn-ghost",
+                                onclick: move |_| action.set(RowAction::None),
+                                "Back"
+                            }
+                        },
+                        RowAction::Modify => rsx! {
+                            div { class: "flex flex-wrap justify-end gap-1",
+                                input {
+                                    r#type: "number",
+                                    class: "input input-xs w-20",
+                                    "aria-label": "Quantity",
+                                    value: quantity(),
+                                    oninput: move |event: FormEvent| quantity.set(event.data.value()),
+                                }
+                                if order_type.needs_price() {
+                                    input {
+                                        r#type: "number",
+                                        class: "input input-xs w-24",
+                                        "aria-label": "Price",
+                                        value: price(),
+                                        oninput: move |event: FormEvent| price.set(event.data.value()),
+                                    }
+                                }
+                                if order_type.needs_trigger() {
+                                    input {
+                                        r#type: "number",
+                                        class: "input input-xs w-24",
+                                        "aria-label": "Trigger price",
+                                        value: trigger_price(),
+                                        oninput: move |event: FormEvent| trigger_price.set(event.data.value()),
+                                    }
+                                }
+                                button {
+                                    class: "btn btn-xs btn-primary",
+                                    onclick: move |_| match build_modify() {
+                                        Ok(request) => {
+                                            form_error.set(None);
+                                            action.set(RowAction::None);
+                                            on_modify.call(request);
+                                        }
+                                        Err(message) => form_error.set(Some(message)),
+                                    },
+                                    "Save"
+                                }
+                                button {
+                                    class: "btn btn-xs btn-ghost",
+                                    onclick: move |_| {
+                                        form_error.set(None);
+                                        action.set(RowAction::None);
+                                    },
+                                    "Back"
+                                }
+                            }
+                            if let Some(message) = form_error() {
+                                div { class: "text-xs text-red-600", "{message}" }
+                            }
+                        },
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[server(GetOrderBookServer)]
+async fn get_order_book_server(
+    authorization: String,
+) -> Result<Vec<Order>, ServerFnError<SmartApiError>> {
+    use crate::smartapi::{bearer_token, SmartApiClient};
+
+    Ok(SmartApiClient::shared()?
+        .get_order_book(bearer_token(&authorization))
+        .await?)
+}
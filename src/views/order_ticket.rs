@@ -0,0 +1,397 @@
+use crate::auth::{use_auth, use_require_auth};
+use crate::components::{
+    get_funds_server, Button, ErrorMessage, FormActions, Input, Label, Select, SimpleForm,
+    SuccessMessage, SymbolSearch, WarningMessage,
+};
+use crate::format::inr;
+use crate::instruments::Instrument;
+use crate::orders::{calculate_margin_server, place_order_server};
+use crate::smartapi::{
+    error_message, Duration, MarginLeg, MarginRequirement, OrderRequest, OrderType, ProductType,
+    TransactionType, Variety, EXCHANGES,
+};
+use dioxus::prelude::*;
+
+#[component]
+pub fn OrderTicket() -> Element {
+    // Require authentication to place orders
+    let is_authenticated = use_require_auth();
+    let auth = use_auth();
+
+    let mut exchange = use_signal(|| EXCHANGES[0].to_string());
+    let mut trading_symbol = use_signal(String::new);
+    let mut symbol_token = use_signal(String::new);
+    let mut transaction_type = use_signal(|| TransactionType::Buy);
+    let mut variety = use_signal(|| Variety::Normal);
+    let mut order_type = use_signal(|| OrderType::Limit);
+    let mut product_type = use_signal(|| ProductType::Delivery);
+    let mut duration = use_signal(|| Duration::Day);
+    let mut quantity = use_signal(|| "1".to_string());
+    let mut price = use_signal(String::new);
+    let mut trigger_price = use_signal(String::new);
+    let mut square_off = use_signal(String::new);
+    let mut stop_loss = use_signal(String::new);
+    let mut error = use_signal(|| None::<String>);
+    let mut success = use_signal(|| None::<String>);
+    let mut is_submitting = use_signal(|| false);
+    // Required margin for the current ticket and the available margin it was checked against
+    let mut margin = use_signal(|| None::<(MarginRequirement, f64)>);
+    let mut is_checking_margin = use_signal(|| false);
+
+    // Build the request from the form, empty number fields count as zero
+    let build_order = move || -> Result<OrderRequest, String> {
+        let number = |value: String, name: &str| -> Result<f64, String> {
+            if value.trim().is_empty() {
+                return Ok(0.0);
+            }
+            value
+                .trim()
+                .parse::<f64>()
+                .map_err(|_| format!("{} is not a number", name))
+        };
+        let quantity = quantity()
+            .trim()
+            .parse::<i64>()
+            .map_err(|_| "Quantity must be a whole number".to_string())?;
+
+        let order = OrderRequest {
+            variety: variety(),
+            order_type: order_type(),
+            duration: duration(),
+            price: if order_type().needs_price() {
+                number(price(), "Price")?
+            } else {
+                0.0
+            },
+            trigger_price: if order_type().needs_trigger() {
+                number(trigger_price(), "Trigger price")?
+            } else {
+                0.0
+            },
+            square_off: number(square_off(), "Target")?,
+            stop_loss: number(stop_loss(), "Stop loss")?,
+            ..OrderRequest::market(
+                &exchange(),
+                trading_symbol().trim(),
+                symbol_token().trim(),
+                transaction_type(),
+                product_type(),
+                quantity,
+            )
+        };
+        order.validate()?;
+        Ok(order)
+    };
+
+    let check_margin = move |_| {
+        let order = match build_order() {
+            Ok(order) => order,
+            Err(message) => {
+                error.set(Some(message));
+                return;
+            }
+        };
+        let Some((_, authorization)) = auth.get_auth_header() else {
+            return;
+        };
+
+        is_checking_margin.set(true);
+        error.set(None);
+        spawn(async move {
+            let legs = vec![MarginLeg::from(&order)];
+            let result = match calculate_margin_server(authorization.clone(), legs).await {
+                Ok(requirement) => get_funds_server(authorization)
+                    .await
+                    .map(|funds| (requirement, funds.net)),
+                Err(e) => Err(e),
+            };
+            match result {
+                Ok(checked) => margin.set(Some(checked)),
+                Err(e) => {
+                    tracing::error!("Margin check failed: {}", e);
+                    margin.set(None);
+                    error.set(Some(error_message(&e)));
+                }
+            }
+            is_checking_margin.set(false);
+        });
+    };
+
+    if !is_authenticated {
+        return rsx! {
+            div { class: "flex items-center justify-center h-full",
+                div { class: "text-lg font-medium text-gray-600",
+                    "Redirecting to login..."
+                }
+            }
+        };
+    }
+
+    let side_class = |side: TransactionType| match (side, transaction_type() == side) {
+        (TransactionType::Buy, true) => "btn btn-sm btn-success",
+        (TransactionType::Sell, true) => "btn btn-sm btn-error",
+        _ => "btn btn-sm btn-ghost",
+    };
+
+    rsx! {
+        div { class: "max-w-xl mx-auto space-y-4",
+            h1 { class: "text-2xl font-bold", "Place Order" }
+
+            div { class: "join",
+                for side in [TransactionType::Buy, TransactionType::Sell] {
+                    button {
+                        r#type: "button",
+                        class: "join-item {side_class(side)}",
+                        onclick: move |_| transaction_type.set(side),
+                        "{side.as_str()}"
+                    }
+                }
+            }
+
+            SimpleForm {
+                onsubmit: move |_: FormEvent| {
+                    let order = match build_order() {
+                        Ok(order) => order,
+                        Err(message) => {
+                            error.set(Some(message));
+                            return;
+                        }
+                    };
+                    let Some((_, authorization)) = auth.get_auth_header() else {
+                        return;
+                    };
+
+                    is_submitting.set(true);
+                    error.set(None);
+                    success.set(None);
+                    margin.set(None);
+                    spawn(async move {
+                        match place_order_server(authorization, order.clone()).await {
+                            Ok(response) => {
+                                success.set(Some(format!(
+                                    "Order {} placed: {} {} x {}",
+                                    response.order_id,
+                                    order.transaction_type.as_str(),
+                                    order.quantity,
+                                    order.trading_symbol
+                                )));
+                            }
+                            Err(e) => {
+                                tracing::error!("Order placement failed: {}", e);
+                                error.set(Some(error_message(&e)));
+                            }
+                        }
+                        is_submitting.set(false);
+                    });
+                },
+
+                div { class: "grid grid-cols-2 gap-4",
+                    div {
+                        Label { r#for: "exchange", "Exchange" }
+                        Select {
+                            field_name: "exchange",
+                            value: exchange(),
+                            options: EXCHANGES.iter().map(|e| (e.to_string(), e.to_string())).collect::<Vec<_>>(),
+                            onchange: move |event: FormEvent| {
+                                // A token only identifies an instrument within its exchange
+                                exchange.set(event.data.value());
+                                trading_symbol.set(String::new());
+                                symbol_token.set(String::new());
+                            },
+                        }
+                    }
+                    div {
+                        Label { r#for: "variety", "Variety" }
+                        Select {
+                            field_name: "variety",
+                            value: variety().as_str(),
+                            options: Variety::ALL.iter().map(|v| (v.as_str().to_string(), v.as_str().to_string())).collect::<Vec<_>>(),
+                            onchange: move |event: FormEvent| {
+                                if let Some(selected) = Variety::ALL.into_iter().find(|v| v.as_str() == event.data.value()) {
+                                    variety.set(selected);
+                                    // Keep the order type consistent with the variety
+                                    match selected {
+                                        Variety::Stoploss if !order_type().needs_trigger() => order_type.set(OrderType::StoplossLimit),
+                                        Variety::Stoploss => {}
+                                        Variety::Robo => {
+                                            order_type.set(OrderType::Limit);
+                                            product_type.set(ProductType::Bo);
+                                        }
+                                        _ if order_type().needs_trigger() => order_type.set(OrderType::Limit),
+                                        _ => {}
+                                    }
+                                }
+                            },
+                        }
+                    }
+                    div { class: "col-span-2",
+                        Label { r#for: "trading_symbol", "Symbol" }
+                        SymbolSearch {
+                            key: "{exchange()}",
+                            field_name: "trading_symbol",
+                            value: trading_symbol(),
+                            exchange: Some(exchange()),
+                            onselect: move |instrument: Instrument| {
+                                trading_symbol.set(instrument.symbol);
+                                symbol_token.set(instrument.token);
+                                exchange.set(instrument.exchange);
+                                if quantity().trim() == "1" && instrument.lot_size > 1 {
+                                    quantity.set(instrument.lot_size.to_string());
+                                }
+                            },
+                        }
+                        if !symbol_token().is_empty() {
+                            p { class: "mt-1 text-xs text-gray-500", "Token {symbol_token()}" }
+                        }
+                    }
+                    div {
+                        Label { r#for: "order_type", "Order type" }
+                        Select {
+                            field_name: "order_type",
+                            value: order_type().label(),
+                            options: OrderType::ALL.iter().map(|t| (t.label().to_string(), t.label().to_string())).collect::<Vec<_>>(),
+                            onchange: move |event: FormEvent| {
+                                if let Some(selected) = OrderType::ALL.into_iter().find(|t| t.label() == event.data.value()) {
+                                    order_type.set(selected);
+                                    if selected.needs_trigger() {
+                                        variety.set(Variety::Stoploss);
+                                    } else if variety() == Variety::Stoploss {
+                                        variety.set(Variety::Normal);
+                                    }
+                                }
+                            },
+                        }
+                    }
+                    div {
+                        Label { r#for: "product_type", "Product" }
+                        Select {
+                            field_name: "product_type",
+                            value: product_type().label(),
+                            options: ProductType::ALL.iter().map(|p| (p.label().to_string(), p.label().to_string())).collect::<Vec<_>>(),
+                            onchange: move |event: FormEvent| {
+                                if let Some(selected) = ProductType::ALL.into_iter().find(|p| p.label() == event.data.value()) {
+                                    product_type.set(selected);
+                                }
+                            },
+                        }
+                    }
+                    div {
+                        Label { r#for: "quantity", "Quantity" }
+                        Input {
+                            field_name: "quantity",
+                            input_type: "number",
+                            value: quantity(),
+                            placeholder: "Quantity",
+                            min: "1",
+                            required: true,
+                            oninput: move |event: FormEvent| quantity.set(event.data.value()),
+                        }
+                    }
+                    div {
+                        Label { r#for: "duration", "Validity" }
+                        Select {
+                            field_name: "duration",
+                            value: duration().as_str(),
+                            options: Duration::ALL.iter().map(|d| (d.as_str().to_string(), d.as_str().to_string())).collect::<Vec<_>>(),
+                            onchange: move |event: FormEvent| {
+                                if let Some(selected) = Duration::ALL.into_iter().find(|d| d.as_str() == event.data.value()) {
+                                    duration.set(selected);
+                                }
+                            },
+                        }
+                    }
+                    if order_type().needs_price() {
+                        div {
+                            Label { r#for: "price", "Price" }
+                            Input {
+                                field_name: "price",
+                                input_type: "number",
+                                value: price(),
+                                placeholder: "Limit price",
+                                min: "0",
+                                oninput: move |event: FormEvent| price.set(event.data.value()),
+                            }
+                        }
+                    }
+                    if order_type().needs_trigger() {
+                        div {
+                            Label { r#for: "trigger_price", "Trigger price" }
+                            Input {
+                                field_name: "trigger_price",
+                                input_type: "number",
+                                value: trigger_price(),
+                                placeholder: "Trigger price",
+                                min: "0",
+                                oninput: move |event: FormEvent| trigger_price.set(event.data.value()),
+                            }
+                        }
+                    }
+                    if variety() == Variety::Robo {
+                        div {
+                            Label { r#for: "square_off", "Target (points)" }
+                            Input {
+                                field_name: "square_off",
+                                input_type: "number",
+                                value: square_off(),
+                                placeholder: "Target",
+                                min: "0",
+                                oninput: move |event: FormEvent| square_off.set(event.data.value()),
+                            }
+                        }
+                        div {
+                            Label { r#for: "stop_loss", "Stop loss (points)" }
+                            Input {
+                                field_name: "stop_loss",
+                                input_type: "number",
+                                value: stop_loss(),
+                                placeholder: "Stop loss",
+                                min: "0",
+                                oninput: move |event: FormEvent| stop_loss.set(event.data.value()),
+                            }
+                        }
+                    }
+                }
+
+                FormActions {
+                    Button {
+                        class: "btn rounded-full",
+                        disabled: is_checking_margin(),
+                        onclick: check_margin,
+                        if is_checking_margin() { "CHECKING..." } else { "MARGIN" }
+                    }
+                    Button {
+                        button_type: "submit",
+                        class: "btn flex-1 rounded-full",
+                        disabled: is_submitting(),
+                        if is_submitting() {
+                            "PLACING ORDER..."
+                        } else {
+                            "{transaction_type().as_str()} {trading_symbol()}"
+                        }
+                    }
+                }
+            }
+
+            if let Some((requirement, available)) = margin() {
+                div { class: "grid grid-cols-2 gap-2 rounded-lg border border-zinc-100 p-4 text-sm dark:border-zinc-800",
+                    span { class: "text-gray-500", "Required margin" }
+                    span { class: "text-right font-medium", "{inr(requirement.total)}" }
+                    span { class: "text-gray-500", "SPAN" }
+                    span { class: "text-right", "{inr(requirement.span())}" }
+                    span { class: "text-gray-500", "Exposure" }
+                    span { class: "text-right", "{inr(requirement.exposure())}" }
+                    span { class: "text-gray-500", "Available margin" }
+                    span { class: "text-right", "{inr(available)}" }
+                }
+                if requirement.total > available {
+                    WarningMessage {
+                        message: Some(format!("Insufficient funds, short by {}", inr(requirement.total - available))),
+                    }
+                }
+            }
+
+            ErrorMessage { message: error() }
+            SuccessMessage { message: success() }
+        }
+    }
+}
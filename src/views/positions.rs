@@ -0,0 +1,236 @@
+use crate::auth::{use_auth, use_require_auth};
+use crate::components::{ErrorMessage, SuccessMessage};
+use crate::format::{inr, pnl_class, signed_inr};
+use crate::orders::place_order_server;
+use crate::smartapi::{error_message, Position, ProductType, SmartApiError};
+use dioxus::prelude::*;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum PositionView {
+    Net,
+    Day,
+}
+
+#[component]
+pub fn Positions() -> Element {
+    // Require authentication to access positions
+    let is_authenticated = use_require_auth();
+    let auth = use_auth();
+    let mut positions = use_signal(Vec::<Position>::new);
+    let mut view = use_signal(|| PositionView::Net);
+    let mut product_filter = use_signal(|| None::<ProductType>);
+    let mut error = use_signal(|| None::<String>);
+    let mut success = use_signal(|| None::<String>);
+    let mut is_loading = use_signal(|| false);
+
+    let refresh = move || {
+        spawn(async move {
+            let Some((_, authorization)) = auth.get_auth_header() else {
+                return;
+            };
+            is_loading.set(true);
+            match get_positions_server(authorization).await {
+                Ok(latest) => {
+                    positions.set(latest);
+                    error.set(None);
+                }
+                Err(e) => {
+                    tracing::error!("Failed to load positions: {}", e);
+                    error.set(Some(error_message(&e)));
+                }
+            }
+            is_loading.set(false);
+        });
+    };
+
+    use_effect(move || {
+        if auth.is_authenticated() {
+            refresh();
+        }
+    });
+
+    let square_off = move |position: Position| {
+        spawn(async move {
+            let (Some((_, authorization)), Some(order)) =
+                (auth.get_auth_header(), position.square_off_order())
+            else {
+                return;
+            };
+            match place_order_server(authorization, order).await {
+                Ok(response) => {
+                    success.set(Some(format!(
+                        "Square off order {} placed for {}",
+                        response.order_id, position.trading_symbol
+                    )));
+                    error.set(None);
+                    refresh();
+                }
+                Err(e) => {
+                    tracing::error!("Square off failed: {}", e);
+                    error.set(Some(error_message(&e)));
+                }
+            }
+        });
+    };
+
+    if !is_authenticated {
+        return rsx! {
+            div { class: "flex items-center justify-center h-full",
+                div { class: "text-lg font-medium text-gray-600",
+                    "Redirecting to login..."
+                }
+            }
+        };
+    }
+
+    let visible: Vec<Position> = positions()
+        .into_iter()
+        .filter(|position| view() == PositionView::Net || position.is_traded_today())
+        .filter(|position| product_filter().is_none_or(|product| position.product_type == product))
+        .collect();
+    let realised: f64 = visible.iter().map(|position| position.realised).sum();
+    let unrealised: f64 = visible.iter().map(|position| position.unrealised).sum();
+
+    rsx! {
+        div { class: "p-4",
+            div { class: "mb-4 flex items-center justify-between",
+                h1 { class: "text-2xl font-bold text-gray-900", "Positions" }
+                button {
+                    class: "btn btn-sm btn-ghost",
+                    disabled: is_loading(),
+                    onclick: move |_| refresh(),
+                    if is_loading() { "Refreshing..." } else { "Refresh" }
+                }
+            }
+
+            div { class: "mb-3 flex flex-wrap items-center gap-2",
+                div { class: "join",
+                    for (label, option) in [("Net", PositionView::Net), ("Day", PositionView::Day)] {
+                        button {
+                            class: if view() == option { "btn btn-sm join-item btn-active" } else { "btn btn-sm join-item" },
+                            onclick: move |_| view.set(option),
+                            "{label}"
+                        }
+                    }
+                }
+                div { class: "join",
+                    for option in [None, Some(ProductType::Intraday), Some(ProductType::Carryforward), Some(ProductType::Delivery)] {
+                        button {
+                            class: if product_filter() == option { "btn btn-sm join-item btn-active" } else { "btn btn-sm join-item" },
+                            onclick: move |_| product_filter.set(option),
+                            {option.map_or("All", ProductType::label)}
+                        }
+                    }
+                }
+            }
+
+            ErrorMessage { message: error() }
+            SuccessMessage { message: success() }
+
+            div { class: "my-4 grid grid-cols-2 gap-2 rounded-lg border border-zinc-100 p-4 text-sm dark:border-zinc-800",
+                span { class: "text-gray-500", "Realised P&L" }
+                span { class: "text-right {pnl_class(realised)}", "{signed_inr(realised)}" }
+                span { class: "text-gray-500", "Unrealised P&L" }
+                span { class: "text-right {pnl_class(unrealised)}", "{signed_inr(unrealised)}" }
+            }
+
+            if visible.is_empty() && !is_loading() {
+                p { class: "text-sm text-gray-500", "No open positions" }
+            }
+
+            div { class: "overflow-x-auto",
+                table { class: "table table-sm",
+                    thead {
+                        tr {
+                            th { "Instrument" }
+                            th { class: "text-right", "Qty" }
+                            th { class: "text-right", "Avg" }
+                            th { class: "text-right", "LTP" }
+                            th { class: "text-right", "P&L" }
+                            th {}
+                        }
+                    }
+                    tbody {
+                        for position in visible {
+                            PositionRow {
+                                key: "{position.exchange}:{position.trading_symbol}:{position.product_type.label()}",
+                                position: position.clone(),
+                                view: view(),
+                                on_square_off: square_off,
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[component]
+fn PositionRow(
+    position: Position,
+    view: PositionView,
+    on_square_off: EventHandler<Position>,
+) -> Element {
+    let mut confirming = use_signal(|| false);
+    let quantity = match view {
+        PositionView::Net => position.net_quantity,
+        PositionView::Day => position.day_quantity(),
+    };
+    let pnl = position.realised + position.unrealised;
+    let is_open = position.net_quantity != 0;
+
+    rsx! {
+        tr {
+            td {
+                div { class: "font-medium", "{position.trading_symbol}" }
+                div { class: "text-xs text-gray-500", "{position.exchange} · {position.product_type.label()}" }
+            }
+            td { class: "text-right", "{quantity}" }
+            td { class: "text-right", "{inr(position.net_average_price)}" }
+            td { class: "text-right", "{inr(position.ltp)}" }
+            td { class: "text-right {pnl_class(pnl)}",
+                div { "{signed_inr(pnl)}" }
+                div { class: "text-xs text-gray-500",
+                    "R {signed_inr(position.realised)} · U {signed_inr(position.unrealised)}"
+                }
+            }
+            td { class: "text-right",
+                if is_open {
+                    if confirming() {
+                        button {
+                            class: "btn btn-xs btn-error",
+                            onclick: move |_| {
+                                confirming.set(false);
+                                on_square_off.call(position.clone());
+                            },
+                            "Confirm"
+                        }
+                        button {
+                            class: "btn btn-xs btn-ghost",
+                            onclick: move |_| confirming.set(false),
+                            "Cancel"
+                        }
+                    } else {
+                        button {
+                            class: "btn btn-xs btn-outline",
+                            onclick: move |_| confirming.set(true),
+                            "Square off"
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[server(GetPositionsServer)]
+async fn get_positions_server(
+    authorization: String,
+) -> Result<Vec<Position>, ServerFnError<SmartApiError>> {
+    use crate::smartapi::{bearer_token, SmartApiClient};
+
+    Ok(SmartApiClient::shared()?
+        .get_positions(bearer_token(&authorization))
+        .await?)
+}
@@ -0,0 +1,233 @@
+use crate::auth::{use_auth, use_require_auth};
+use crate::components::ErrorMessage;
+use crate::format::inr;
+use crate::smartapi::{error_message, Order, OrderFills, SmartApiError, Trade, TransactionType};
+use dioxus::prelude::*;
+
+#[component]
+pub fn Trades() -> Element {
+    // Require authentication to access the trade book
+    let is_authenticated = use_require_auth();
+    let auth = use_auth();
+    let mut trades = use_signal(Vec::<Trade>::new);
+    let mut error = use_signal(|| None::<String>);
+    let mut is_loading = use_signal(|| false);
+
+    let refresh = move || {
+        spawn(async move {
+            let Some((_, authorization)) = auth.get_auth_header() else {
+                return;
+            };
+            is_loading.set(true);
+            match get_trade_book_server(authorization).await {
+                Ok(latest) => {
+                    trades.set(latest);
+                    error.set(None);
+                }
+                Err(e) => {
+                    tracing::error!("Failed to load trade book: {}", e);
+                    error.set(Some(error_message(&e)));
+                }
+            }
+            is_loading.set(false);
+        });
+    };
+
+    use_effect(move || {
+        if auth.is_authenticated() {
+            refresh();
+        }
+    });
+
+    if !is_authenticated {
+        return rsx! {
+            div { class: "flex items-center justify-center h-full",
+                div { class: "text-lg font-medium text-gray-600",
+                    "Redirecting to login..."
+                }
+            }
+        };
+    }
+
+    let groups = OrderFills::group(trades());
+
+    rsx! {
+        div { class: "p-4",
+            div { class: "mb-4 flex items-center justify-between",
+                h1 { class: "text-2xl font-bold text-gray-900", "Trades" }
+                button {
+                    class: "btn btn-sm btn-ghost",
+                    disabled: is_loading(),
+                    onclick: move |_| refresh(),
+                    if is_loading() { "Refreshing..." } else { "Refresh" }
+                }
+            }
+
+            ErrorMessage { message: error() }
+
+            if groups.is_empty() && !is_loading() {
+                p { class: "text-sm text-gray-500", "No trades today" }
+            }
+
+            div { class: "overflow-x-auto",
+                table { class: "table table-sm",
+                    thead {
+                        tr {
+                            th { "Instrument" }
+                            th { class: "text-right", "Qty" }
+                            th { class: "text-right", "Avg price" }
+                            th { class: "text-right", "Value" }
+                            th {}
+                        }
+                    }
+                    tbody {
+                        for fills in groups {
+                            OrderFillsRows { key: "{fills.order_id}", fills: fills.clone() }
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[component]
+fn OrderFillsRows(fills: OrderFills) -> Element {
+    let auth = use_auth();
+    let mut expanded = use_signal(|| false);
+    let mut details = use_signal(|| None::<Order>);
+    let mut error = use_signal(|| None::<String>);
+
+    let order_id = fills.order_id.clone();
+    let toggle = move |_| {
+        expanded.set(!expanded());
+        if !expanded() || details.peek().is_some() {
+            return;
+        }
+        let order_id = order_id.clone();
+        spawn(async move {
+            let Some((_, authorization)) = auth.get_auth_header() else {
+                return;
+            };
+            match get_order_details_server(authorization, order_id).await {
+                Ok(order) => {
+                    details.set(Some(order));
+                    error.set(None);
+                }
+                Err(e) => {
+                    tracing::error!("Failed to load order details: {}", e);
+                    error.set(Some(error_message(&e)));
+                }
+            }
+        });
+    };
+
+    let first = fills.first();
+    let side_class = match first.transaction_type {
+        TransactionType::Buy => "text-green-600",
+        TransactionType::Sell => "text-red-600",
+    };
+
+    rsx! {
+        tr {
+            td {
+                div { class: "font-medium",
+                    span { class: side_class, "{first.transaction_type.as_str()} " }
+                    "{first.trading_symbol}"
+                }
+                div { class: "text-xs text-gray-500",
+                    "{first.exchange} · {first.product_type.label()} · Order {fills.order_id}"
+                }
+            }
+            td { class: "text-right", "{fills.quantity()}" }
+            td { class: "text-right", "{inr(fills.average_price())}" }
+            td { class: "text-right", "{inr(fills.value())}" }
+            td { class: "text-right",
+                button {
+                    class: "btn btn-xs btn-ghost",
+                    onclick: toggle,
+                    if expanded() { "Hide" } else { "{fills.trades.len()} fills" }
+                }
+            }
+        }
+        if expanded() {
+            tr {
+                td { colspan: "5", class: "bg-zinc-50 dark:bg-zinc-900",
+                    ErrorMessage { message: error() }
+                    if let Some(order) = details() {
+                        div { class: "mb-2 grid grid-cols-2 gap-1 text-xs sm:grid-cols-4",
+                            span { class: "text-gray-500", "Exchange order id" }
+                            span {
+                                if order.exchange_order_id.is_empty() { "—" } else { "{order.exchange_order_id}" }
+                            }
+                            span { class: "text-gray-500", "Status" }
+                            span { "{order.status}" }
+                            span { class: "text-gray-500", "Order type" }
+                            span { "{order.order_type.label()} · {order.variety.as_str()}" }
+                            span { class: "text-gray-500", "Exchange time" }
+                            span { "{order.exchange_update_time}" }
+                        }
+                    }
+                    table { class: "table table-xs",
+                        thead {
+                            tr {
+                                th { "Fill id" }
+                                th { "Time" }
+                                th { class: "text-right", "Qty" }
+                                th { class: "text-right", "Price" }
+                            }
+                        }
+                        tbody {
+                            for trade in fills.trades.iter() {
+                                tr { key: "{trade.fill_id}",
+                                    td { "{trade.fill_id}" }
+                                    td { "{trade.fill_time}" }
+                                    td { class: "text-right", "{trade.fill_size}" }
+                                    td { class: "text-right", "{inr(trade.fill_price)}" }
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[server(GetTradeBookServer)]
+async fn get_trade_book_server(
+    authorization: String,
+) -> Result<Vec<Trade>, ServerFnError<SmartApiError>> {
+    use crate::smartapi::{bearer_token, SmartApiClient};
+
+    Ok(SmartApiClient::shared()?
+        .get_trade_book(bearer_token(&authorization))
+        .await?)
+}
+
+#[server(GetOrderDetailsServer)]
+async fn get_order_details_server(
+    authorization: String,
+    order_id: String,
+) -> Result<Order, ServerFnError<SmartApiError>> {
+    use crate::smartapi::{bearer_token, SmartApiClient};
+
+    // The trade book only carries the order id, the details endpoint wants the unique one
+    let client = SmartApiClient::shared()?;
+    let jwt_token = bearer_token(&authorization);
+    let unique_order_id = client
+        .get_order_book(jwt_token)
+        .await?
+        .into_iter()
+        .find(|order| order.order_id == order_id)
+        .map(|order| order.unique_order_id)
+        .filter(|unique_order_id| !unique_order_id.is_empty())
+        .ok_or_else(|| SmartApiError::Api {
+            code: "ORDER_NOT_FOUND".to_string(),
+            message: format!("Order {} is not in today's order book", order_id),
+        })?;
+
+    Ok(client
+        .get_order_details(jwt_token, &unique_order_id)
+        .await?)
+}
@@ -0,0 +1,103 @@
+//! Named lists of instruments the trader keeps an eye on.
+
+use crate::instruments::Instrument;
+use crate::smartapi::MAX_QUOTE_TOKENS;
+use serde::{Deserialize, Serialize};
+
+/// A list is quoted in one request, so it can't outgrow Angel's quote limit.
+pub const MAX_WATCHLIST_ITEMS: usize = MAX_QUOTE_TOKENS;
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct WatchlistItem {
+    pub exchange: String,
+    pub token: String,
+    pub symbol: String,
+}
+
+impl From<Instrument> for WatchlistItem {
+    fn from(instrument: Instrument) -> Self {
+        Self {
+            exchange: instrument.exchange,
+            token: instrument.token,
+            symbol: instrument.symbol,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct Watchlist {
+    pub name: String,
+    pub items: Vec<WatchlistItem>,
+}
+
+impl Watchlist {
+    pub fn new(name: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            items: Vec::new(),
+        }
+    }
+
+    pub fn add(&mut self, item: WatchlistItem) -> Result<(), String> {
+        if self
+            .items
+            .iter()
+            .any(|existing| existing.exchange == item.exchange && existing.token == item.token)
+        {
+            return Err(format!("{} is already in {}", item.symbol, self.name));
+        }
+        if self.items.len() >= MAX_WATCHLIST_ITEMS {
+            return Err(format!(
+                "A watchlist holds at most {} instruments",
+                MAX_WATCHLIST_ITEMS
+            ));
+        }
+        self.items.push(item);
+        Ok(())
+    }
+
+    pub fn remove(&mut self, index: usize) {
+        if index < self.items.len() {
+            self.items.remove(index);
+        }
+    }
+
+    /// Move an item one place up (`-1`) or down (`1`), ignoring moves past either end.
+    pub fn move_item(&mut self, index: usize, offset: isize) {
+        if let Some(target) = index.checked_add_signed(offset) {
+            if index < self.items.len() && target < self.items.len() {
+                self.items.swap(index, target);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn item(symbol: &str, token: &str) -> WatchlistItem {
+        WatchlistItem {
+            exchange: "NSE".to_string(),
+            token: token.to_string(),
+            symbol: symbol.to_string(),
+        }
+    }
+
+    #[test]
+    fn adds_reorders_and_removes_items() {
+        let mut list = Watchlist::new("Banks");
+        list.add(item("SBIN-EQ", "3045")).unwrap();
+        list.add(item("HDFCBANK-EQ", "1333")).unwrap();
+        assert!(list.add(item("SBIN-EQ", "3045")).is_err());
+
+        list.move_item(1, -1);
+        assert_eq!(list.items[0].symbol, "HDFCBANK-EQ");
+        list.move_item(0, -1);
+        list.move_item(1, 1);
+        assert_eq!(list.items[1].symbol, "SBIN-EQ");
+
+        list.remove(0);
+        assert_eq!(list.items, vec![item("SBIN-EQ", "3045")]);
+    }
+}